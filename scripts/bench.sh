@@ -0,0 +1,4 @@
+#!/usr/bin/env bash
+set -e
+
+cargo +nightly bench --features bench
@@ -6,9 +6,9 @@ target_dir=wasm_generated
 
 yarn
 
-cargo +nightly build --target wasm32-unknown-unknown
+cargo build --target wasm32-unknown-unknown --features wasm
 rm -rf $target_dir
 mkdir $target_dir
-wasm-bindgen target/wasm32-unknown-unknown/debug/$application_name.wasm --out-dir ./$target_dir
+wasm-bindgen target/wasm32-unknown-unknown/debug/$application_name.wasm --target web --out-dir ./$target_dir
 
 yarn webpack
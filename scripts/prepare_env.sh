@@ -7,9 +7,11 @@ if [[ $UID != 0 ]]; then
     exit 1
 fi
 
-curl https://sh.rustup.rs -sSf | sh -s -- --default-toolchain nightly -y
-rustup target add wasm32-unknown-unknown --toolchain nightly
-cargo install wasm-bindgen-cli
+curl https://sh.rustup.rs -sSf | sh -s -- -y
+rustup target add wasm32-unknown-unknown
+rustup toolchain install nightly
+# Has to match the version of the wasm-bindgen crate in Cargo.toml
+cargo install wasm-bindgen-cli --version 0.2.129
 
 curl -sS https://dl.yarnpkg.com/debian/pubkey.gpg | sudo apt-key add -
 echo "deb https://dl.yarnpkg.com/debian/ stable main" | sudo tee /etc/apt/sources.list.d/yarn.list
@@ -0,0 +1,4 @@
+#!/usr/bin/env bash
+set -e
+
+cargo run --release --bin soup_search -- "$@"
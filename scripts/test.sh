@@ -1,4 +1,4 @@
 #!/usr/bin/env bash
 set -e
 
-cargo +nightly test
+cargo test
@@ -0,0 +1,286 @@
+use crate::generation_calculator::{change_at, Change, GenerationCalculator, NEIGHBOUR_OFFSETS};
+use crate::grid::{Grid, Position};
+use crate::rule::Rule;
+use crate::topology::Topology;
+use std::cell::{Cell, RefCell};
+
+const TILE_SIZE: u32 = 8;
+
+struct ActiveTiles {
+    width: u32,
+    height: u32,
+    topology: Topology,
+    columns: u32,
+    rows: u32,
+    active: Vec<bool>,
+}
+
+impl ActiveTiles {
+    fn new(grid: &dyn Grid, is_active: bool) -> Self {
+        let columns = grid.width().div_ceil(TILE_SIZE);
+        let rows = grid.height().div_ceil(TILE_SIZE);
+        ActiveTiles {
+            width: grid.width(),
+            height: grid.height(),
+            topology: grid.topology(),
+            columns,
+            rows,
+            active: vec![is_active; (columns * rows) as usize],
+        }
+    }
+
+    fn fits(&self, grid: &dyn Grid) -> bool {
+        self.width == grid.width()
+            && self.height == grid.height()
+            && self.topology == grid.topology()
+    }
+
+    // A changed cell can only affect itself and its neighbours in the next generation
+    fn mark_around(&mut self, position: Position) {
+        self.mark(position);
+        for &(delta_x, delta_y) in NEIGHBOUR_OFFSETS.iter() {
+            let neighbour = self.topology.resolve(
+                self.width,
+                self.height,
+                i64::from(position.x) + delta_x,
+                i64::from(position.y) + delta_y,
+            );
+            if let Some(neighbour) = neighbour {
+                self.mark(neighbour);
+            }
+        }
+    }
+
+    fn mark(&mut self, position: Position) {
+        if position.x < self.width && position.y < self.height {
+            let index = (position.y / TILE_SIZE) * self.columns + position.x / TILE_SIZE;
+            self.active[index as usize] = true;
+        }
+    }
+
+    fn clear(&mut self) {
+        for is_active in &mut self.active {
+            *is_active = false;
+        }
+    }
+
+    fn is_active(&self, column: u32, row: u32) -> bool {
+        self.active[(row * self.columns + column) as usize]
+    }
+
+    fn scan(&self, grid: &dyn Grid, rule: &Rule) -> Vec<Change> {
+        let mut changes = Vec::new();
+        for row in 0..self.rows {
+            let first_y = row * TILE_SIZE;
+            let last_y = (first_y + TILE_SIZE).min(self.height);
+            for y in first_y..last_y {
+                for column in (0..self.columns).filter(|&column| self.is_active(column, row)) {
+                    let first_x = column * TILE_SIZE;
+                    let last_x = (first_x + TILE_SIZE).min(self.width);
+                    for x in first_x..last_x {
+                        if let Some(change) = change_at(grid, rule, Position { x, y }) {
+                            changes.push(change);
+                        }
+                    }
+                }
+            }
+        }
+        changes
+    }
+}
+
+// Only re-evaluates the tiles touched by the changes accepted since the last generation,
+// so it relies on being told about every change made to the grid. The tiles of a generation
+// stay active until its changes are accepted, so it may be computed again until then.
+pub struct ActiveRegionGenerationCalculator {
+    rule: Rule,
+    active_tiles: RefCell<Option<ActiveTiles>>,
+    awaits_changes: Cell<bool>,
+}
+
+impl ActiveRegionGenerationCalculator {
+    pub fn new(rule: Rule) -> Self {
+        ActiveRegionGenerationCalculator {
+            rule,
+            active_tiles: RefCell::new(None),
+            awaits_changes: Cell::new(false),
+        }
+    }
+}
+
+impl GenerationCalculator for ActiveRegionGenerationCalculator {
+    fn next_generation(&self, grid: &dyn Grid) -> Vec<Change> {
+        let mut active_tiles = self.active_tiles.borrow_mut();
+        let mut tiles = match active_tiles.take() {
+            Some(tiles) if tiles.fits(grid) => tiles,
+            _ => ActiveTiles::new(grid, true),
+        };
+        let changes = tiles.scan(grid, &self.rule);
+        // A generation without changes leaves nothing to accept
+        if changes.is_empty() {
+            tiles.clear();
+        }
+        self.awaits_changes.set(!changes.is_empty());
+        *active_tiles = Some(tiles);
+        changes
+    }
+
+    fn accept_changes(&self, changes: &[Change]) {
+        if let Some(ref mut tiles) = *self.active_tiles.borrow_mut() {
+            if self.awaits_changes.replace(false) {
+                tiles.clear();
+            }
+            for change in changes {
+                tiles.mark_around(change.position);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generation_calculator::GenerationCalculatorImpl;
+    use crate::grid::{apply_changes, GridImpl, ALIVE};
+    use crate::test_util::fill_soup;
+    use crate::topology::TwistedEdges;
+    use std::cell::Cell;
+
+    struct CountingGrid {
+        grid: GridImpl,
+        lookups: Cell<u32>,
+    }
+
+    impl Grid for CountingGrid {
+        fn width(&self) -> u32 {
+            self.grid.width()
+        }
+        fn height(&self) -> u32 {
+            self.grid.height()
+        }
+        fn topology(&self) -> Topology {
+            self.grid.topology()
+        }
+        fn is_alive_at(&self, position: Position) -> bool {
+            self.lookups.set(self.lookups.get() + 1);
+            self.grid.is_alive_at(position)
+        }
+        fn set_alive_at(&mut self, position: Position) {
+            self.grid.set_alive_at(position);
+        }
+        fn set_dead_at(&mut self, position: Position) {
+            self.grid.set_dead_at(position);
+        }
+    }
+
+    fn assert_matches_full_scan(width: u32, height: u32, topology: Topology) {
+        let mut grid = GridImpl::with_topology(width, height, topology);
+        fill_soup(&mut grid, 0x853c_49e6_748f_ea9b);
+        let full_scan = GenerationCalculatorImpl::new(Rule::conway());
+        let active_region = ActiveRegionGenerationCalculator::new(Rule::conway());
+
+        for generation in 0..60 {
+            let expected = full_scan.next_generation(&grid);
+            let changes = active_region.next_generation(&grid);
+            assert_eq!(expected, changes, "generation {}", generation);
+            apply_changes(&mut grid, &changes);
+            active_region.accept_changes(&changes);
+
+            if generation % 10 == 5 {
+                let toggle = [Change {
+                    position: Position {
+                        x: generation % width,
+                        y: (generation * 7) % height,
+                    },
+                    state: ALIVE,
+                }];
+                apply_changes(&mut grid, &toggle);
+                active_region.accept_changes(&toggle);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_full_scan_on_bounded_grid() {
+        assert_matches_full_scan(45, 30, Topology::Bounded);
+    }
+
+    #[test]
+    fn matches_full_scan_on_torus() {
+        assert_matches_full_scan(40, 27, Topology::Torus);
+    }
+
+    #[test]
+    fn matches_full_scan_on_klein_bottle() {
+        assert_matches_full_scan(33, 40, Topology::KleinBottle(TwistedEdges::TopAndBottom));
+    }
+
+    #[test]
+    fn skips_stable_areas() {
+        let mut grid = CountingGrid {
+            grid: GridImpl::new(64, 64),
+            lookups: Cell::new(0),
+        };
+        for &(x, y) in &[(1, 1), (2, 1), (1, 2), (2, 2), (40, 40), (41, 40), (42, 40)] {
+            grid.set_alive_at(Position { x, y });
+        }
+        let generation_calculator = ActiveRegionGenerationCalculator::new(Rule::conway());
+
+        let changes = generation_calculator.next_generation(&grid);
+        apply_changes(&mut grid, &changes);
+        generation_calculator.accept_changes(&changes);
+        let full_scan_lookups = grid.lookups.replace(0);
+
+        let changes = generation_calculator.next_generation(&grid);
+        assert_eq!(4, changes.len());
+        assert!(grid.lookups.get() * 4 < full_scan_lookups);
+    }
+
+    #[test]
+    fn computes_same_generation_until_changes_are_accepted() {
+        let mut grid = GridImpl::new(16, 16);
+        for x in 1..4 {
+            grid.set_alive_at(Position { x, y: 2 });
+        }
+        let generation_calculator = ActiveRegionGenerationCalculator::new(Rule::conway());
+        let changes = generation_calculator.next_generation(&grid);
+        assert_eq!(changes, generation_calculator.next_generation(&grid));
+
+        apply_changes(&mut grid, &changes);
+        generation_calculator.accept_changes(&changes);
+
+        let expected = GenerationCalculatorImpl::new(Rule::conway()).next_generation(&grid);
+        assert_eq!(expected, generation_calculator.next_generation(&grid));
+    }
+
+    #[test]
+    fn stable_grid_is_not_rescanned() {
+        let mut grid = CountingGrid {
+            grid: GridImpl::new(16, 16),
+            lookups: Cell::new(0),
+        };
+        for &(x, y) in &[(1, 1), (2, 1), (1, 2), (2, 2)] {
+            grid.set_alive_at(Position { x, y });
+        }
+        let generation_calculator = ActiveRegionGenerationCalculator::new(Rule::conway());
+        assert!(generation_calculator.next_generation(&grid).is_empty());
+        grid.lookups.set(0);
+
+        assert!(generation_calculator.next_generation(&grid).is_empty());
+        assert_eq!(0, grid.lookups.get());
+    }
+
+    #[test]
+    fn rescans_everything_when_grid_size_changes() {
+        let generation_calculator = ActiveRegionGenerationCalculator::new(Rule::conway());
+        generation_calculator.next_generation(&GridImpl::new(10, 10));
+        let mut grid = GridImpl::new(20, 20);
+        for x in 14..17 {
+            grid.set_alive_at(Position { x, y: 15 });
+        }
+
+        let changes = generation_calculator.next_generation(&grid);
+
+        assert_eq!(4, changes.len());
+    }
+}
@@ -0,0 +1,146 @@
+extern crate game_of_life;
+
+use game_of_life::soup::is_valid_density;
+use game_of_life::soup_search::{search, SearchSettings};
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::process;
+use std::str::FromStr;
+
+const USAGE: &str = "Usage: soup_search [options]
+
+Runs random soups until they settle and writes a report of the results.
+
+Options:
+    --soups <count>            number of soups to run [default: 1000]
+    --seed <seed>              seed of the first soup, the others count up [default: 1]
+    --grid <spec>              board, e.g. P64,64 or T128,128 [default: P64,64]
+    --soup-size <cells>        side length of the soup in the middle of the board [default: 16]
+    --density <fraction>       chance of a soup cell being alive [default: 0.5]
+    --symmetry <symmetry>      C1, C2, C4, D2, D4 or D8 [default: C1]
+    --rule <rule>              rule in B/S notation [default: B3/S23]
+    --calculator <name>        naive, active or bit [default: bit]
+    --max-generations <count>  give up on soups that take longer to settle [default: 10000]
+    --methuselah <count>       lifespan from which a soup is a methuselah [default: 1000]
+    --json <path>              where to write the JSON report [default: soup_search.json]
+    --csv <path>               where to write the CSV report [default: soup_search.csv]
+    --help                     print this message
+";
+
+// Soups between two progress messages
+const PROGRESS_INTERVAL: u64 = 100;
+
+struct Arguments {
+    settings: SearchSettings,
+    soups: u32,
+    seed: u64,
+    json_path: String,
+    csv_path: String,
+}
+
+fn main() {
+    let arguments = parse_arguments(env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    let report = search(
+        &arguments.settings,
+        arguments.seed,
+        arguments.soups,
+        |result| {
+            let index = result.seed - arguments.seed + 1;
+            if index.is_multiple_of(PROGRESS_INTERVAL) {
+                eprintln!("{} of {} soups done", index, arguments.soups);
+            }
+        },
+    );
+
+    write(&arguments.json_path, &report.to_json());
+    write(&arguments.csv_path, &report.to_csv());
+    println!(
+        "Ran {} soups, {} methuselahs, {} unusual objects",
+        report.results.len(),
+        report.methuselahs().len(),
+        report.unusual_objects().len()
+    );
+    if let Some(result) = report.max_population() {
+        println!(
+            "Largest population: {} cells in soup {}",
+            result.max_population, result.seed
+        );
+    }
+}
+
+fn parse_arguments<I>(mut arguments: I) -> Result<Arguments, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut parsed = Arguments {
+        settings: SearchSettings::default(),
+        soups: 1000,
+        seed: 1,
+        json_path: "soup_search.json".to_string(),
+        csv_path: "soup_search.csv".to_string(),
+    };
+    while let Some(option) = arguments.next() {
+        if option == "--help" {
+            print!("{}", USAGE);
+            process::exit(0);
+        }
+        let value = arguments
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", option))?;
+        let settings = &mut parsed.settings;
+        match option.as_str() {
+            "--soups" => parsed.soups = parse_value(&option, &value)?,
+            "--seed" => parsed.seed = parse_value(&option, &value)?,
+            "--grid" => settings.grid_spec = parse_value(&option, &value)?,
+            "--soup-size" => settings.soup_size = parse_value(&option, &value)?,
+            "--density" => {
+                settings.density = parse_value(&option, &value)?;
+                if !is_valid_density(settings.density) {
+                    return Err(format!(
+                        "Invalid value '{}' for {}: must be between 0 and 1",
+                        value, option
+                    ));
+                }
+            }
+            "--symmetry" => settings.symmetry = parse_value(&option, &value)?,
+            "--rule" => settings.rule = parse_value(&option, &value)?,
+            "--calculator" => settings.calculator = parse_value(&option, &value)?,
+            "--max-generations" => settings.max_generations = parse_value(&option, &value)?,
+            "--methuselah" => settings.methuselah_lifespan = parse_value(&option, &value)?,
+            "--json" => parsed.json_path = value,
+            "--csv" => parsed.csv_path = value,
+            _ => return Err(format!("Unknown option {}", option)),
+        }
+    }
+    // The soups count their seeds up from the first one
+    let last_soup = u64::from(parsed.soups.saturating_sub(1));
+    if parsed.seed.checked_add(last_soup).is_none() {
+        return Err(format!(
+            "Seeds of {} soups starting at {} don't fit into 64 bits",
+            parsed.soups, parsed.seed
+        ));
+    }
+    Ok(parsed)
+}
+
+fn parse_value<T>(option: &str, value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|error| format!("Invalid value '{}' for {}: {}", value, option, error))
+}
+
+fn write(path: &str, contents: &str) {
+    fs::write(path, contents).unwrap_or_else(|error| {
+        eprintln!("Failed to write {}: {}", path, error);
+        process::exit(1);
+    });
+}
@@ -0,0 +1,151 @@
+extern crate game_of_life;
+extern crate termion;
+
+use game_of_life::bit_grid::{BitGenerationCalculator, BitGrid};
+use game_of_life::generation_calculator::GenerationCalculator;
+use game_of_life::grid::Grid;
+use game_of_life::interactive_game::{GenerationOverflow, InteractiveGame, InteractiveGameImpl};
+use game_of_life::rule::Rule;
+use game_of_life::terminal::controls::{command_for_key, Command, HELP};
+use game_of_life::terminal::presenter::{TerminalPresenter, TerminalView};
+use game_of_life::terminal::screen::Screen;
+use game_of_life::terminal::view::TerminalViewImpl;
+use game_of_life::topology::{GridSpec, Topology};
+use std::cell::RefCell;
+use std::env;
+use std::io::{self, Write};
+use std::process;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::{clear, cursor};
+
+const MIN_DELAY_MS: u64 = 10;
+const MAX_DELAY_MS: u64 = 2000;
+const INITIAL_DELAY_MS: u64 = 100;
+const POLL_INTERVAL_MS: u64 = 5;
+
+fn main() {
+    let (columns, lines) = termion::terminal_size().unwrap_or((80, 24));
+    let (columns, lines) = (
+        u32::from(columns),
+        u32::from(lines).saturating_sub(1).max(1),
+    );
+    // Without a grid spec the board fills the terminal
+    let grid_spec = match env::args().nth(1) {
+        Some(spec) => spec.parse().unwrap_or_else(|error| {
+            eprintln!("Invalid grid spec '{}': {}", spec, error);
+            process::exit(2);
+        }),
+        None => GridSpec {
+            topology: Topology::Bounded,
+            width: columns,
+            height: lines * 2,
+        },
+    };
+
+    let raw_terminal = io::stdout().into_raw_mode().unwrap_or_else(|error| {
+        eprintln!("Failed to switch the terminal to raw mode: {}", error);
+        process::exit(1);
+    });
+    print!("{}{}", clear::All, cursor::Hide);
+
+    let screen = Rc::new(RefCell::new(Screen::new(
+        grid_spec.width,
+        grid_spec.height,
+        columns,
+        lines,
+    )));
+    let presenter = TerminalPresenter::new(
+        Box::new(TerminalViewImpl::new(io::stdout())),
+        screen.clone(),
+    );
+    let (grid, generation_calculator) = game_parts(&grid_spec);
+    let mut game = InteractiveGameImpl::new(grid, generation_calculator, Box::new(presenter));
+    run(&mut game, &screen);
+
+    print!("{}{}{}", clear::All, cursor::Goto(1, 1), cursor::Show);
+    let _ = io::stdout().flush();
+    drop(raw_terminal);
+}
+
+fn game_parts(grid_spec: &GridSpec) -> (Box<dyn Grid>, Box<dyn GenerationCalculator>) {
+    let (width, height, topology) = (grid_spec.width, grid_spec.height, grid_spec.topology);
+    let grid = BitGrid::with_topology(width, height, topology);
+    let generation_calculator = BitGenerationCalculator::new(&grid, Rule::default());
+    (Box::new(grid), Box::new(generation_calculator))
+}
+
+fn run(game: &mut InteractiveGameImpl, screen: &Rc<RefCell<Screen>>) {
+    let mut view = TerminalViewImpl::new(io::stdout());
+    let mut keys = termion::async_stdin().keys();
+    let mut is_running = false;
+    let mut delay = INITIAL_DELAY_MS;
+    let mut last_generation = Instant::now();
+    let mut overflow = None;
+    loop {
+        let mut needs_redraw = false;
+        while let Some(Ok(key)) = keys.next() {
+            needs_redraw = true;
+            match command_for_key(key) {
+                Some(Command::Quit) => return,
+                Some(Command::ToggleRunning) => is_running = !is_running,
+                Some(Command::Step) => overflow = game.next_generation().err(),
+                Some(Command::StepBack) => game.step_back(1),
+                Some(Command::SpeedUp) => delay = (delay / 2).max(MIN_DELAY_MS),
+                Some(Command::SlowDown) => delay = (delay * 2).min(MAX_DELAY_MS),
+                Some(Command::MoveCursor(dx, dy)) => screen.borrow_mut().move_cursor(dx, dy),
+                Some(Command::Scroll(dx, dy)) => screen.borrow_mut().scroll(dx, dy),
+                Some(Command::ToggleCell) => {
+                    let cursor = screen.borrow().cursor();
+                    game.toggle_cell(&cursor);
+                }
+                Some(Command::Undo) => game.undo(),
+                Some(Command::Redo) => game.redo(),
+                Some(Command::Clear) => game.clear(),
+                None => {}
+            }
+        }
+        if is_running && last_generation.elapsed() >= Duration::from_millis(delay) {
+            last_generation = Instant::now();
+            overflow = game.next_generation().err();
+            needs_redraw = true;
+        }
+        if overflow.is_some() {
+            is_running = false;
+        }
+        if needs_redraw {
+            let status = status(game, is_running, delay, overflow);
+            screen.borrow_mut().set_status(&status);
+            view.draw(&screen.borrow().render());
+        }
+        thread::sleep(Duration::from_millis(POLL_INTERVAL_MS));
+    }
+}
+
+fn status(
+    game: &InteractiveGameImpl,
+    is_running: bool,
+    delay: u64,
+    overflow: Option<GenerationOverflow>,
+) -> String {
+    let state = match overflow {
+        Some(overflow) => overflow.to_string(),
+        None if is_running => "running".to_string(),
+        None => "paused".to_string(),
+    };
+    let stabilisation = game
+        .stabilisation()
+        .map(|stabilisation| format!(" | {}", stabilisation))
+        .unwrap_or_default();
+    format!(
+        "gen {} | {} | {}ms{} | {}",
+        game.timeline_position().current,
+        state,
+        delay,
+        stabilisation,
+        HELP
+    )
+}
@@ -0,0 +1,478 @@
+use crate::generation_calculator::{Change, GenerationCalculator};
+use crate::grid::{Grid, Position, ALIVE, DEAD};
+use crate::rule::Rule;
+use crate::topology::Topology;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const WORD_BITS: u32 = 64;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct BitBoard {
+    words: Vec<u64>,
+    width: u32,
+    height: u32,
+    words_per_row: usize,
+    topology: Topology,
+}
+
+// The rows around the one being computed, kept by the calculator between generations
+// so that computing one does not allocate them for every row
+#[derive(Debug, Default)]
+pub struct RowBuffers {
+    shifted: Vec<u64>,
+    beyond_edge: Vec<u64>,
+}
+
+impl BitBoard {
+    pub fn new(width: u32, height: u32, topology: Topology) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS) as usize;
+        BitBoard {
+            words: vec![0; words_per_row * height as usize],
+            width,
+            height,
+            words_per_row,
+            topology,
+        }
+    }
+
+    pub fn is_alive_at(&self, position: Position) -> bool {
+        let (index, bit) = self.locate(position);
+        self.words[index] & bit != 0
+    }
+
+    pub fn set_alive_at(&mut self, position: Position) {
+        let (index, bit) = self.locate(position);
+        self.words[index] |= bit;
+    }
+
+    pub fn set_dead_at(&mut self, position: Position) {
+        let (index, bit) = self.locate(position);
+        self.words[index] &= !bit;
+    }
+
+    pub fn population(&self) -> u32 {
+        self.words.iter().map(|word| word.count_ones()).sum()
+    }
+
+    pub fn next_generation(&self, rule: &Rule, buffers: &mut RowBuffers) -> Vec<Change> {
+        let mut changes = Vec::new();
+        if self.width == 0 {
+            return changes;
+        }
+        let words_per_row = self.words_per_row;
+        buffers.shifted.resize(6 * words_per_row, 0);
+        buffers.beyond_edge.resize(2 * words_per_row, 0);
+        for y in 0..self.height {
+            let (above_beyond_edge, below_beyond_edge) =
+                buffers.beyond_edge.split_at_mut(words_per_row);
+            let above = self.neighbour_row(i64::from(y) - 1, above_beyond_edge);
+            let current = Some(self.row(y));
+            let below = self.neighbour_row(i64::from(y) + 1, below_beyond_edge);
+            let (above_shifted, shifted) = buffers.shifted.split_at_mut(2 * words_per_row);
+            let (current_shifted, below_shifted) = shifted.split_at_mut(2 * words_per_row);
+            self.shift(above, i64::from(y) - 1, above_shifted);
+            self.shift(current, i64::from(y), current_shifted);
+            self.shift(below, i64::from(y) + 1, below_shifted);
+            let (above_west, above_east) = above_shifted.split_at(words_per_row);
+            let (west, east) = current_shifted.split_at(words_per_row);
+            let (below_west, below_east) = below_shifted.split_at(words_per_row);
+
+            for word in 0..words_per_row {
+                let neighbours = [
+                    above_west[word],
+                    above.map_or(0, |row| row[word]),
+                    above_east[word],
+                    west[word],
+                    east[word],
+                    below_west[word],
+                    below.map_or(0, |row| row[word]),
+                    below_east[word],
+                ];
+                let counts = count_neighbours(&neighbours);
+                let alive = current.map_or(0, |row| row[word]);
+                let mut next = 0;
+                for count in 0..=8 {
+                    let has_count = equals(&counts, count);
+                    if rule.is_born(count) {
+                        next |= !alive & has_count;
+                    }
+                    if rule.survives(count) {
+                        next |= alive & has_count;
+                    }
+                }
+                next &= self.word_mask(word);
+
+                let mut differences = next ^ alive;
+                while differences != 0 {
+                    let bit = differences.trailing_zeros();
+                    differences &= differences - 1;
+                    changes.push(Change {
+                        position: Position {
+                            x: word as u32 * WORD_BITS + bit,
+                            y,
+                        },
+                        state: if next & (1 << bit) != 0 { ALIVE } else { DEAD },
+                    });
+                }
+            }
+        }
+        changes
+    }
+
+    fn locate(&self, position: Position) -> (usize, u64) {
+        let index = position.y as usize * self.words_per_row + (position.x / WORD_BITS) as usize;
+        (index, 1 << (position.x % WORD_BITS))
+    }
+
+    fn row(&self, y: u32) -> &[u64] {
+        let start = y as usize * self.words_per_row;
+        &self.words[start..start + self.words_per_row]
+    }
+
+    // Rows beyond the top and bottom edge are gathered into the buffer cell by cell,
+    // as twisted edges mirror them
+    fn neighbour_row<'a>(&'a self, y: i64, beyond_edge: &'a mut [u64]) -> Option<&'a [u64]> {
+        if y >= 0 && y < i64::from(self.height) {
+            return Some(self.row(y as u32));
+        }
+        if self.topology == Topology::Bounded {
+            return None;
+        }
+        for word in beyond_edge.iter_mut() {
+            *word = 0;
+        }
+        for x in 0..self.width {
+            if self.is_alive_beyond_edge(i64::from(x), y) {
+                beyond_edge[(x / WORD_BITS) as usize] |= 1 << (x % WORD_BITS);
+            }
+        }
+        Some(beyond_edge)
+    }
+
+    fn is_alive_beyond_edge(&self, x: i64, y: i64) -> bool {
+        self.topology
+            .resolve(self.width, self.height, x, y)
+            .is_some_and(|position| self.is_alive_at(position))
+    }
+
+    // For every cell of row y, the state of its west neighbour followed by that of its east one
+    fn shift(&self, row: Option<&[u64]>, y: i64, shifted: &mut [u64]) {
+        let (west, east) = shifted.split_at_mut(self.words_per_row);
+        let row = match row {
+            Some(row) => row,
+            None => {
+                for word in west.iter_mut().chain(east.iter_mut()) {
+                    *word = 0;
+                }
+                return;
+            }
+        };
+        for word in 0..self.words_per_row {
+            let previous = if word > 0 { row[word - 1] } else { 0 };
+            let next = if word + 1 < self.words_per_row {
+                row[word + 1]
+            } else {
+                0
+            };
+            west[word] = (row[word] << 1) | (previous >> (WORD_BITS - 1));
+            east[word] = (row[word] >> 1) | (next << (WORD_BITS - 1));
+        }
+        let last_x = self.width - 1;
+        let last_word = self.words_per_row - 1;
+        let last_bit = 1 << (last_x % WORD_BITS);
+        // Bits past the last column are always dead, so only the first and last column
+        // need fixing up when the topology gives them neighbours beyond the edge
+        east[last_word] &= !last_bit;
+        if self.is_alive_beyond_edge(-1, y) {
+            west[0] |= 1;
+        }
+        if self.is_alive_beyond_edge(i64::from(self.width), y) {
+            east[last_word] |= last_bit;
+        }
+    }
+
+    fn word_mask(&self, word: usize) -> u64 {
+        let remaining_bits = self.width - word as u32 * WORD_BITS;
+        if remaining_bits >= WORD_BITS {
+            !0
+        } else {
+            (1 << remaining_bits) - 1
+        }
+    }
+}
+
+// Adds up eight one bit numbers per bit position, giving the four bits of every count
+fn count_neighbours(neighbours: &[u64; 8]) -> [u64; 4] {
+    let (sum_a, carry_a) = full_add(neighbours[0], neighbours[1], neighbours[2]);
+    let (sum_b, carry_b) = full_add(neighbours[3], neighbours[4], neighbours[5]);
+    let (sum_c, carry_c) = half_add(neighbours[6], neighbours[7]);
+    let (ones, carry_d) = full_add(sum_a, sum_b, sum_c);
+    let (sum_e, carry_e) = full_add(carry_a, carry_b, carry_c);
+    let (twos, carry_f) = half_add(sum_e, carry_d);
+    let (fours, eights) = half_add(carry_e, carry_f);
+    [ones, twos, fours, eights]
+}
+
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (partial & c))
+}
+
+fn half_add(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
+
+fn equals(counts: &[u64; 4], count: u32) -> u64 {
+    counts
+        .iter()
+        .enumerate()
+        .fold(!0, |matching, (bit, &count_bit)| {
+            if count & (1 << bit) != 0 {
+                matching & count_bit
+            } else {
+                matching & !count_bit
+            }
+        })
+}
+
+pub struct BitGrid {
+    board: Rc<RefCell<BitBoard>>,
+}
+
+impl BitGrid {
+    pub fn new(width: u32, height: u32) -> Self {
+        BitGrid::with_topology(width, height, Topology::Bounded)
+    }
+
+    pub fn with_topology(width: u32, height: u32, topology: Topology) -> Self {
+        BitGrid {
+            board: Rc::new(RefCell::new(BitBoard::new(width, height, topology))),
+        }
+    }
+
+    pub fn board(&self) -> Rc<RefCell<BitBoard>> {
+        Rc::clone(&self.board)
+    }
+}
+
+impl Grid for BitGrid {
+    fn width(&self) -> u32 {
+        self.board.borrow().width
+    }
+    fn height(&self) -> u32 {
+        self.board.borrow().height
+    }
+    fn topology(&self) -> Topology {
+        self.board.borrow().topology
+    }
+    fn is_alive_at(&self, position: Position) -> bool {
+        self.board.borrow().is_alive_at(position)
+    }
+    fn set_alive_at(&mut self, position: Position) {
+        self.board.borrow_mut().set_alive_at(position);
+    }
+    fn set_dead_at(&mut self, position: Position) {
+        self.board.borrow_mut().set_dead_at(position);
+    }
+}
+
+// Computes generations straight from the packed words of a BitGrid
+// instead of asking for every cell through the Grid trait
+pub struct BitGenerationCalculator {
+    board: Rc<RefCell<BitBoard>>,
+    rule: Rule,
+    buffers: RefCell<RowBuffers>,
+}
+
+impl BitGenerationCalculator {
+    pub fn new(grid: &BitGrid, rule: Rule) -> Self {
+        BitGenerationCalculator {
+            board: grid.board(),
+            rule,
+            buffers: RefCell::new(RowBuffers::default()),
+        }
+    }
+}
+
+impl GenerationCalculator for BitGenerationCalculator {
+    fn next_generation(&self, _grid: &dyn Grid) -> Vec<Change> {
+        self.board
+            .borrow()
+            .next_generation(&self.rule, &mut self.buffers.borrow_mut())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generation_calculator::GenerationCalculatorImpl;
+    use crate::grid::{apply_changes, GridImpl};
+    use crate::test_util::fill_soup;
+    use crate::topology::TwistedEdges;
+
+    fn assert_matches_reference(width: u32, height: u32, topology: Topology, rule: &str) {
+        let rule: Rule = rule.parse().unwrap();
+        let mut reference_grid = GridImpl::with_topology(width, height, topology);
+        let mut bit_grid = BitGrid::with_topology(width, height, topology);
+        fill_soup(&mut reference_grid, 0x2545_f491_4f6c_dd1d);
+        fill_soup(&mut bit_grid, 0x2545_f491_4f6c_dd1d);
+        let reference_calculator = GenerationCalculatorImpl::new(rule.clone());
+        let bit_calculator = BitGenerationCalculator::new(&bit_grid, rule);
+
+        for _ in 0..20 {
+            let expected = reference_calculator.next_generation(&reference_grid);
+            let changes = bit_calculator.next_generation(&bit_grid);
+            assert_eq!(expected, changes);
+            apply_changes(&mut reference_grid, &expected);
+            apply_changes(&mut bit_grid, &changes);
+        }
+    }
+
+    #[test]
+    fn grid_has_correct_size() {
+        let grid = BitGrid::new(100, 70);
+        assert_eq!(100, grid.width());
+        assert_eq!(70, grid.height());
+    }
+
+    #[test]
+    fn grid_sets_alive_across_words() {
+        let mut grid = BitGrid::new(130, 3);
+        for &x in &[0, 63, 64, 129] {
+            grid.set_alive_at(Position { x, y: 1 });
+        }
+        for x in 0..130 {
+            let expected = x == 0 || x == 63 || x == 64 || x == 129;
+            assert_eq!(expected, grid.is_alive_at(Position { x, y: 1 }));
+        }
+        assert_eq!(4, grid.board().borrow().population());
+    }
+
+    #[test]
+    fn grid_sets_dead() {
+        let mut grid = BitGrid::new(10, 10);
+        let position = Position { x: 2, y: 3 };
+        grid.set_alive_at(position);
+        grid.set_dead_at(position);
+        assert_eq!(false, grid.is_alive_at(position));
+    }
+
+    #[test]
+    fn computes_nothing_on_empty_board() {
+        let grid = BitGrid::new(0, 0);
+        let generation_calculator = BitGenerationCalculator::new(&grid, Rule::conway());
+        assert!(generation_calculator.next_generation(&grid).is_empty());
+    }
+
+    #[test]
+    fn counts_every_number_of_neighbours() {
+        for count in 0..=8 {
+            let mut neighbours = [0; 8];
+            for neighbour in neighbours.iter_mut().take(count) {
+                *neighbour = 1;
+            }
+            let counts = count_neighbours(&neighbours);
+            for candidate in 0..=8 {
+                assert_eq!(
+                    if candidate == count as u32 { 1 } else { 0 },
+                    equals(&counts, candidate) & 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn blinker_across_word_boundary_oscillates() {
+        let mut grid = BitGrid::new(128, 3);
+        for &x in &[63, 64, 65] {
+            grid.set_alive_at(Position { x, y: 1 });
+        }
+        let generation_calculator = BitGenerationCalculator::new(&grid, Rule::conway());
+
+        let changes = generation_calculator.next_generation(&grid);
+
+        let expected = vec![
+            Change {
+                position: Position { x: 64, y: 0 },
+                state: ALIVE,
+            },
+            Change {
+                position: Position { x: 63, y: 1 },
+                state: DEAD,
+            },
+            Change {
+                position: Position { x: 65, y: 1 },
+                state: DEAD,
+            },
+            Change {
+                position: Position { x: 64, y: 2 },
+                state: ALIVE,
+            },
+        ];
+        assert_eq!(expected, changes);
+    }
+
+    #[test]
+    fn matches_reference_on_bounded_grid() {
+        assert_matches_reference(100, 37, Topology::Bounded, "B3/S23");
+    }
+
+    #[test]
+    fn matches_reference_on_word_aligned_grid() {
+        assert_matches_reference(128, 20, Topology::Bounded, "B3/S23");
+    }
+
+    #[test]
+    fn matches_reference_on_torus() {
+        assert_matches_reference(70, 33, Topology::Torus, "B3/S23");
+        assert_matches_reference(64, 16, Topology::Torus, "B3/S23");
+    }
+
+    #[test]
+    fn matches_reference_on_twisted_topologies() {
+        for &twisted_edges in &[TwistedEdges::TopAndBottom, TwistedEdges::LeftAndRight] {
+            let topology = Topology::KleinBottle(twisted_edges);
+            assert_matches_reference(70, 33, topology, "B3/S23");
+            assert_matches_reference(64, 16, topology, "B3/S23");
+        }
+        assert_matches_reference(70, 33, Topology::CrossSurface, "B3/S23");
+        assert_matches_reference(64, 16, Topology::CrossSurface, "B3/S23");
+    }
+
+    #[test]
+    fn matches_reference_for_other_rules() {
+        assert_matches_reference(90, 30, Topology::Bounded, "B36/S23");
+        assert_matches_reference(90, 30, Topology::Torus, "B3678/S34678");
+        assert_matches_reference(90, 30, Topology::Bounded, "B1/S012345678");
+    }
+}
+
+#[cfg(all(test, feature = "bench"))]
+mod bench {
+    extern crate test;
+
+    use self::test::Bencher;
+    use super::*;
+    use crate::generation_calculator::GenerationCalculatorImpl;
+    use crate::grid::GridImpl;
+    use crate::test_util::fill_soup;
+
+    const SIZE: u32 = 512;
+
+    #[bench]
+    fn generation_calculator_impl(bencher: &mut Bencher) {
+        let mut grid = GridImpl::new(SIZE, SIZE);
+        fill_soup(&mut grid, 0x9e37_79b9_7f4a_7c15);
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
+        bencher.iter(|| generation_calculator.next_generation(&grid));
+    }
+
+    #[bench]
+    fn bit_generation_calculator(bencher: &mut Bencher) {
+        let mut grid = BitGrid::new(SIZE, SIZE);
+        fill_soup(&mut grid, 0x9e37_79b9_7f4a_7c15);
+        let generation_calculator = BitGenerationCalculator::new(&grid, Rule::conway());
+        bencher.iter(|| generation_calculator.next_generation(&grid));
+    }
+}
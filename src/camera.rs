@@ -0,0 +1,162 @@
+use crate::coordinate_translator::{Rect, ViewInfo};
+use crate::grid::Position;
+use crate::grid_info::GridInfo;
+
+const MIN_SCALE: f64 = 1.0;
+const MAX_SCALE: f64 = 16.0;
+
+// Tracks which part of the board is shown on the canvas.
+// The board is drawn at `scale` times its size with its top left corner at `x`, `y`,
+// which are never positive, so the board always covers the whole canvas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+    canvas_width: f64,
+    canvas_height: f64,
+    board_width: f64,
+    board_height: f64,
+    x: f64,
+    y: f64,
+    scale: f64,
+}
+
+impl Camera {
+    pub fn new(grid_info: &GridInfo) -> Self {
+        let width = f64::from(grid_info.width);
+        let height = f64::from(grid_info.height);
+        Camera {
+            canvas_width: width,
+            canvas_height: height,
+            board_width: width,
+            board_height: height,
+            x: 0.0,
+            y: 0.0,
+            scale: MIN_SCALE,
+        }
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    // Keeps the point of the board under the anchor in place
+    pub fn zoom(&mut self, anchor: &Position, factor: f64) {
+        let anchor_x = f64::from(anchor.x);
+        let anchor_y = f64::from(anchor.y);
+        let scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+        let board_x = (anchor_x - self.x) / self.scale;
+        let board_y = (anchor_y - self.y) / self.scale;
+        self.scale = scale;
+        self.x = anchor_x - board_x * scale;
+        self.y = anchor_y - board_y * scale;
+        self.clamp();
+    }
+
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.x += f64::from(dx);
+        self.y += f64::from(dy);
+        self.clamp();
+    }
+
+    fn clamp(&mut self) {
+        let min_x = self.canvas_width - self.board_width * self.scale;
+        let min_y = self.canvas_height - self.board_height * self.scale;
+        self.x = self.x.max(min_x).min(0.0);
+        self.y = self.y.max(min_y).min(0.0);
+    }
+}
+
+// Maps positions on the canvas to positions on the unscaled board
+impl ViewInfo for Camera {
+    fn view_rect(&self) -> Rect {
+        Rect {
+            x: self.x,
+            y: self.y,
+            width: self.board_width * self.scale,
+            height: self.board_height * self.scale,
+        }
+    }
+
+    fn client_rect(&self) -> Rect {
+        Rect {
+            x: 0.0,
+            y: 0.0,
+            width: self.board_width,
+            height: self.board_height,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_camera() -> Camera {
+        Camera::new(&GridInfo {
+            width: 100,
+            height: 80,
+            rows: 8,
+            columns: 10,
+        })
+    }
+
+    #[test]
+    fn shows_whole_board_initially() {
+        let camera = create_camera();
+        let expected = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 80.0,
+        };
+        assert_eq!(expected, camera.view_rect());
+        assert_eq!(expected, camera.client_rect());
+    }
+
+    #[test]
+    fn zooms_around_anchor() {
+        let mut camera = create_camera();
+        camera.zoom(&Position { x: 40, y: 20 }, 2.5);
+
+        let expected = Rect {
+            x: -60.0,
+            y: -30.0,
+            width: 250.0,
+            height: 200.0,
+        };
+        assert_eq!(expected, camera.view_rect());
+        assert_eq!(2.5, camera.scale());
+    }
+
+    #[test]
+    fn limits_scale() {
+        let mut camera = create_camera();
+        camera.zoom(&Position { x: 50, y: 50 }, 0.5);
+        assert_eq!(MIN_SCALE, camera.scale());
+        camera.zoom(&Position { x: 50, y: 50 }, 100.0);
+        assert_eq!(MAX_SCALE, camera.scale());
+    }
+
+    #[test]
+    fn keeps_board_on_canvas_when_panning() {
+        let mut camera = create_camera();
+        camera.zoom(&Position { x: 0, y: 0 }, 2.0);
+        camera.pan(-30, 10);
+        assert_eq!((-30.0, 0.0), (camera.view_rect().x, camera.view_rect().y));
+        camera.pan(-500, -500);
+        assert_eq!(
+            (-100.0, -80.0),
+            (camera.view_rect().x, camera.view_rect().y)
+        );
+    }
+
+    #[test]
+    fn zooming_out_keeps_board_on_canvas() {
+        let mut camera = create_camera();
+        camera.zoom(&Position { x: 100, y: 80 }, 4.0);
+        camera.zoom(&Position { x: 0, y: 0 }, 0.5);
+        assert_eq!(
+            (-100.0, -80.0),
+            (camera.view_rect().x, camera.view_rect().y)
+        );
+    }
+}
@@ -3,3 +3,6 @@ pub const CANVAS_HEIGHT: u32 = 1000;
 pub const LINE_COLOUR: &str = "lightgray";
 pub const ALIVE_CELL_COLOUR: &str = "black";
 pub const DEAD_CELL_COLOUR: &str = "white";
+// Cells of multi-state rules fade from the first to the last colour while dying
+pub const FIRST_DYING_CELL_COLOUR: (u8, u8, u8) = (200, 40, 40);
+pub const LAST_DYING_CELL_COLOUR: (u8, u8, u8) = (250, 220, 200);
@@ -1,3 +1,4 @@
 pub mod constant;
 pub mod presenter;
+#[cfg(feature = "wasm")]
 pub mod view;
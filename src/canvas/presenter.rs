@@ -1,8 +1,12 @@
 use crate::canvas::constant;
+use crate::coordinate_translator::{Rect, ViewInfo};
 use crate::generation_calculator::Change;
-use crate::grid::Position;
+use crate::grid::{Position, ALIVE, DEAD};
 use crate::grid_info::GridInfo;
+use crate::hex_layout::HexLayout;
 use crate::interactive_game::Presenter;
+use crate::neighbourhood::Neighbourhood;
+use std::collections::{HashMap, HashSet};
 
 #[cfg(test)]
 extern crate mockers;
@@ -13,6 +17,7 @@ use mockers_derive::mocked;
 pub struct CanvasViewModel {
     pub lines: Vec<Line>,
     pub squares: Vec<Square>,
+    pub hexagons: Vec<Hexagon>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -30,6 +35,12 @@ pub struct Square {
     pub colour: String,
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub struct Hexagon {
+    pub corners: Vec<Position>,
+    pub colour: String,
+}
+
 #[cfg_attr(test, mocked)]
 pub trait CanvasView {
     fn init_board(&mut self, view_model: &CanvasViewModel);
@@ -37,40 +48,112 @@ pub trait CanvasView {
 }
 
 pub struct CanvasPresenter {
-    view: Box<CanvasView>,
+    view: Box<dyn CanvasView>,
     is_initialized: bool,
     grid_info: GridInfo,
+    view_info: Option<Box<dyn ViewInfo>>,
+    palette: Palette,
+    neighbourhood: Neighbourhood,
 }
 
 impl CanvasPresenter {
-    pub fn new(view: Box<CanvasView>, grid_info: GridInfo) -> Self {
+    pub fn new(view: Box<dyn CanvasView>, grid_info: GridInfo) -> Self {
         CanvasPresenter {
             view,
             is_initialized: false,
             grid_info,
+            view_info: None,
+            palette: Palette::default(),
+            neighbourhood: Neighbourhood::Moore,
+        }
+    }
+
+    // Draws the part of the board the view info maps onto the canvas
+    pub fn with_view_info(mut self, view_info: Box<dyn ViewInfo>) -> Self {
+        self.view_info = Some(view_info);
+        self
+    }
+
+    // Gives each dying state of a multi-state rule its own colour
+    pub fn with_state_count(mut self, states: u8) -> Self {
+        self.palette.states = states;
+        self
+    }
+
+    // Colours the given states like a rule table asks for, the dead state excepted
+    pub fn with_state_colours(mut self, colours: &[(u8, (u8, u8, u8))]) -> Self {
+        for &(state, (red, green, blue)) in colours.iter().filter(|&&(state, _)| state != DEAD) {
+            let colour = format!("rgb({}, {}, {})", red, green, blue);
+            self.palette.colours.insert(state, colour);
+        }
+        self
+    }
+
+    // Draws hexagonal cells for the hexagonal neighbourhood and square ones otherwise
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    fn geometry(&self) -> Geometry {
+        let board = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: f64::from(self.grid_info.width),
+            height: f64::from(self.grid_info.height),
+        };
+        let (view_rect, client_rect) = match self.view_info {
+            Some(ref view_info) => (view_info.view_rect(), view_info.client_rect()),
+            None => (board.clone(), board),
+        };
+        Geometry {
+            grid_info: self.grid_info.clone(),
+            x_scale: view_rect.width / client_rect.width,
+            y_scale: view_rect.height / client_rect.height,
+            view_rect,
         }
     }
 }
 
 impl Presenter for CanvasPresenter {
     fn init_board(&mut self, alive_cells: &[Position]) {
-        if self.is_initialized {
-            panic!(
-                "Initialized board multiple times \
-                 Did you accidentally call .init_board() multiple times?"
-            );
-        }
         self.is_initialized = true;
-        let lines = get_lines(&self.grid_info);
+        let geometry = self.geometry();
         let cells_as_changes = alive_cells
             .iter()
             .map(|&position| Change {
                 position,
-                is_alive: true,
+                state: ALIVE,
             })
             .collect::<Vec<_>>();
-        let squares = get_squares(&self.grid_info, &cells_as_changes);
-        let view_model = CanvasViewModel { lines, squares };
+        let view_model = match self.neighbourhood {
+            Neighbourhood::Hexagonal => {
+                let mut hexagons = get_hexagon_outlines(&geometry);
+                let alive_cells: HashSet<_> = alive_cells.iter().cloned().collect();
+                let cells = (0..self.grid_info.rows)
+                    .flat_map(|y| (0..self.grid_info.columns).map(move |x| Position { x, y }))
+                    .map(|position| Change {
+                        position,
+                        state: if alive_cells.contains(&position) {
+                            ALIVE
+                        } else {
+                            DEAD
+                        },
+                    })
+                    .collect::<Vec<_>>();
+                hexagons.extend(get_hexagons(&geometry, &cells, &self.palette));
+                CanvasViewModel {
+                    lines: Vec::new(),
+                    squares: Vec::new(),
+                    hexagons,
+                }
+            }
+            _ => CanvasViewModel {
+                lines: get_lines(&geometry),
+                squares: get_squares(&geometry, &cells_as_changes, &self.palette),
+                hexagons: Vec::new(),
+            },
+        };
         self.view.init_board(&view_model);
     }
 
@@ -81,39 +164,113 @@ impl Presenter for CanvasPresenter {
                  Did you forget to call .init_board()?"
             );
         }
-        let squares = get_squares(&self.grid_info, changes);
-        let view_model = CanvasViewModel {
-            lines: Vec::new(),
-            squares,
+        let geometry = self.geometry();
+        let view_model = match self.neighbourhood {
+            Neighbourhood::Hexagonal => CanvasViewModel {
+                lines: Vec::new(),
+                squares: Vec::new(),
+                hexagons: get_hexagons(&geometry, changes, &self.palette),
+            },
+            _ => CanvasViewModel {
+                lines: Vec::new(),
+                squares: get_squares(&geometry, changes, &self.palette),
+                hexagons: Vec::new(),
+            },
         };
         self.view.draw_view_model(&view_model);
     }
 }
 
-fn get_lines(grid_info: &GridInfo) -> Vec<Line> {
+// Where the board ends up on the canvas, which has the same size as the unscaled board
+struct Geometry {
+    grid_info: GridInfo,
+    view_rect: Rect,
+    x_scale: f64,
+    y_scale: f64,
+}
+
+impl Geometry {
+    // Cells fill the whole board, so they need not be a whole number of pixels wide
+    fn cell_width(&self) -> f64 {
+        f64::from(self.grid_info.width) / f64::from(self.grid_info.columns)
+    }
+
+    fn cell_height(&self) -> f64 {
+        f64::from(self.grid_info.height) / f64::from(self.grid_info.rows)
+    }
+
+    fn canvas_x(&self, board_x: f64) -> f64 {
+        (self.view_rect.x + board_x * self.x_scale).round()
+    }
+
+    fn canvas_y(&self, board_y: f64) -> f64 {
+        (self.view_rect.y + board_y * self.y_scale).round()
+    }
+
+    fn canvas_point(&self, (board_x, board_y): (f64, f64)) -> (f64, f64) {
+        (
+            self.view_rect.x + board_x * self.x_scale,
+            self.view_rect.y + board_y * self.y_scale,
+        )
+    }
+}
+
+// The part of the span that lies on the canvas, as its start and length
+fn visible_span(start: f64, end: f64, limit: u32) -> Option<(u32, u32)> {
+    let start = start.max(0.0);
+    let end = end.min(f64::from(limit));
+    if end > start {
+        Some((start as u32, (end - start) as u32))
+    } else {
+        None
+    }
+}
+
+fn get_lines(geometry: &Geometry) -> Vec<Line> {
+    let grid_info = &geometry.grid_info;
+    let (left, width) = visible_span(
+        geometry.canvas_x(0.0),
+        geometry.canvas_x(f64::from(grid_info.width)),
+        grid_info.width,
+    )
+    .unwrap_or((0, 0));
+    let (top, height) = visible_span(
+        geometry.canvas_y(0.0),
+        geometry.canvas_y(f64::from(grid_info.height)),
+        grid_info.height,
+    )
+    .unwrap_or((0, 0));
     let mut lines = Vec::new();
     for y in 1..grid_info.rows {
+        let canvas_y = geometry.canvas_y(f64::from(y) * geometry.cell_height());
+        if canvas_y <= 0.0 || canvas_y >= f64::from(grid_info.height) {
+            continue;
+        }
         lines.push(Line {
             from: Position {
-                x: 0,
-                y: y * (grid_info.height / grid_info.rows),
+                x: left,
+                y: canvas_y as u32,
             },
             to: Position {
-                x: grid_info.width,
-                y: y * (grid_info.height / grid_info.rows),
+                x: left + width,
+                y: canvas_y as u32,
             },
             colour: constant::LINE_COLOUR.into(),
         })
     }
     for x in 1..grid_info.columns {
+        let canvas_x = geometry.canvas_x(f64::from(x) * geometry.cell_width());
+        if canvas_x <= 0.0 || canvas_x >= f64::from(grid_info.width) {
+            continue;
+        }
         lines.push(Line {
             from: Position {
-                x: x * (grid_info.width / grid_info.columns),
-                y: 0,
+                x: canvas_x as u32,
+                y: top,
             },
             to: Position {
-                x: x * (grid_info.width / grid_info.columns),
-                y: grid_info.height,
+                x: canvas_x as u32,
+                y: top + height,
             },
             colour: constant::LINE_COLOUR.into(),
         })
@@ -121,34 +278,167 @@ fn get_lines(grid_info: &GridInfo) -> Vec<Line> {
     lines
 }
 
-fn get_squares(grid_info: &GridInfo, changes: &[Change]) -> Vec<Square> {
+// Cells outside of the canvas are left out
+fn get_squares(geometry: &Geometry, changes: &[Change], palette: &Palette) -> Vec<Square> {
+    const BORDER_THICKNESS: f64 = 1.0;
+    let cell_width = geometry.cell_width();
+    let cell_height = geometry.cell_height();
     let mut squares = Vec::new();
     for change in changes {
-        let cell_width = grid_info.width / grid_info.columns;
-        let cell_height = grid_info.height / grid_info.rows;
-        const BORDER_THICKNESS: u32 = 1;
-        squares.push(Square {
-            width: cell_width - BORDER_THICKNESS * 2,
-            height: cell_height - BORDER_THICKNESS * 2,
-            origin: Position {
-                x: change.position.x * cell_width + BORDER_THICKNESS,
-                y: change.position.y * cell_height + BORDER_THICKNESS,
-            },
-            colour: if change.is_alive {
-                constant::ALIVE_CELL_COLOUR.into()
-            } else {
-                constant::DEAD_CELL_COLOUR.into()
-            },
-        });
+        let x = f64::from(change.position.x) * cell_width;
+        let y = f64::from(change.position.y) * cell_height;
+        let horizontal = visible_span(
+            geometry.canvas_x(x) + BORDER_THICKNESS,
+            geometry.canvas_x(x + cell_width) - BORDER_THICKNESS,
+            geometry.grid_info.width,
+        );
+        let vertical = visible_span(
+            geometry.canvas_y(y) + BORDER_THICKNESS,
+            geometry.canvas_y(y + cell_height) - BORDER_THICKNESS,
+            geometry.grid_info.height,
+        );
+        if let (Some((x, width)), Some((y, height))) = (horizontal, vertical) {
+            squares.push(Square {
+                width,
+                height,
+                origin: Position { x, y },
+                colour: palette.colour(change.state),
+            });
+        }
     }
     squares
 }
 
+fn get_hexagon_outlines(geometry: &Geometry) -> Vec<Hexagon> {
+    let layout = HexLayout::new(&geometry.grid_info);
+    let mut hexagons = Vec::new();
+    for y in 0..geometry.grid_info.rows {
+        for x in 0..geometry.grid_info.columns {
+            let corners = canvas_corners(geometry, &layout, &Position { x, y }, 0.0);
+            if let Some(corners) = corners {
+                hexagons.push(Hexagon {
+                    corners,
+                    colour: constant::LINE_COLOUR.into(),
+                });
+            }
+        }
+    }
+    hexagons
+}
+
+// Drawn inside of the outlines, which are left visible as the border between cells
+fn get_hexagons(geometry: &Geometry, changes: &[Change], palette: &Palette) -> Vec<Hexagon> {
+    const BORDER_THICKNESS: f64 = 1.0;
+    let layout = HexLayout::new(&geometry.grid_info);
+    changes
+        .iter()
+        .filter_map(|change| {
+            let corners = canvas_corners(geometry, &layout, &change.position, BORDER_THICKNESS)?;
+            Some(Hexagon {
+                corners,
+                colour: palette.colour(change.state),
+            })
+        })
+        .collect()
+}
+
+// The corners moved towards the centre by the inset, or None if the hexagon is off the canvas.
+// Corners beyond the canvas are clamped to its edge, as they cannot be drawn anyway.
+fn canvas_corners(
+    geometry: &Geometry,
+    layout: &HexLayout,
+    position: &Position,
+    inset: f64,
+) -> Option<Vec<Position>> {
+    let (centre_x, centre_y) = geometry.canvas_point(layout.centre(position));
+    let corners = layout
+        .corners(position)
+        .into_iter()
+        .map(|corner| {
+            let (x, y) = geometry.canvas_point(corner);
+            let length = (centre_x - x).hypot(centre_y - y);
+            (
+                x + (centre_x - x) / length * inset,
+                y + (centre_y - y) / length * inset,
+            )
+        })
+        .collect::<Vec<_>>();
+    let width = f64::from(geometry.grid_info.width);
+    let height = f64::from(geometry.grid_info.height);
+    let is_visible = corners.iter().any(|&(x, _)| x > 0.0)
+        && corners.iter().any(|&(x, _)| x < width)
+        && corners.iter().any(|&(_, y)| y > 0.0)
+        && corners.iter().any(|&(_, y)| y < height);
+    if !is_visible {
+        return None;
+    }
+    let corners = corners
+        .into_iter()
+        .map(|(x, y)| Position {
+            x: x.max(0.0).min(width).round() as u32,
+            y: y.max(0.0).min(height).round() as u32,
+        })
+        .collect();
+    Some(corners)
+}
+
+struct Palette {
+    states: u8,
+    colours: HashMap<u8, String>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            states: 2,
+            colours: HashMap::new(),
+        }
+    }
+}
+
+impl Palette {
+    fn colour(&self, state: u8) -> String {
+        match self.colours.get(&state) {
+            Some(colour) => colour.clone(),
+            None => cell_colour(state, self.states),
+        }
+    }
+}
+
+fn cell_colour(state: u8, states: u8) -> String {
+    match state {
+        DEAD => constant::DEAD_CELL_COLOUR.into(),
+        ALIVE => constant::ALIVE_CELL_COLOUR.into(),
+        dying => {
+            let (first, last) = (
+                constant::FIRST_DYING_CELL_COLOUR,
+                constant::LAST_DYING_CELL_COLOUR,
+            );
+            // Dying cells take the states after ALIVE up to states - 1
+            let dying_states = states.saturating_sub(2).max(2);
+            let progress = (f64::from(dying - 2) / f64::from(dying_states - 1)).min(1.0);
+            let blend = |from: u8, to: u8| {
+                (f64::from(from) + (f64::from(to) - f64::from(from)) * progress).round()
+            };
+            format!(
+                "rgb({}, {}, {})",
+                blend(first.0, last.0),
+                blend(first.1, last.1),
+                blend(first.2, last.2)
+            )
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::camera::Camera;
     use crate::canvas::constant;
+    use mockers::matchers::{check, ANY};
     use mockers::Scenario;
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     const WIDTH: u32 = 10;
     const HEIGHT: u32 = 8;
@@ -160,13 +450,13 @@ mod test {
         columns: 10,
     };
 
-    fn create_mock() -> (Scenario, CanvasViewMock) {
+    fn create_mock() -> (Scenario, CanvasViewMock, CanvasViewMockHandle) {
         let scenario = Scenario::new();
-        let view = scenario.create_mock_for::<CanvasView>();
-        (scenario, view)
+        let (view, view_handle) = scenario.create_mock_for::<dyn CanvasView>();
+        (scenario, view, view_handle)
     }
 
-    fn init_board(scenario: &Scenario, view: &CanvasViewMock) {
+    fn init_board(scenario: &Scenario, view_handle: &CanvasViewMockHandle, times: u32) {
         lazy_static! {
             static ref EMPTY_INITIALIZED_VIEW_MODEL: CanvasViewModel = {
                 let mut lines = Vec::new();
@@ -199,37 +489,39 @@ mod test {
                 CanvasViewModel {
                     lines,
                     squares: Vec::new(),
+                    hexagons: Vec::new(),
                 }
             };
         }
         scenario.expect(
-            view.init_board_call(&*EMPTY_INITIALIZED_VIEW_MODEL)
-                .and_return(()),
+            view_handle
+                .init_board(&*EMPTY_INITIALIZED_VIEW_MODEL)
+                .and_return_clone(())
+                .times(times),
         );
     }
 
     #[test]
     #[should_panic]
     fn panics_when_presenting_changes_and_not_initialized() {
-        let (_scenario, view) = create_mock();
+        let (_scenario, view, _view_handle) = create_mock();
         let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone());
         presenter.present_changes(&Vec::new());
     }
 
     #[test]
     fn inits_empty_board() {
-        let (scenario, view) = create_mock();
-        init_board(&scenario, &view);
+        let (scenario, view, view_handle) = create_mock();
+        init_board(&scenario, &view_handle, 1);
 
         let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone());
         presenter.init_board(&Vec::new());
     }
 
     #[test]
-    #[should_panic]
-    fn panics_initializing_multiple_times() {
-        let (scenario, view) = create_mock();
-        init_board(&scenario, &view);
+    fn reinitializes_board() {
+        let (scenario, view, view_handle) = create_mock();
+        init_board(&scenario, &view_handle, 2);
 
         let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone());
         presenter.init_board(&Vec::new());
@@ -238,9 +530,9 @@ mod test {
 
     #[test]
     fn presents_changes() {
-        let (scenario, view) = create_mock();
-        init_board(&scenario, &view);
-        lazy_static!{
+        let (scenario, view, view_handle) = create_mock();
+        init_board(&scenario, &view_handle, 1);
+        lazy_static! {
             static ref EXPECTED_VIEW_MODEL: CanvasViewModel = {
                 let mut squares = Vec::new();
                 for (x, y) in &[(2, 3), (3, 4), (1, 1)] {
@@ -260,11 +552,13 @@ mod test {
                 CanvasViewModel {
                     lines: Vec::new(), // We only send changes to the view, so this is empty
                     squares,
+                    hexagons: Vec::new(),
                 }
             };
         }
         scenario.expect(
-            view.draw_view_model_call(&*EXPECTED_VIEW_MODEL)
+            view_handle
+                .draw_view_model(&*EXPECTED_VIEW_MODEL)
                 .and_return(()),
         );
         let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone());
@@ -272,17 +566,296 @@ mod test {
         let changes = vec![
             Change {
                 position: Position { x: 2, y: 3 },
-                is_alive: true,
+                state: ALIVE,
             },
             Change {
                 position: Position { x: 3, y: 4 },
-                is_alive: true,
+                state: ALIVE,
             },
             Change {
                 position: Position { x: 1, y: 1 },
-                is_alive: true,
+                state: ALIVE,
             },
         ];
         presenter.present_changes(&changes);
     }
+
+    #[test]
+    fn presents_visible_cells_at_current_scale() {
+        let (scenario, view, view_handle) = create_mock();
+        let camera = Rc::new(RefCell::new(Camera::new(&GRID_INFO)));
+        camera.borrow_mut().zoom(&Position { x: 0, y: 0 }, 2.0);
+        let cell_width = constant::CANVAS_WIDTH / WIDTH;
+        let cell_height = constant::CANVAS_HEIGHT / HEIGHT;
+        let scaled_square = |x: u32, y: u32| Square {
+            width: cell_width * 2 - 2,
+            height: cell_height * 2 - 2,
+            origin: Position {
+                x: cell_width * x * 2 + 1,
+                y: cell_height * y * 2 + 1,
+            },
+            colour: constant::ALIVE_CELL_COLOUR.into(),
+        };
+        let expected = CanvasViewModel {
+            lines: Vec::new(),
+            squares: vec![scaled_square(0, 0), scaled_square(1, 2)],
+            hexagons: Vec::new(),
+        };
+        scenario.expect(view_handle.init_board(ANY).and_return(()));
+        scenario.expect(
+            view_handle
+                .draw_view_model(check(move |view_model: &&CanvasViewModel| {
+                    **view_model == expected
+                }))
+                .and_return(()),
+        );
+        let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone())
+            .with_view_info(Box::new(camera.clone()));
+
+        presenter.init_board(&[]);
+        presenter.present_changes(&[
+            Change {
+                position: Position { x: 0, y: 0 },
+                state: ALIVE,
+            },
+            Change {
+                position: Position { x: 1, y: 2 },
+                state: ALIVE,
+            },
+            Change {
+                position: Position { x: 6, y: 1 },
+                state: ALIVE,
+            },
+        ]);
+    }
+
+    #[test]
+    fn presents_cells_of_fractional_size_up_to_canvas_edge() {
+        let (scenario, view, view_handle) = create_mock();
+        let grid_info = GridInfo {
+            width: constant::CANVAS_WIDTH,
+            height: constant::CANVAS_HEIGHT,
+            rows: 300,
+            columns: 300,
+        };
+        // The last of 300 cells of 3.33 pixels ends on the right edge of the canvas
+        let expected = CanvasViewModel {
+            lines: Vec::new(),
+            squares: vec![Square {
+                width: 1,
+                height: 1,
+                origin: Position { x: 998, y: 1 },
+                colour: constant::ALIVE_CELL_COLOUR.into(),
+            }],
+            hexagons: Vec::new(),
+        };
+        scenario.expect(view_handle.init_board(ANY).and_return(()));
+        scenario.expect(
+            view_handle
+                .draw_view_model(check(move |view_model: &&CanvasViewModel| {
+                    **view_model == expected
+                }))
+                .and_return(()),
+        );
+        let mut presenter = CanvasPresenter::new(Box::new(view), grid_info);
+
+        presenter.init_board(&[]);
+        presenter.present_changes(&[Change {
+            position: Position { x: 299, y: 0 },
+            state: ALIVE,
+        }]);
+    }
+
+    #[test]
+    fn presents_cells_of_fractional_size_at_fractional_scale() {
+        let (scenario, view, view_handle) = create_mock();
+        let grid_info = GridInfo {
+            width: constant::CANVAS_WIDTH,
+            height: constant::CANVAS_HEIGHT,
+            rows: 300,
+            columns: 300,
+        };
+        let camera = Rc::new(RefCell::new(Camera::new(&grid_info)));
+        camera.borrow_mut().zoom(&Position { x: 0, y: 0 }, 1.5);
+        // Cell 3 spans board pixels 10 to 13.33, which are drawn at 15 to 20
+        let expected = CanvasViewModel {
+            lines: Vec::new(),
+            squares: vec![Square {
+                width: 3,
+                height: 3,
+                origin: Position { x: 16, y: 16 },
+                colour: constant::ALIVE_CELL_COLOUR.into(),
+            }],
+            hexagons: Vec::new(),
+        };
+        scenario.expect(view_handle.init_board(ANY).and_return(()));
+        scenario.expect(
+            view_handle
+                .draw_view_model(check(move |view_model: &&CanvasViewModel| {
+                    **view_model == expected
+                }))
+                .and_return(()),
+        );
+        let mut presenter =
+            CanvasPresenter::new(Box::new(view), grid_info).with_view_info(Box::new(camera));
+
+        presenter.init_board(&[]);
+        presenter.present_changes(&[Change {
+            position: Position { x: 3, y: 3 },
+            state: ALIVE,
+        }]);
+    }
+
+    #[test]
+    fn draws_only_visible_grid_lines() {
+        let (scenario, view, view_handle) = create_mock();
+        let camera = Rc::new(RefCell::new(Camera::new(&GRID_INFO)));
+        camera.borrow_mut().zoom(&Position { x: 0, y: 0 }, 4.0);
+        scenario.expect(
+            view_handle
+                .init_board(check(|view_model: &&CanvasViewModel| {
+                    let (horizontal, vertical): (Vec<_>, Vec<_>) = view_model
+                        .lines
+                        .iter()
+                        .partition(|line| line.from.y == line.to.y);
+                    // Lines on the edge of the canvas are left out as well
+                    horizontal.len() == 1 && vertical.len() == 2
+                }))
+                .and_return(()),
+        );
+        let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone())
+            .with_view_info(Box::new(camera));
+
+        presenter.init_board(&[]);
+    }
+
+    #[test]
+    fn colours_each_dying_state() {
+        let (scenario, view, view_handle) = create_mock();
+        init_board(&scenario, &view_handle, 1);
+        scenario.expect(
+            view_handle
+                .draw_view_model(check(|view_model: &&CanvasViewModel| {
+                    let colours = view_model
+                        .squares
+                        .iter()
+                        .map(|square| square.colour.as_str())
+                        .collect::<Vec<_>>();
+                    colours
+                        == vec![
+                            constant::ALIVE_CELL_COLOUR,
+                            "rgb(200, 40, 40)",
+                            "rgb(225, 130, 120)",
+                            "rgb(250, 220, 200)",
+                            constant::DEAD_CELL_COLOUR,
+                        ]
+                }))
+                .and_return(()),
+        );
+        let mut presenter =
+            CanvasPresenter::new(Box::new(view), GRID_INFO.clone()).with_state_count(5);
+
+        presenter.init_board(&[]);
+        let changes = (0..5)
+            .map(|state| Change {
+                position: Position { x: 0, y: 0 },
+                state: (state + 1) % 5,
+            })
+            .collect::<Vec<_>>();
+        presenter.present_changes(&changes);
+    }
+
+    #[test]
+    fn uses_colours_of_rule_table() {
+        let (scenario, view, view_handle) = create_mock();
+        init_board(&scenario, &view_handle, 1);
+        scenario.expect(
+            view_handle
+                .draw_view_model(check(|view_model: &&CanvasViewModel| {
+                    let colours = view_model
+                        .squares
+                        .iter()
+                        .map(|square| square.colour.as_str())
+                        .collect::<Vec<_>>();
+                    colours
+                        == vec![
+                            "rgb(0, 128, 255)",
+                            "rgb(200, 40, 40)",
+                            constant::DEAD_CELL_COLOUR,
+                        ]
+                }))
+                .and_return(()),
+        );
+        let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone())
+            .with_state_count(3)
+            .with_state_colours(&[(0, (48, 48, 48)), (1, (0, 128, 255))]);
+
+        presenter.init_board(&[]);
+        let changes = [1, 2, 0]
+            .iter()
+            .map(|&state| Change {
+                position: Position { x: 0, y: 0 },
+                state,
+            })
+            .collect::<Vec<_>>();
+        presenter.present_changes(&changes);
+    }
+
+    #[test]
+    fn draws_hexagonal_cells_with_outlines() {
+        let (scenario, view, view_handle) = create_mock();
+        scenario.expect(
+            view_handle
+                .init_board(check(|view_model: &&CanvasViewModel| {
+                    let cells = WIDTH * HEIGHT;
+                    let alive = view_model
+                        .hexagons
+                        .iter()
+                        .filter(|hexagon| hexagon.colour == constant::ALIVE_CELL_COLOUR)
+                        .count();
+                    view_model.lines.is_empty()
+                        && view_model.squares.is_empty()
+                        && view_model.hexagons.len() == 2 * cells as usize
+                        && alive == 1
+                }))
+                .and_return(()),
+        );
+        let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone())
+            .with_neighbourhood(Neighbourhood::Hexagonal);
+
+        presenter.init_board(&[Position { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn presents_changes_as_hexagons() {
+        let (scenario, view, view_handle) = create_mock();
+        scenario.expect(view_handle.init_board(ANY).and_return(()));
+        lazy_static! {
+            static ref EXPECTED_VIEW_MODEL: CanvasViewModel = CanvasViewModel {
+                lines: Vec::new(),
+                squares: Vec::new(),
+                hexagons: vec![Hexagon {
+                    // The odd row is shifted by half a cell
+                    corners: [(190, 105), (237, 146), (237, 229), (190, 270), (144, 229), (144, 146)]
+                        .iter()
+                        .map(|&(x, y)| Position { x, y })
+                        .collect(),
+                    colour: constant::ALIVE_CELL_COLOUR.into(),
+                }],
+            };
+        }
+        scenario.expect(
+            view_handle
+                .draw_view_model(&*EXPECTED_VIEW_MODEL)
+                .and_return(()),
+        );
+        let mut presenter = CanvasPresenter::new(Box::new(view), GRID_INFO.clone())
+            .with_neighbourhood(Neighbourhood::Hexagonal);
+
+        presenter.init_board(&[]);
+        presenter.present_changes(&[Change {
+            position: Position { x: 1, y: 1 },
+            state: ALIVE,
+        }]);
+    }
 }
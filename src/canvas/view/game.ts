@@ -1,12 +1,53 @@
-const rust = import('../../../wasm_generated/game_of_life')
+import init, { EntryPoint } from '../../../wasm_generated/game_of_life'
 
-rust.then(rust => {
+init().then(() => {
     const canvas = document.getElementById('game-board') as HTMLCanvasElement
-    const game = rust.EntryPoint.new(canvas)
+    let game = EntryPoint.new(canvas)
 
-    canvas.addEventListener('click', (e) => {
+    // Dragging with the left button paints, any other button pans the board
+    let panPosition: MousePosition | null = null
+
+    canvas.addEventListener('mousedown', (e) => {
+        const pos = getMousePos(canvas, e)
+        if (e.button === 0 && !e.shiftKey) {
+            game.on_pointer_down(pos.x, pos.y)
+        } else {
+            panPosition = pos
+        }
+    })
+
+    canvas.addEventListener('mousemove', (e) => {
         const pos = getMousePos(canvas, e)
-        game.on_click(pos.x, pos.y)
+        if (panPosition !== null) {
+            game.on_pan(pos.x - panPosition.x, pos.y - panPosition.y)
+            panPosition = pos
+        } else {
+            game.on_pointer_move(pos.x, pos.y)
+        }
+    })
+
+    canvas.addEventListener('mouseup', (e) => {
+        panPosition = null
+        game.on_pointer_up()
+        updateTimeline()
+    })
+
+    canvas.addEventListener('mouseleave', (e) => {
+        panPosition = null
+        game.on_pointer_up()
+        updateTimeline()
+    })
+
+    canvas.addEventListener('contextmenu', (e) => {
+        e.preventDefault()
+    })
+
+    const zoomStep = 1.25
+
+    canvas.addEventListener('wheel', (e) => {
+        e.preventDefault()
+        const pos = getMousePos(canvas, e)
+        game.on_zoom(pos.x, pos.y, e.deltaY < 0 ? zoomStep : 1 / zoomStep)
     })
 
     const startStop = document.getElementById('start-stop') as HTMLInputElement
@@ -30,6 +71,140 @@ rust.then(rust => {
         isGameRunning = !isGameRunning
     }
 
+    const pattern = document.getElementById('pattern') as HTMLTextAreaElement
+    const loadPattern = document.getElementById('load-pattern') as HTMLInputElement
+    const exportPattern = document.getElementById('export-pattern') as HTMLInputElement
+    const clear = document.getElementById('clear') as HTMLInputElement
+    const patternX = document.getElementById('pattern-x') as HTMLInputElement
+    const patternY = document.getElementById('pattern-y') as HTMLInputElement
+
+    loadPattern.addEventListener('click', (e) => {
+        game.load_pattern(pattern.value, Number(patternX.value), Number(patternY.value))
+        updateTimeline()
+    })
+
+    exportPattern.addEventListener('click', (e) => {
+        pattern.value = game.export_rle()
+    })
+
+    clear.addEventListener('click', (e) => {
+        game.clear()
+        updateTimeline()
+    })
+
+    const soupSeed = document.getElementById('soup-seed') as HTMLInputElement
+    const soupDensity = document.getElementById('soup-density') as HTMLInputElement
+    const soupSymmetry = document.getElementById('soup-symmetry') as HTMLSelectElement
+    const randomise = document.getElementById('randomise') as HTMLInputElement
+
+    randomise.addEventListener('click', (e) => {
+        game.randomise(Number(soupSeed.value), Number(soupDensity.value), soupSymmetry.value)
+        updateTimeline()
+    })
+
+    const undo = document.getElementById('undo') as HTMLInputElement
+    const redo = document.getElementById('redo') as HTMLInputElement
+
+    undo.addEventListener('click', (e) => {
+        game.undo()
+        updateTimeline()
+    })
+
+    redo.addEventListener('click', (e) => {
+        game.redo()
+        updateTimeline()
+    })
+
+    const stepBack = document.getElementById('step-back') as HTMLInputElement
+    const timeline = document.getElementById('timeline') as HTMLInputElement
+    const stabilisation = document.getElementById('stabilisation') as HTMLElement
+
+    stepBack.addEventListener('click', (e) => {
+        game.step_back(1)
+        updateTimeline()
+    })
+
+    timeline.addEventListener('input', (e) => {
+        game.jump_to_generation(Number(timeline.value))
+        updateTimeline()
+    })
+
+    function updateTimeline() {
+        timeline.min = String(game.earliest_generation())
+        timeline.max = String(game.latest_generation())
+        timeline.value = String(game.generation())
+        stabilisation.textContent = game.stabilisation()
+    }
+
+    const brushState = document.getElementById('brush-state') as HTMLInputElement
+
+    brushState.addEventListener('change', (e) => {
+        game.select_state(Number(brushState.value))
+    })
+
+    // Columns or rows the arrows move the viewport of infinite boards by
+    const viewportStep = 10
+
+    const viewportMoves: [string, number, number][] = [
+        ['viewport-left', -viewportStep, 0],
+        ['viewport-up', 0, -viewportStep],
+        ['viewport-down', 0, viewportStep],
+        ['viewport-right', viewportStep, 0],
+    ]
+
+    for (const [id, columns, rows] of viewportMoves) {
+        document.getElementById(id).addEventListener('click', (e) => {
+            game.move_viewport(columns, rows)
+            updateTimeline()
+        })
+    }
+
+    const boardKind = document.getElementById('board-kind') as HTMLSelectElement
+    const boardRule = document.getElementById('board-rule') as HTMLTextAreaElement
+    const applyBoard = document.getElementById('apply-board') as HTMLInputElement
+
+    // What the text next to the board kind holds, kinds without a placeholder ignore it
+    const boardPlaceholders: { [kind: string]: string } = {
+        'grid': 'e.g. T100,100',
+        'generations': 'e.g. 345/2/4',
+        'hensel': 'e.g. B2-a/S12',
+        'larger-than-life': 'e.g. R5,C0,M1,S34..58,B34..45,NM',
+        'neighbourhood': 'e.g. B2/S34H',
+        'rule-table': 'text of a .rule file',
+        'hashlife': 'step exponent, e.g. 4',
+    }
+
+    boardKind.addEventListener('change', (e) => {
+        boardRule.placeholder = boardPlaceholders[boardKind.value] || ''
+    })
+
+    function createGame(kind: string, rule: string): EntryPoint {
+        switch (kind) {
+            case 'grid': return EntryPoint.with_grid_spec(canvas, rule)
+            case 'generations': return EntryPoint.with_generations_rule(canvas, rule)
+            case 'hensel': return EntryPoint.with_hensel_rule(canvas, rule)
+            case 'larger-than-life': return EntryPoint.with_larger_than_life_rule(canvas, rule)
+            case 'neighbourhood': return EntryPoint.with_neighbourhood_rule(canvas, rule)
+            case 'rule-table': return EntryPoint.with_rule_table(canvas, rule)
+            case 'wireworld': return EntryPoint.with_wireworld(canvas)
+            case 'infinite': return EntryPoint.with_infinite_plane(canvas)
+            case 'hashlife': return EntryPoint.with_hashlife(canvas, Number(rule))
+            default: return EntryPoint.new(canvas)
+        }
+    }
+
+    applyBoard.addEventListener('click', (e) => {
+        if (isGameRunning) {
+            stopGame()
+            startStop.innerText = "Start"
+            isGameRunning = false
+        }
+        game.free()
+        game = createGame(boardKind.value, boardRule.value)
+        game.select_state(Number(brushState.value))
+        updateTimeline()
+    })
+
     speedSlider.addEventListener('change', (e) => {
         speed = Number(speedSlider.value)
         setGameSpeed(speed)
@@ -43,7 +218,12 @@ rust.then(rust => {
         stopGame()
         const timeout = Number(speedSlider.max) - speed
         timerId = window.setInterval(() => {
-            game.on_timer()
+            if (!game.on_timer()) {
+                stopGame()
+                startStop.innerText = "Start"
+                isGameRunning = false
+            }
+            updateTimeline()
         }, timeout)
     }
 })
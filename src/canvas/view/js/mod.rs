@@ -46,28 +46,28 @@ extern "C" {
     pub type DOMRect;
 
     #[wasm_bindgen(method, getter, structural)]
-    pub fn x(this: &DOMRect) -> i32;
+    pub fn x(this: &DOMRect) -> f64;
 
     #[wasm_bindgen(method, getter, structural)]
-    pub fn y(this: &DOMRect) -> i32;
+    pub fn y(this: &DOMRect) -> f64;
 
     #[wasm_bindgen(method, getter, structural)]
-    pub fn width(this: &DOMRect) -> i32;
+    pub fn width(this: &DOMRect) -> f64;
 
     #[wasm_bindgen(method, getter, structural)]
-    pub fn height(this: &DOMRect) -> i32;
+    pub fn height(this: &DOMRect) -> f64;
 
     #[wasm_bindgen(method, getter, structural)]
-    pub fn top(this: &DOMRect) -> i32;
+    pub fn top(this: &DOMRect) -> f64;
 
     #[wasm_bindgen(method, getter, structural)]
-    pub fn right(this: &DOMRect) -> i32;
+    pub fn right(this: &DOMRect) -> f64;
 
     #[wasm_bindgen(method, getter, structural)]
-    pub fn bottom(this: &DOMRect) -> i32;
+    pub fn bottom(this: &DOMRect) -> f64;
 
     #[wasm_bindgen(method, getter, structural)]
-    pub fn left(this: &DOMRect) -> i32;
+    pub fn left(this: &DOMRect) -> f64;
 
     #[wasm_bindgen(method, js_name = getBoundingClientRect)]
     pub fn get_bounding_client_rect(this: &HTMLCanvasElement) -> DOMRect;
@@ -1,5 +1,6 @@
 pub mod js;
-use super::presenter::{CanvasView, CanvasViewModel, Line, Square};
+use super::constant;
+use super::presenter::{CanvasView, CanvasViewModel, Hexagon, Line, Square};
 
 pub struct CanvasViewImpl {
     context: js::CanvasRenderingContext2D,
@@ -25,10 +26,25 @@ impl CanvasViewImpl {
             square.height,
         );
     }
+    fn draw_hexagon(&mut self, hexagon: &Hexagon) {
+        self.context.set_fill_style(&hexagon.colour);
+        self.context.begin_path();
+        let (first, rest) = hexagon
+            .corners
+            .split_first()
+            .expect("hexagon without corners");
+        self.context.move_to(first.x, first.y);
+        for corner in rest {
+            self.context.line_to(corner.x, corner.y);
+        }
+        self.context.fill();
+    }
 }
 
 impl CanvasView for CanvasViewImpl {
     fn init_board(&mut self, view_model: &CanvasViewModel) {
+        self.context
+            .clear_rect(0, 0, constant::CANVAS_WIDTH, constant::CANVAS_HEIGHT);
         self.draw_view_model(view_model);
     }
     fn draw_view_model(&mut self, view_model: &CanvasViewModel) {
@@ -38,6 +54,9 @@ impl CanvasView for CanvasViewImpl {
         for square in &view_model.squares {
             self.draw_square(square);
         }
+        for hexagon in &view_model.hexagons {
+            self.draw_hexagon(hexagon);
+        }
     }
 }
 
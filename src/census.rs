@@ -0,0 +1,382 @@
+use crate::grid::{alive_cells, Grid};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+// Under B3/S23 two cells influence each other as long as a single dead cell
+// separates them, so anything within a distance of two belongs to the same object
+const INTERACTION_RADIUS: i64 = 2;
+
+// Groups that are too big to be split into known objects
+const MAX_PARTS: usize = 8;
+
+// Every phase of the oscillators and spaceships is listed, as a settled board can show any of them
+const KNOWN_OBJECTS: [(&str, &[&str]); 25] = [
+    ("block", &["OO", "OO"]),
+    ("beehive", &[".OO.", "O..O", ".OO."]),
+    ("loaf", &[".OO.", "O..O", ".O.O", "..O."]),
+    ("boat", &["OO.", "O.O", ".O."]),
+    ("ship", &["OO.", "O.O", ".OO"]),
+    ("tub", &[".O.", "O.O", ".O."]),
+    ("pond", &[".OO.", "O..O", "O..O", ".OO."]),
+    ("long boat", &["OO..", "O.O.", ".O.O", "..O."]),
+    ("barge", &[".O..", "O.O.", ".O.O", "..O."]),
+    ("mango", &[".OO..", "O..O.", ".O..O", "..OO."]),
+    ("long barge", &[".O...", "O.O..", ".O.O.", "..O.O", "...O."]),
+    (
+        "ship-tie",
+        &["OO....", "O.O...", ".OO...", "...OO.", "...O.O", "....OO"],
+    ),
+    (
+        "boat-tie",
+        &[".O....", "O.O...", ".OO...", "...OO.", "...O.O", "....O."],
+    ),
+    ("blinker", &["OOO"]),
+    ("blinker", &["O", "O", "O"]),
+    ("toad", &[".OOO", "OOO."]),
+    ("toad", &["..O.", "O..O", "O..O", ".O.."]),
+    ("beacon", &["OO..", "O...", "...O", "..OO"]),
+    ("beacon", &["OO..", "OO..", "..OO", "..OO"]),
+    ("glider", &[".O.", "..O", "OOO"]),
+    ("glider", &["O.O", ".OO", ".O."]),
+    (
+        "lightweight spaceship",
+        &[".O..O", "O....", "O...O", "OOOO."],
+    ),
+    (
+        "lightweight spaceship",
+        &["..OO.", "OO.OO", "OOOO.", ".OO.."],
+    ),
+    (
+        "traffic light",
+        &[
+            "..OOO..", ".......", "O.....O", "O.....O", "O.....O", ".......", "..OOO..",
+        ],
+    ),
+    (
+        "traffic light",
+        &[
+            "....O....",
+            "....O....",
+            "....O....",
+            ".........",
+            "OOO...OOO",
+            ".........",
+            "....O....",
+            "....O....",
+            "....O....",
+        ],
+    ),
+];
+
+type Shape = Vec<(i64, i64)>;
+
+// Counts the objects on the board by name, objects that are not known
+// are grouped by their number of cells
+pub fn census(grid: &dyn Grid) -> BTreeMap<String, u32> {
+    let known_objects = KNOWN_OBJECTS
+        .iter()
+        .map(|&(name, rows)| (canonical_shape(&parse_shape(rows)), name))
+        .collect::<HashMap<_, _>>();
+    let mut census = BTreeMap::new();
+    for object in objects(grid) {
+        let names = match known_parts(parts(&object), &known_objects) {
+            Some(names) => names.iter().map(|name| name.to_string()).collect(),
+            None => vec![format!("unidentified {}-cell object", object.len())],
+        };
+        for name in names {
+            *census.entry(name).or_insert(0) += 1;
+        }
+    }
+    census
+}
+
+// Objects that only come within the interaction radius of each other, like two blinkers
+// next to each other, are told apart by looking for known objects among the touching parts
+// of the group. None if the parts can't all be assigned to known objects.
+fn known_parts<'a>(
+    mut parts: Vec<Shape>,
+    known_objects: &HashMap<Shape, &'a str>,
+) -> Option<Vec<&'a str>> {
+    let first = match parts.pop() {
+        Some(first) => first,
+        None => return Some(Vec::new()),
+    };
+    if parts.len() >= MAX_PARTS {
+        return None;
+    }
+    // Whole objects are tried before their pieces, so a traffic light isn't counted as blinkers
+    let mut selections = (0..1u32 << parts.len()).collect::<Vec<_>>();
+    selections.sort_by_key(|selection| std::cmp::Reverse(selection.count_ones()));
+    for selection in selections {
+        let selected = |index: usize| selection & (1 << index) != 0;
+        let mut object = first.clone();
+        for (_, part) in parts
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| selected(index))
+        {
+            object.extend(part);
+        }
+        if let Some(&name) = known_objects.get(&canonical_shape(&object)) {
+            let remaining = parts
+                .iter()
+                .enumerate()
+                .filter(|&(index, _)| !selected(index))
+                .map(|(_, part)| part.clone())
+                .collect();
+            if let Some(mut names) = known_parts(remaining, known_objects) {
+                names.push(name);
+                return Some(names);
+            }
+        }
+    }
+    None
+}
+
+// Splits an object into its groups of touching cells
+fn parts(object: &[(i64, i64)]) -> Vec<Shape> {
+    let cells = object.iter().cloned().collect::<HashSet<_>>();
+    let mut visited = HashSet::new();
+    let mut parts = Vec::new();
+    for &start in object {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut part = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some((x, y)) = queue.pop_front() {
+            part.push((x, y));
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let neighbour = (x + dx, y + dy);
+                    if cells.contains(&neighbour) && visited.insert(neighbour) {
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+        parts.push(part);
+    }
+    parts
+}
+
+// Splits the alive cells into groups of interacting cells. Cells are given coordinates
+// relative to the first cell of their group, so objects wrapping around an edge stay in one piece.
+fn objects(grid: &dyn Grid) -> Vec<Shape> {
+    let width = grid.width();
+    let height = grid.height();
+    let topology = grid.topology();
+    let alive_cells = alive_cells(grid);
+    let alive_set = alive_cells.iter().cloned().collect::<HashSet<_>>();
+    let mut visited = HashSet::new();
+    let mut objects = Vec::new();
+    for &start in &alive_cells {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut object = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((start, (0, 0)));
+        while let Some((position, (x, y))) = queue.pop_front() {
+            object.push((x, y));
+            for dy in -INTERACTION_RADIUS..=INTERACTION_RADIUS {
+                for dx in -INTERACTION_RADIUS..=INTERACTION_RADIUS {
+                    let neighbour = topology.resolve(
+                        width,
+                        height,
+                        i64::from(position.x) + dx,
+                        i64::from(position.y) + dy,
+                    );
+                    if let Some(neighbour) = neighbour {
+                        if alive_set.contains(&neighbour) && visited.insert(neighbour) {
+                            queue.push_back((neighbour, (x + dx, y + dy)));
+                        }
+                    }
+                }
+            }
+        }
+        objects.push(object);
+    }
+    objects
+}
+
+fn parse_shape(rows: &[&str]) -> Shape {
+    rows.iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|&(_, character)| character == 'O')
+                .map(move |(x, _)| (x as i64, y as i64))
+        })
+        .collect()
+}
+
+type Transformation = fn((i64, i64)) -> (i64, i64);
+
+// The smallest of the shape's eight rotations and reflections, moved to the origin
+fn canonical_shape(shape: &[(i64, i64)]) -> Shape {
+    let transformations: [Transformation; 8] = [
+        |(x, y)| (x, y),
+        |(x, y)| (-x, y),
+        |(x, y)| (x, -y),
+        |(x, y)| (-x, -y),
+        |(x, y)| (y, x),
+        |(x, y)| (-y, x),
+        |(x, y)| (y, -x),
+        |(x, y)| (-y, -x),
+    ];
+    transformations
+        .iter()
+        .map(|transform| normalize(shape.iter().cloned().map(transform).collect()))
+        .min()
+        .unwrap_or_default()
+}
+
+fn normalize(mut shape: Shape) -> Shape {
+    let min_x = shape.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = shape.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    for cell in &mut shape {
+        *cell = (cell.0 - min_x, cell.1 - min_y);
+    }
+    shape.sort_by_key(|&(x, y)| (y, x));
+    shape
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generation_calculator::GenerationCalculatorImpl;
+    use crate::grid::{GridImpl, Position};
+    use crate::rule::Rule;
+    use crate::test_util::step;
+    use crate::topology::Topology;
+
+    fn place(grid: &mut GridImpl, rows: &[&str], x: u32, y: u32) {
+        for (cell_x, cell_y) in parse_shape(rows) {
+            grid.set_alive_at(Position {
+                x: x + cell_x as u32,
+                y: y + cell_y as u32,
+            });
+        }
+    }
+
+    fn counts(entries: &[(&str, u32)]) -> BTreeMap<String, u32> {
+        entries
+            .iter()
+            .map(|&(name, count)| (name.to_string(), count))
+            .collect()
+    }
+
+    #[test]
+    fn empty_board_has_no_objects() {
+        assert!(census(&GridImpl::new(10, 10)).is_empty());
+    }
+
+    #[test]
+    fn counts_objects_by_type() {
+        let mut grid = GridImpl::new(30, 30);
+        place(&mut grid, &["OO", "OO"], 1, 1);
+        place(&mut grid, &["OO", "OO"], 10, 1);
+        place(&mut grid, &["OOO"], 20, 2);
+        place(&mut grid, &[".OO.", "O..O", ".OO."], 1, 10);
+        place(&mut grid, &[".O.", "..O", "OOO"], 10, 10);
+
+        let expected = counts(&[("beehive", 1), ("blinker", 1), ("block", 2), ("glider", 1)]);
+        assert_eq!(expected, census(&grid));
+    }
+
+    #[test]
+    fn recognizes_rotated_and_reflected_objects() {
+        let mut grid = GridImpl::new(30, 30);
+        place(&mut grid, &[".O.", "O.O", ".OO"], 1, 1);
+        place(&mut grid, &[".OO", "O.O", ".O."], 10, 1);
+        place(&mut grid, &["..O.", ".O.O", "O..O", ".OO."], 20, 1);
+        place(&mut grid, &["O", "O", "O"], 1, 10);
+        place(&mut grid, &["OOO", "O..", ".O."], 10, 10);
+
+        let expected = counts(&[("blinker", 1), ("boat", 2), ("glider", 1), ("loaf", 1)]);
+        assert_eq!(expected, census(&grid));
+    }
+
+    #[test]
+    fn recognizes_every_phase_of_lightweight_spaceship() {
+        let mut grid = GridImpl::new(20, 10);
+        place(&mut grid, &[".O..O", "O....", "O...O", "OOOO."], 10, 3);
+        let calculator = GenerationCalculatorImpl::new(Rule::conway());
+        let expected = counts(&[("lightweight spaceship", 1)]);
+        for _ in 0..4 {
+            assert_eq!(expected, census(&grid));
+            step(&mut grid, &calculator);
+        }
+        assert_eq!(expected, census(&grid));
+    }
+
+    #[test]
+    fn merges_cells_within_interaction_radius() {
+        let mut grid = GridImpl::new(20, 20);
+        place(&mut grid, &["OO.O", "OO.O"], 1, 1);
+        place(&mut grid, &["OO..OO", "OO..OO"], 1, 10);
+
+        let expected = counts(&[("block", 2), ("unidentified 6-cell object", 1)]);
+        assert_eq!(expected, census(&grid));
+    }
+
+    #[test]
+    fn recognizes_every_phase_of_traffic_light() {
+        let mut grid = GridImpl::new(20, 20);
+        place(
+            &mut grid,
+            &[
+                "..OOO..", ".......", "O.....O", "O.....O", "O.....O", ".......", "..OOO..",
+            ],
+            5,
+            5,
+        );
+        let calculator = GenerationCalculatorImpl::new(Rule::conway());
+        let expected = counts(&[("traffic light", 1)]);
+        assert_eq!(expected, census(&grid));
+        step(&mut grid, &calculator);
+        assert_eq!(expected, census(&grid));
+    }
+
+    #[test]
+    fn separates_objects_that_only_come_close() {
+        let mut grid = GridImpl::new(30, 30);
+        place(&mut grid, &["OOO.", "....", "...O", "...O", "...O"], 1, 1);
+        place(&mut grid, &["OO.OO", "OO.OO"], 10, 1);
+        place(&mut grid, &["OO..", "OO..", "....", "..OO", "..OO"], 20, 1);
+        place(
+            &mut grid,
+            &[
+                "..OOO..", ".......", "O.....O", "O.....O", "O.....O", ".......", "..OOO..",
+                ".......", "OO.....", "OO.....",
+            ],
+            1,
+            10,
+        );
+        place(
+            &mut grid,
+            &["OO..", "O...", "...O", "..OO", "....", "OOO."],
+            20,
+            10,
+        );
+
+        let expected = counts(&[
+            ("beacon", 1),
+            ("blinker", 3),
+            ("block", 5),
+            ("traffic light", 1),
+        ]);
+        assert_eq!(expected, census(&grid));
+    }
+
+    #[test]
+    fn keeps_objects_wrapping_around_torus_together() {
+        let mut grid = GridImpl::with_topology(10, 10, Topology::Torus);
+        place(&mut grid, &["O", "O"], 0, 4);
+        place(&mut grid, &["O", "O"], 9, 4);
+
+        assert_eq!(counts(&[("block", 1)]), census(&grid));
+    }
+}
@@ -1,4 +1,6 @@
 use crate::grid::Position;
+use std::cell::RefCell;
+use std::rc::Rc;
 #[cfg(test)]
 extern crate mockers;
 #[cfg(test)]
@@ -6,14 +8,31 @@ use mockers_derive::mocked;
 
 #[cfg_attr(test, mocked)]
 pub trait CoordinateTranslator {
-    fn to_local(&self, position: &Position) -> Option<Position>;
+    fn to_local(&self, position: &Position) -> Option<Point>;
 }
 
-#[derive(Debug, Clone)]
+// A position on the board, which lies between whole pixels at fractional scales
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl From<Position> for Point {
+    fn from(position: Position) -> Self {
+        Point {
+            x: f64::from(position.x),
+            y: f64::from(position.y),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rect {
-    pub origin: Position,
-    pub width: u32,
-    pub height: u32,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
 }
 
 #[cfg_attr(test, mocked)]
@@ -22,36 +41,45 @@ pub trait ViewInfo {
     fn client_rect(&self) -> Rect;
 }
 
-#[allow(dead_code)]
+// Lets the view info be changed while translators and presenters hold on to it
+impl<T: ViewInfo> ViewInfo for Rc<RefCell<T>> {
+    fn view_rect(&self) -> Rect {
+        self.borrow().view_rect()
+    }
+
+    fn client_rect(&self) -> Rect {
+        self.borrow().client_rect()
+    }
+}
+
 pub struct ScalingCoordinateTranslator {
-    view_info: Box<ViewInfo>,
+    view_info: Box<dyn ViewInfo>,
 }
 
 impl ScalingCoordinateTranslator {
-    #[allow(dead_code)]
     pub fn new(view_info: Box<dyn ViewInfo>) -> Self {
         ScalingCoordinateTranslator { view_info }
     }
 }
 
 impl CoordinateTranslator for ScalingCoordinateTranslator {
-    fn to_local(&self, position: &Position) -> Option<Position> {
+    fn to_local(&self, position: &Position) -> Option<Point> {
         let view_rect = self.view_info.view_rect();
         let client_rect = self.view_info.client_rect();
         let x_scale = client_rect.width / view_rect.width;
         let y_scale = client_rect.height / view_rect.height;
-        let local_x: i32 = (position.x as i32 - view_rect.origin.x as i32) * x_scale as i32;
-        let local_y: i32 = (position.y as i32 - view_rect.origin.y as i32) * y_scale as i32;
-        if local_x < 0
-            || local_y < 0
-            || local_x > client_rect.width as i32
-            || local_y > client_rect.height as i32
+        let local_x = (f64::from(position.x) - view_rect.x) * x_scale;
+        let local_y = (f64::from(position.y) - view_rect.y) * y_scale;
+        if local_x < 0.0
+            || local_y < 0.0
+            || local_x >= client_rect.width
+            || local_y >= client_rect.height
         {
             None
         } else {
-            Some(Position {
-                x: local_x as u32,
-                y: local_y as u32,
+            Some(Point {
+                x: local_x,
+                y: local_y,
             })
         }
     }
@@ -60,8 +88,8 @@ impl CoordinateTranslator for ScalingCoordinateTranslator {
 pub struct IdentityCoordinateTranslator;
 
 impl CoordinateTranslator for IdentityCoordinateTranslator {
-    fn to_local(&self, position: &Position) -> Option<Position> {
-        Some(*position)
+    fn to_local(&self, position: &Position) -> Option<Point> {
+        Some(Point::from(*position))
     }
 }
 
@@ -71,46 +99,77 @@ mod test {
     use crate::grid::Position;
     use mockers::Scenario;
 
-    fn create_mock() -> (Scenario, ViewInfoMock) {
+    fn create_mock(view_width: f64) -> (Scenario, ViewInfoMock) {
         let scenario = Scenario::new();
-        let view_info = scenario.create_mock_for::<ViewInfo>();
-        scenario.expect(view_info.view_rect_call().and_return(Rect {
-            width: 1000,
-            height: 1000,
-            origin: Position { x: 771, y: 0 },
+        let (view_info, view_info_handle) = scenario.create_mock_for::<dyn ViewInfo>();
+        scenario.expect(view_info_handle.view_rect().and_return(Rect {
+            width: view_width,
+            height: 1000.0,
+            x: 771.0,
+            y: 0.0,
         }));
-        scenario.expect(view_info.client_rect_call().and_return(Rect {
-            width: 1000,
-            height: 1000,
-            origin: Position { x: 0, y: 0 },
+        scenario.expect(view_info_handle.client_rect().and_return(Rect {
+            width: 1000.0,
+            height: 1000.0,
+            x: 0.0,
+            y: 0.0,
         }));
         (scenario, view_info)
     }
 
     #[test]
     fn converts_to_local() {
-        let (_scenario, view_info) = create_mock();
+        let (_scenario, view_info) = create_mock(1000.0);
         let coordinate_translator = ScalingCoordinateTranslator::new(Box::new(view_info));
         let global = Position { x: 772, y: 7 };
         let local = coordinate_translator.to_local(&global);
-        let expected = Some(Position { x: 1, y: 7 });
+        let expected = Some(Point { x: 1.0, y: 7.0 });
         assert_eq!(expected, local);
     }
 
     #[test]
     fn converts_to_out_of_bounds_local() {
-        let (_scenario, view_info) = create_mock();
+        let (_scenario, view_info) = create_mock(1000.0);
         let coordinate_translator = ScalingCoordinateTranslator::new(Box::new(view_info));
         let global = Position { x: 3, y: 3 };
         let local = coordinate_translator.to_local(&global);
         assert_eq!(None, local);
     }
 
+    #[test]
+    fn converts_with_fractional_scale() {
+        let (_scenario, view_info) = create_mock(600.0);
+        let coordinate_translator = ScalingCoordinateTranslator::new(Box::new(view_info));
+        let global = Position { x: 1071, y: 7 };
+        let local = coordinate_translator.to_local(&global);
+        let expected = Some(Point { x: 500.0, y: 7.0 });
+        assert_eq!(expected, local);
+    }
+
+    #[test]
+    fn keeps_fraction_of_scaled_position() {
+        let (_scenario, view_info) = create_mock(400.0);
+        let coordinate_translator = ScalingCoordinateTranslator::new(Box::new(view_info));
+        let global = Position { x: 772, y: 7 };
+        let local = coordinate_translator.to_local(&global);
+        let expected = Some(Point { x: 2.5, y: 7.0 });
+        assert_eq!(expected, local);
+    }
+
+    #[test]
+    fn converts_far_edge_to_out_of_bounds_local() {
+        let (_scenario, view_info) = create_mock(1000.0);
+        let coordinate_translator = ScalingCoordinateTranslator::new(Box::new(view_info));
+        let global = Position { x: 1771, y: 7 };
+        let local = coordinate_translator.to_local(&global);
+        assert_eq!(None, local);
+    }
+
     #[test]
     fn entity_returns_itself() {
         let coordinate_translator = IdentityCoordinateTranslator;
-        let expected = Position { x: 10, y: 20 };
-        let actual = coordinate_translator.to_local(&expected);
-        assert_eq!(Some(expected), actual);
+        let position = Position { x: 10, y: 20 };
+        let actual = coordinate_translator.to_local(&position);
+        assert_eq!(Some(Point { x: 10.0, y: 20.0 }), actual);
     }
 }
@@ -1,66 +1,475 @@
 extern crate wasm_bindgen;
+use crate::bit_grid::{BitGenerationCalculator, BitGrid};
+use crate::camera::Camera;
 use crate::canvas::constant;
 use crate::canvas::presenter::CanvasPresenter;
 use crate::canvas::view::js;
+use crate::canvas::view::js::console;
 use crate::canvas::view::CanvasViewImpl;
-use crate::coordinate_translator::IdentityCoordinateTranslator;
-use crate::generation_calculator::GenerationCalculatorImpl;
-use crate::grid::GridImpl;
+use crate::coordinate_translator::ScalingCoordinateTranslator;
+use crate::generation_calculator::GenerationCalculator;
+use crate::generations::{GenerationsCalculator, GenerationsRule};
+use crate::grid::{Grid, GridImpl, Position};
 use crate::grid_info::GridInfo;
-use crate::input_handler::{ClickableInputHandler, ClickableInputHandlerImpl};
+use crate::hashlife::HashLifeCalculator;
+use crate::hensel::{HenselGenerationCalculator, HenselRule};
+use crate::infinite_grid::{
+    Coordinate, InfiniteGenerationCalculator, InfiniteGrid, PlaneCells, Viewport,
+};
+use crate::input_handler::{ClickableInputHandler, ClickableInputHandlerImpl, PointerInputHandler};
 use crate::interactive_game::InteractiveGameImpl;
+use crate::larger_than_life::{LargerThanLifeCalculator, LargerThanLifeRule};
+use crate::neighbourhood::{Neighbourhood, NeighbourhoodGenerationCalculator, NeighbourhoodRule};
+use crate::pattern::{self, rle};
+use crate::rule::Rule;
+use crate::rule_table::{self, Colour, RuleTable, RuleTableCalculator};
+use crate::soup::{self, Soup, Symmetry};
+use crate::topology::{GridSpec, Topology};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
 use wasm_bindgen::prelude::*;
 
+const COLUMNS: u32 = 100;
+const ROWS: u32 = 100;
+const LIFE_CELLS: CellStyle = CellStyle {
+    states: 2,
+    neighbourhood: Neighbourhood::Moore,
+    colours: Vec::new(),
+};
+
+// How the cells of a rule are drawn and hit
+struct CellStyle {
+    states: u8,
+    neighbourhood: Neighbourhood,
+    colours: Vec<(u8, Colour)>,
+}
+
 #[wasm_bindgen]
 pub struct EntryPoint {
-    input_handler: Box<ClickableInputHandler>,
+    input_handler: ClickableInputHandlerImpl,
 }
 
 #[wasm_bindgen]
 impl EntryPoint {
     pub fn new(canvas: js::HTMLCanvasElement) -> Self {
-        let grid_info = GridInfo {
-            width: constant::CANVAS_WIDTH,
-            height: constant::CANVAS_HEIGHT,
-            rows: 100,
-            columns: 100,
+        EntryPoint::with_topology(canvas, COLUMNS, ROWS, Topology::Bounded)
+    }
+
+    // Every cell needs at least a pixel of the canvas
+    pub fn with_grid_spec(canvas: js::HTMLCanvasElement, grid_spec: &str) -> Self {
+        let description = format!("grid spec '{}'", grid_spec);
+        let grid_spec: GridSpec = match parse(grid_spec, &description) {
+            Some(grid_spec) => grid_spec,
+            None => return EntryPoint::new(canvas),
         };
-        let context = canvas.get_context("2d");
-        let view = Box::new(CanvasViewImpl::new(context));
-        let presenter = Box::new(CanvasPresenter::new(view, grid_info.clone()));
-        let generation_calculator = Box::new(GenerationCalculatorImpl::new());
-        let grid = Box::new(GridImpl::new(grid_info.columns, grid_info.rows));
-        let game = Box::new(InteractiveGameImpl::new(
-            grid,
-            generation_calculator,
-            presenter,
-        ));
-        let coordinate_translator = Box::new(IdentityCoordinateTranslator);
-        let input_handler = Box::new(ClickableInputHandlerImpl::new(
-            game,
-            coordinate_translator,
-            grid_info,
-        ));
-        EntryPoint { input_handler }
+        if grid_spec.width > constant::CANVAS_WIDTH || grid_spec.height > constant::CANVAS_HEIGHT {
+            log_invalid(
+                &description,
+                &format!(
+                    "the canvas fits at most {} by {} cells",
+                    constant::CANVAS_WIDTH,
+                    constant::CANVAS_HEIGHT
+                ),
+            );
+            return EntryPoint::new(canvas);
+        }
+        EntryPoint::with_topology(
+            canvas,
+            grid_spec.width,
+            grid_spec.height,
+            grid_spec.topology,
+        )
+    }
+
+    pub fn with_generations_rule(canvas: js::HTMLCanvasElement, rule: &str) -> Self {
+        let rule: GenerationsRule = match parse(rule, &format!("Generations rule '{}'", rule)) {
+            Some(rule) => rule,
+            None => return EntryPoint::new(canvas),
+        };
+        let states = rule.states();
+        let grid = GridImpl::new(COLUMNS, ROWS);
+        let generation_calculator = GenerationsCalculator::new(rule);
+        EntryPoint::with_game_parts(
+            canvas,
+            Box::new(grid),
+            Box::new(generation_calculator),
+            CellStyle {
+                states,
+                neighbourhood: Neighbourhood::Moore,
+                colours: Vec::new(),
+            },
+        )
+    }
+
+    pub fn with_hensel_rule(canvas: js::HTMLCanvasElement, rule: &str) -> Self {
+        let rule: HenselRule = match parse(rule, &format!("non-totalistic rule '{}'", rule)) {
+            Some(rule) => rule,
+            None => return EntryPoint::new(canvas),
+        };
+        let grid = GridImpl::new(COLUMNS, ROWS);
+        let generation_calculator = HenselGenerationCalculator::new(rule);
+        EntryPoint::with_game_parts(
+            canvas,
+            Box::new(grid),
+            Box::new(generation_calculator),
+            LIFE_CELLS,
+        )
+    }
+
+    pub fn with_larger_than_life_rule(canvas: js::HTMLCanvasElement, rule: &str) -> Self {
+        let rule: LargerThanLifeRule =
+            match parse(rule, &format!("Larger than Life rule '{}'", rule)) {
+                Some(rule) => rule,
+                None => return EntryPoint::new(canvas),
+            };
+        let states = rule.states();
+        let grid = GridImpl::new(COLUMNS, ROWS);
+        let generation_calculator = LargerThanLifeCalculator::new(rule);
+        EntryPoint::with_game_parts(
+            canvas,
+            Box::new(grid),
+            Box::new(generation_calculator),
+            CellStyle {
+                states,
+                neighbourhood: Neighbourhood::Moore,
+                colours: Vec::new(),
+            },
+        )
+    }
+
+    // A trailing 'H' plays the rule on hexagonal cells, a trailing 'V' in the von Neumann neighbourhood
+    pub fn with_neighbourhood_rule(canvas: js::HTMLCanvasElement, rule: &str) -> Self {
+        let rule: NeighbourhoodRule = match parse(rule, &format!("rule '{}'", rule)) {
+            Some(rule) => rule,
+            None => return EntryPoint::new(canvas),
+        };
+        let neighbourhood = rule.neighbourhood();
+        let grid = GridImpl::new(COLUMNS, ROWS);
+        let generation_calculator = NeighbourhoodGenerationCalculator::new(rule);
+        EntryPoint::with_game_parts(
+            canvas,
+            Box::new(grid),
+            Box::new(generation_calculator),
+            CellStyle {
+                states: LIFE_CELLS.states,
+                neighbourhood,
+                colours: Vec::new(),
+            },
+        )
+    }
+
+    // The text of a Golly .rule file, e.g. rule_table::WIREWORLD
+    pub fn with_rule_table(canvas: js::HTMLCanvasElement, rule_table: &str) -> Self {
+        let rule_table: RuleTable = match parse(rule_table, "rule table") {
+            Some(rule_table) => rule_table,
+            None => return EntryPoint::new(canvas),
+        };
+        let cell_style = CellStyle {
+            states: rule_table.states(),
+            neighbourhood: rule_table.neighbourhood(),
+            colours: rule_table.colours().to_vec(),
+        };
+        let grid = GridImpl::new(COLUMNS, ROWS);
+        let generation_calculator = RuleTableCalculator::new(rule_table);
+        EntryPoint::with_game_parts(
+            canvas,
+            Box::new(grid),
+            Box::new(generation_calculator),
+            cell_style,
+        )
+    }
+
+    pub fn with_wireworld(canvas: js::HTMLCanvasElement) -> Self {
+        EntryPoint::with_rule_table(canvas, rule_table::WIREWORLD)
+    }
+
+    pub fn with_infinite_plane(canvas: js::HTMLCanvasElement) -> Self {
+        let grid = InfiniteGrid::new(EntryPoint::viewport());
+        let rule = Rule::default();
+        let generation_calculator = match InfiniteGenerationCalculator::new(&grid, rule) {
+            Ok(generation_calculator) => generation_calculator,
+            Err(error) => {
+                log_invalid("rule for the infinite plane", &error);
+                return EntryPoint::new(canvas);
+            }
+        };
+        EntryPoint::with_infinite_grid(canvas, grid, Box::new(generation_calculator))
+    }
+
+    pub fn with_hashlife(canvas: js::HTMLCanvasElement, step_exponent: u32) -> Self {
+        let viewport = EntryPoint::viewport();
+        let rule = Rule::default();
+        let generation_calculator = match HashLifeCalculator::new(viewport, rule, step_exponent) {
+            Ok(generation_calculator) => generation_calculator,
+            Err(error) => {
+                log_invalid("HashLife settings", &error);
+                return EntryPoint::new(canvas);
+            }
+        };
+        let grid = generation_calculator.grid();
+        EntryPoint::with_infinite_grid(canvas, grid, Box::new(generation_calculator))
     }
 
     #[wasm_bindgen]
     pub fn on_click(&mut self, x: u32, y: u32) {
-        (self as &mut dyn ClickableInputHandler).on_click(x, y);
+        self.input_handler.on_click(x, y);
     }
 
     #[wasm_bindgen]
-    pub fn on_timer(&mut self) {
-        (self as &mut dyn ClickableInputHandler).on_timer();
+    pub fn on_pointer_down(&mut self, x: u32, y: u32) {
+        self.input_handler.on_pointer_down(x, y);
+    }
+
+    #[wasm_bindgen]
+    pub fn on_pointer_move(&mut self, x: u32, y: u32) {
+        self.input_handler.on_pointer_move(x, y);
+    }
+
+    #[wasm_bindgen]
+    pub fn on_pointer_up(&mut self) {
+        self.input_handler.on_pointer_up();
+    }
+
+    #[wasm_bindgen]
+    pub fn on_zoom(&mut self, x: u32, y: u32, factor: f64) {
+        self.input_handler.on_zoom(x, y, factor);
+    }
+
+    #[wasm_bindgen]
+    pub fn on_pan(&mut self, dx: i32, dy: i32) {
+        self.input_handler.on_pan(dx, dy);
+    }
+
+    // Shows another part of the plane on infinite boards, which can be undone like an edit
+    #[wasm_bindgen]
+    pub fn move_viewport(&mut self, columns: i32, rows: i32) {
+        self.input_handler.on_move_viewport(columns, rows);
+    }
+
+    // Clicked and painted cells take this state, or die if they already had it
+    #[wasm_bindgen]
+    pub fn select_state(&mut self, state: u8) {
+        self.input_handler.on_select_state(state);
+    }
+
+    // False once the generation counter cannot count any further
+    #[wasm_bindgen]
+    pub fn on_timer(&mut self) -> bool {
+        match self.input_handler.on_timer() {
+            Ok(()) => true,
+            Err(error) => {
+                console::log(&format!("Failed to advance: {}", error));
+                false
+            }
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn load_pattern(&mut self, pattern: &str, x: u32, y: u32) -> bool {
+        match pattern::parse(pattern) {
+            Ok(pattern) => {
+                self.input_handler
+                    .game_mut()
+                    .place_pattern(&pattern, &Position { x, y });
+                true
+            }
+            Err(error) => {
+                console::log(&format!("Failed to load pattern: {}", error));
+                false
+            }
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn clear(&mut self) {
+        self.input_handler.game_mut().clear();
+    }
+
+    #[wasm_bindgen]
+    pub fn randomise(&mut self, seed: u32, density: f64, symmetry: &str) -> bool {
+        if !soup::is_valid_density(density) {
+            console::log(&format!(
+                "Failed to randomise board: density {} is not between 0 and 1",
+                density
+            ));
+            return false;
+        }
+        match symmetry.parse::<Symmetry>() {
+            Ok(symmetry) => {
+                self.input_handler
+                    .on_randomise(&Soup::new(u64::from(seed), density, symmetry));
+                true
+            }
+            Err(error) => {
+                console::log(&format!("Failed to randomise board: {}", error));
+                false
+            }
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn export_rle(&self) -> String {
+        rle::write(&self.input_handler.game().to_pattern())
+    }
+
+    #[wasm_bindgen]
+    pub fn undo(&mut self) {
+        self.input_handler.game_mut().undo();
+    }
+
+    #[wasm_bindgen]
+    pub fn redo(&mut self) {
+        self.input_handler.game_mut().redo();
+    }
+
+    #[wasm_bindgen]
+    pub fn step_back(&mut self, generations: u32) {
+        self.input_handler
+            .game_mut()
+            .step_back(u64::from(generations));
+    }
+
+    // Generations are passed as JavaScript numbers, which hold them exactly up to 2^53
+    #[wasm_bindgen]
+    pub fn jump_to_generation(&mut self, generation: f64) {
+        self.input_handler
+            .game_mut()
+            .jump_to_generation(generation as u64);
+    }
+
+    #[wasm_bindgen]
+    pub fn generation(&self) -> f64 {
+        self.input_handler.game().timeline_position().current as f64
+    }
+
+    #[wasm_bindgen]
+    pub fn earliest_generation(&self) -> f64 {
+        self.input_handler.game().timeline_position().earliest as f64
+    }
+
+    #[wasm_bindgen]
+    pub fn latest_generation(&self) -> f64 {
+        self.input_handler.game().timeline_position().latest as f64
+    }
+
+    // Empty as long as the board has not settled
+    #[wasm_bindgen]
+    pub fn stabilisation(&self) -> String {
+        self.input_handler
+            .game()
+            .stabilisation()
+            .map(|stabilisation| stabilisation.to_string())
+            .unwrap_or_default()
     }
 }
 
-impl ClickableInputHandler for EntryPoint {
-    fn on_click(&mut self, x: u32, y: u32) {
-        self.input_handler.on_click(x, y);
+impl EntryPoint {
+    fn viewport() -> Viewport {
+        Viewport {
+            origin: Coordinate { x: 0, y: 0 },
+            width: COLUMNS,
+            height: ROWS,
+        }
+    }
+
+    fn with_topology(
+        canvas: js::HTMLCanvasElement,
+        columns: u32,
+        rows: u32,
+        topology: Topology,
+    ) -> Self {
+        let grid = BitGrid::with_topology(columns, rows, topology);
+        let generation_calculator = BitGenerationCalculator::new(&grid, Rule::default());
+        EntryPoint::with_game_parts(
+            canvas,
+            Box::new(grid),
+            Box::new(generation_calculator),
+            LIFE_CELLS,
+        )
+    }
+
+    fn with_infinite_grid<P: PlaneCells + 'static>(
+        canvas: js::HTMLCanvasElement,
+        grid: InfiniteGrid<P>,
+        generation_calculator: Box<dyn GenerationCalculator>,
+    ) -> Self {
+        let viewport = grid.shared_viewport();
+        let input_handler = EntryPoint::input_handler(
+            canvas,
+            Box::new(grid),
+            generation_calculator,
+            LIFE_CELLS,
+            Some(viewport),
+        );
+        EntryPoint { input_handler }
     }
 
-    fn on_timer(&mut self) {
-        self.input_handler.on_timer();
+    fn with_game_parts(
+        canvas: js::HTMLCanvasElement,
+        grid: Box<dyn Grid>,
+        generation_calculator: Box<dyn GenerationCalculator>,
+        cell_style: CellStyle,
+    ) -> Self {
+        let input_handler =
+            EntryPoint::input_handler(canvas, grid, generation_calculator, cell_style, None);
+        EntryPoint { input_handler }
+    }
+
+    fn input_handler(
+        canvas: js::HTMLCanvasElement,
+        grid: Box<dyn Grid>,
+        generation_calculator: Box<dyn GenerationCalculator>,
+        cell_style: CellStyle,
+        viewport: Option<Rc<RefCell<Viewport>>>,
+    ) -> ClickableInputHandlerImpl {
+        let grid_info = GridInfo {
+            width: constant::CANVAS_WIDTH,
+            height: constant::CANVAS_HEIGHT,
+            rows: grid.height(),
+            columns: grid.width(),
+        };
+        let camera = Rc::new(RefCell::new(Camera::new(&grid_info)));
+        let context = canvas.get_context("2d");
+        let view = Box::new(CanvasViewImpl::new(context));
+        let presenter = Box::new(
+            CanvasPresenter::new(view, grid_info.clone())
+                .with_view_info(Box::new(camera.clone()))
+                .with_state_count(cell_style.states)
+                .with_state_colours(&cell_style.colours)
+                .with_neighbourhood(cell_style.neighbourhood),
+        );
+        let mut game = InteractiveGameImpl::new(grid, generation_calculator, presenter);
+        if let Some(viewport) = viewport {
+            game = game.with_viewport(viewport);
+        }
+        let coordinate_translator =
+            Box::new(ScalingCoordinateTranslator::new(Box::new(camera.clone())));
+        ClickableInputHandlerImpl::new(Box::new(game), coordinate_translator, grid_info)
+            .with_camera(camera)
+            .with_neighbourhood(cell_style.neighbourhood)
     }
 }
+
+// Invalid settings are logged and the caller falls back to the default Life board
+fn parse<T>(text: &str, description: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    match text.parse() {
+        Ok(value) => Some(value),
+        Err(error) => {
+            log_invalid(description, &error);
+            None
+        }
+    }
+}
+
+fn log_invalid(description: &str, error: &dyn fmt::Display) {
+    console::log(&format!(
+        "Invalid {}: {}, playing Life instead",
+        description, error
+    ));
+}
@@ -3,25 +3,47 @@ extern crate mockers;
 #[cfg(test)]
 use mockers_derive::mocked;
 
-use crate::grid::{Grid, Position};
+use crate::grid::{Grid, Position, ALIVE, DEAD};
+use crate::neighbourhood::{self, Neighbourhood};
+use crate::rule::Rule;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Change {
     pub position: Position,
-    pub is_alive: bool,
+    pub state: u8,
+}
+
+impl Change {
+    pub fn is_alive(&self) -> bool {
+        self.state == ALIVE
+    }
 }
 
 #[cfg_attr(test, mocked)]
 pub trait GenerationCalculator {
-    fn next_generation(&self, grid: &Grid) -> Vec<Change>;
+    // The mocks record their arguments, which needs the grid's type to outlive the call
+    fn next_generation(&self, grid: &(dyn Grid + 'static)) -> Vec<Change>;
+    fn accept_changes(&self, _changes: &[Change]) {}
+
+    // How many generations every call of next_generation advances
+    fn generations_per_step(&self) -> u32 {
+        1
+    }
+
+    // Calculators of boards larger than the grid keep the cells beyond it themselves.
+    // They are told when the game returns to another generation or clears the board.
+    fn restore_generation(&self, _generation: u64) {}
+    fn clear(&self) {}
 }
 
 #[derive(Debug)]
-pub struct GenerationCalculatorImpl;
+pub struct GenerationCalculatorImpl {
+    rule: Rule,
+}
 
 impl GenerationCalculatorImpl {
-    pub fn new() -> Self {
-        GenerationCalculatorImpl
+    pub fn new(rule: Rule) -> Self {
+        GenerationCalculatorImpl { rule }
     }
 }
 impl GenerationCalculator for GenerationCalculatorImpl {
@@ -29,19 +51,8 @@ impl GenerationCalculator for GenerationCalculatorImpl {
         let mut changes = Vec::new();
         for y in 0..grid.height() {
             for x in 0..grid.width() {
-                let position = Position { x, y };
-                let neighbours = count_neighbours_at(grid, position).expect("x or y out of bounds");
-                let is_alive = grid.is_alive_at(position);
-                if is_alive && (neighbours < 2 || neighbours > 3) {
-                    changes.push(Change {
-                        position,
-                        is_alive: false,
-                    });
-                } else if !is_alive && neighbours == 3 {
-                    changes.push(Change {
-                        position,
-                        is_alive: true,
-                    })
+                if let Some(change) = change_at(grid, &self.rule, Position { x, y }) {
+                    changes.push(change);
                 }
             }
         }
@@ -49,88 +60,98 @@ impl GenerationCalculator for GenerationCalculatorImpl {
     }
 }
 
-fn count_neighbours_at(grid: &dyn Grid, position: Position) -> Option<u32> {
-    if position.x >= grid.width() || position.y >= grid.height() {
-        return None;
+// Asks for the next state of every cell row by row and collects the cells it differs for
+pub fn changes_by_cell<F>(grid: &dyn Grid, mut next_state: F) -> Vec<Change>
+where
+    F: FnMut(Position, u8) -> u8,
+{
+    let mut changes = Vec::new();
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let position = Position { x, y };
+            let state = grid.state_at(position);
+            let next_state = next_state(position, state);
+            if next_state != state {
+                changes.push(Change {
+                    position,
+                    state: next_state,
+                });
+            }
+        }
     }
+    changes
+}
 
-    let top = position.y == 0;
-    let right = position.x == grid.width() - 1;
-    let bottom = position.y == grid.height() - 1;
-    let left = position.x == 0;
-
-    let mut neighbours = 0;
-    if !top && grid.is_alive_at(Position {
-        x: position.x,
-        y: position.y - 1,
-    }) {
-        neighbours += 1;
-    }
-    if !top && !right && grid.is_alive_at(Position {
-        x: position.x + 1,
-        y: position.y - 1,
-    }) {
-        neighbours += 1;
-    }
-    if !right && grid.is_alive_at(Position {
-        x: position.x + 1,
-        y: position.y,
-    }) {
-        neighbours += 1;
-    }
-    if !right && !bottom && grid.is_alive_at(Position {
-        x: position.x + 1,
-        y: position.y + 1,
-    }) {
-        neighbours += 1;
-    }
-    if !bottom && grid.is_alive_at(Position {
-        x: position.x,
-        y: position.y + 1,
-    }) {
-        neighbours += 1;
-    }
-    if !left && !bottom && grid.is_alive_at(Position {
-        x: position.x - 1,
-        y: position.y + 1,
-    }) {
-        neighbours += 1;
-    }
-    if !left && grid.is_alive_at(Position {
-        x: position.x - 1,
-        y: position.y,
-    }) {
-        neighbours += 1;
+pub fn change_at(grid: &dyn Grid, rule: &Rule, position: Position) -> Option<Change> {
+    let neighbours = count_neighbours_at(grid, position).expect("x or y out of bounds");
+    let is_alive = grid.is_alive_at(position);
+    if is_alive && !rule.survives(neighbours) {
+        Some(Change {
+            position,
+            state: DEAD,
+        })
+    } else if !is_alive && rule.is_born(neighbours) {
+        Some(Change {
+            position,
+            state: ALIVE,
+        })
+    } else {
+        None
     }
-    if !left && !top && grid.is_alive_at(Position {
-        x: position.x - 1,
-        y: position.y - 1,
-    }) {
-        neighbours += 1;
-    }
-    Some(neighbours)
+}
+
+pub const NEIGHBOUR_OFFSETS: [(i64, i64); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+pub fn count_neighbours_at(grid: &dyn Grid, position: Position) -> Option<u32> {
+    neighbourhood::count_neighbours_at(grid, position, Neighbourhood::Moore)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::grid::{GridMock, Position};
+    use crate::grid::{GridImpl, GridMock, GridMockHandle, Position};
+    use crate::topology::{Topology, TwistedEdges};
     use mockers::matchers::*;
     use mockers::Scenario;
 
-    fn create_mock_with_size(width: u32, height: u32) -> (Scenario, GridMock) {
+    fn create_mock_with_size(width: u32, height: u32) -> (Scenario, GridMock, GridMockHandle) {
         let scenario = Scenario::new();
-        let grid = scenario.create_mock_for::<Grid>();
-        scenario.expect(grid.width_call().and_return_clone(width).times(..));
-        scenario.expect(grid.height_call().and_return_clone(height).times(..));
-        scenario.expect(grid.is_alive_at_call(ANY).and_return_clone(false).times(..));
-        (scenario, grid)
+        let (grid, grid_handle) = scenario.create_mock_for::<dyn Grid>();
+        scenario.expect(grid_handle.width().and_return_clone(width).times(..));
+        scenario.expect(grid_handle.height().and_return_clone(height).times(..));
+        scenario.expect(
+            grid_handle
+                .topology()
+                .and_return_clone(Topology::Bounded)
+                .times(..),
+        );
+        scenario.expect(
+            grid_handle
+                .is_alive_at(ANY)
+                .and_return_clone(false)
+                .times(..),
+        );
+        (scenario, grid, grid_handle)
     }
 
-    fn set_grid_alive_at(scenario: &Scenario, grid: &GridMock, positions: &[Position]) {
+    fn set_grid_alive_at(
+        scenario: &Scenario,
+        grid_handle: &GridMockHandle,
+        positions: &[Position],
+    ) {
         for position in positions {
             scenario.expect(
-                grid.is_alive_at_call(*position)
+                grid_handle
+                    .is_alive_at(*position)
                     .and_return_clone(true)
                     .times(..),
             );
@@ -139,9 +160,9 @@ mod test {
 
     #[test]
     fn dead_grid_stays_dead() {
-        let (_, grid) = create_mock_with_size(5, 4);
+        let (_, grid, _) = create_mock_with_size(5, 4);
 
-        let generation_calculator = GenerationCalculatorImpl::new();
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         assert_eq!(0, changes.len());
@@ -149,64 +170,64 @@ mod test {
 
     #[test]
     fn lone_alive_cell_dies() {
-        let (scenario, grid) = create_mock_with_size(5, 4);
-        set_grid_alive_at(&scenario, &grid, &[Position { x: 1, y: 1 }]);
+        let (scenario, grid, grid_handle) = create_mock_with_size(5, 4);
+        set_grid_alive_at(&scenario, &grid_handle, &[Position { x: 1, y: 1 }]);
 
-        let generation_calculator = GenerationCalculatorImpl::new();
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         assert_eq!(1, changes.len());
         let expected = Change {
             position: Position { x: 1, y: 1 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[0]);
     }
 
     #[test]
     fn alive_cell_in_corner_dies() {
-        let (scenario, grid) = create_mock_with_size(5, 4);
-        set_grid_alive_at(&scenario, &grid, &[Position { x: 0, y: 0 }]);
+        let (scenario, grid, grid_handle) = create_mock_with_size(5, 4);
+        set_grid_alive_at(&scenario, &grid_handle, &[Position { x: 0, y: 0 }]);
 
-        let generation_calculator = GenerationCalculatorImpl::new();
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         assert_eq!(1, changes.len());
         let expected = Change {
             position: Position { x: 0, y: 0 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[0]);
     }
 
     #[test]
     fn alive_cell_in_corner_with_single_neighbour_dies() {
-        let (scenario, grid) = create_mock_with_size(3, 3);
+        let (scenario, grid, grid_handle) = create_mock_with_size(3, 3);
         set_grid_alive_at(
             &scenario,
-            &grid,
+            &grid_handle,
             &[Position { x: 0, y: 0 }, Position { x: 1, y: 1 }],
         );
 
-        let generation_calculator = GenerationCalculatorImpl::new();
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         assert_eq!(2, changes.len());
         let expected = Change {
             position: Position { x: 0, y: 0 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[0]);
         let expected = Change {
             position: Position { x: 1, y: 1 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[1]);
     }
 
     #[test]
     fn dead_cell_with_three_neighbours_resurrects() {
-        let (scenario, grid) = create_mock_with_size(3, 3);
+        let (scenario, grid, grid_handle) = create_mock_with_size(3, 3);
         /*
          * O | . | .
          * O | O | .
@@ -214,7 +235,7 @@ mod test {
          */
         set_grid_alive_at(
             &scenario,
-            &grid,
+            &grid_handle,
             &[
                 Position { x: 0, y: 0 },
                 Position { x: 0, y: 1 },
@@ -222,27 +243,27 @@ mod test {
             ],
         );
 
-        let generation_calculator = GenerationCalculatorImpl {};
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         assert_eq!(1, changes.len());
         let expected = Change {
             position: Position { x: 1, y: 0 },
-            is_alive: true,
+            state: ALIVE,
         };
         assert_eq!(expected, changes[0]);
     }
 
     #[test]
     fn alive_cell_with_four_neighbours_dies() {
-        let (scenario, grid) = create_mock_with_size(3, 2);
+        let (scenario, grid, grid_handle) = create_mock_with_size(3, 2);
         /*
          * . | O | O
          * O | O | O
          */
         set_grid_alive_at(
             &scenario,
-            &grid,
+            &grid_handle,
             &[
                 Position { x: 1, y: 0 },
                 Position { x: 2, y: 0 },
@@ -252,37 +273,37 @@ mod test {
             ],
         );
 
-        let generation_calculator = GenerationCalculatorImpl {};
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         assert_eq!(3, changes.len());
         let expected = Change {
             position: Position { x: 0, y: 0 },
-            is_alive: true,
+            state: ALIVE,
         };
         assert_eq!(expected, changes[0]);
         let expected = Change {
             position: Position { x: 1, y: 0 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[1]);
         let expected = Change {
             position: Position { x: 1, y: 1 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[2]);
     }
 
     #[test]
     fn dead_cell_with_four_neighbours_stays_dead() {
-        let (scenario, grid) = create_mock_with_size(3, 2);
+        let (scenario, grid, grid_handle) = create_mock_with_size(3, 2);
         /*
          * O | O | O
          * O | . | O
          */
         set_grid_alive_at(
             &scenario,
-            &grid,
+            &grid_handle,
             &[
                 Position { x: 0, y: 0 },
                 Position { x: 1, y: 0 },
@@ -292,7 +313,7 @@ mod test {
             ],
         );
 
-        let generation_calculator = GenerationCalculatorImpl {};
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         assert_eq!(1, changes.len());
@@ -300,7 +321,7 @@ mod test {
 
     #[test]
     fn block_stays_block() {
-        let (scenario, grid) = create_mock_with_size(4, 4);
+        let (scenario, grid, grid_handle) = create_mock_with_size(4, 4);
         /*
          * . | . | . | .
          * . | O | O | .
@@ -309,7 +330,7 @@ mod test {
          */
         set_grid_alive_at(
             &scenario,
-            &grid,
+            &grid_handle,
             &[
                 Position { x: 1, y: 1 },
                 Position { x: 1, y: 2 },
@@ -318,7 +339,7 @@ mod test {
             ],
         );
 
-        let generation_calculator = GenerationCalculatorImpl {};
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         assert_eq!(0, changes.len());
@@ -326,7 +347,7 @@ mod test {
 
     #[test]
     fn blinker_period_one_becomes_period_two() {
-        let (scenario, grid) = create_mock_with_size(3, 3);
+        let (scenario, grid, grid_handle) = create_mock_with_size(3, 3);
         /*
          * . | . | .
          * O | O | O
@@ -334,7 +355,7 @@ mod test {
          */
         set_grid_alive_at(
             &scenario,
-            &grid,
+            &grid_handle,
             &[
                 Position { x: 0, y: 1 },
                 Position { x: 1, y: 1 },
@@ -342,7 +363,7 @@ mod test {
             ],
         );
 
-        let generation_calculator = GenerationCalculatorImpl {};
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         /*
@@ -353,29 +374,29 @@ mod test {
         assert_eq!(4, changes.len());
         let expected = Change {
             position: Position { x: 1, y: 0 },
-            is_alive: true,
+            state: ALIVE,
         };
         assert_eq!(expected, changes[0]);
         let expected = Change {
             position: Position { x: 0, y: 1 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[1]);
         let expected = Change {
             position: Position { x: 2, y: 1 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[2]);
         let expected = Change {
             position: Position { x: 1, y: 2 },
-            is_alive: true,
+            state: ALIVE,
         };
         assert_eq!(expected, changes[3]);
     }
 
     #[test]
     fn blinker_period_two_becomes_period_one() {
-        let (scenario, grid) = create_mock_with_size(3, 3);
+        let (scenario, grid, grid_handle) = create_mock_with_size(3, 3);
         /*
          * . | O | .
          * . | O | .
@@ -383,7 +404,7 @@ mod test {
          */
         set_grid_alive_at(
             &scenario,
-            &grid,
+            &grid_handle,
             &[
                 Position { x: 1, y: 0 },
                 Position { x: 1, y: 1 },
@@ -391,7 +412,7 @@ mod test {
             ],
         );
 
-        let generation_calculator = GenerationCalculatorImpl {};
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
         let changes = generation_calculator.next_generation(&grid);
 
         /*
@@ -402,23 +423,294 @@ mod test {
         assert_eq!(4, changes.len());
         let expected = Change {
             position: Position { x: 1, y: 0 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[0]);
         let expected = Change {
             position: Position { x: 0, y: 1 },
-            is_alive: true,
+            state: ALIVE,
         };
         assert_eq!(expected, changes[1]);
         let expected = Change {
             position: Position { x: 2, y: 1 },
-            is_alive: true,
+            state: ALIVE,
         };
         assert_eq!(expected, changes[2]);
         let expected = Change {
             position: Position { x: 1, y: 2 },
-            is_alive: false,
+            state: DEAD,
         };
         assert_eq!(expected, changes[3]);
     }
+
+    #[test]
+    fn dead_cell_with_six_neighbours_resurrects_in_highlife() {
+        let (scenario, grid, grid_handle) = create_mock_with_size(3, 3);
+        /*
+         * O | O | O
+         * O | . | .
+         * O | O | .
+         */
+        set_grid_alive_at(
+            &scenario,
+            &grid_handle,
+            &[
+                Position { x: 0, y: 0 },
+                Position { x: 1, y: 0 },
+                Position { x: 2, y: 0 },
+                Position { x: 0, y: 1 },
+                Position { x: 0, y: 2 },
+                Position { x: 1, y: 2 },
+            ],
+        );
+
+        let generation_calculator = GenerationCalculatorImpl::new("B36/S23".parse().unwrap());
+        let changes = generation_calculator.next_generation(&grid);
+
+        let expected = Change {
+            position: Position { x: 1, y: 1 },
+            state: ALIVE,
+        };
+        assert!(changes.contains(&expected));
+    }
+
+    #[test]
+    fn alive_cell_without_neighbours_survives_with_s0() {
+        let (scenario, grid, grid_handle) = create_mock_with_size(3, 3);
+        set_grid_alive_at(&scenario, &grid_handle, &[Position { x: 1, y: 1 }]);
+
+        let generation_calculator = GenerationCalculatorImpl::new("B3/S0".parse().unwrap());
+        let changes = generation_calculator.next_generation(&grid);
+
+        assert_eq!(0, changes.len());
+    }
+
+    fn create_grid(
+        width: u32,
+        height: u32,
+        topology: Topology,
+        alive_cells: &[Position],
+    ) -> GridImpl {
+        let mut grid = GridImpl::with_topology(width, height, topology);
+        for &position in alive_cells {
+            grid.set_alive_at(position);
+        }
+        grid
+    }
+
+    fn create_torus(width: u32, height: u32, alive_cells: &[Position]) -> GridImpl {
+        create_grid(width, height, Topology::Torus, alive_cells)
+    }
+
+    fn advance(grid: &mut GridImpl, generation_calculator: &GenerationCalculatorImpl) {
+        let changes = generation_calculator.next_generation(grid);
+        for change in changes {
+            if change.is_alive() {
+                grid.set_alive_at(change.position);
+            } else {
+                grid.set_dead_at(change.position);
+            }
+        }
+    }
+
+    #[test]
+    fn lone_cell_on_torus_edge_counts_wrapped_neighbours() {
+        /*
+         * . | . | . | .
+         * O | . | O | O
+         * . | . | . | .
+         */
+        let grid = create_torus(
+            4,
+            3,
+            &[
+                Position { x: 0, y: 1 },
+                Position { x: 2, y: 1 },
+                Position { x: 3, y: 1 },
+            ],
+        );
+
+        let neighbours = count_neighbours_at(&grid, Position { x: 3, y: 1 });
+
+        assert_eq!(Some(2), neighbours);
+    }
+
+    #[test]
+    fn blinker_across_torus_edge_oscillates() {
+        /*
+         * . | . | . | . | .
+         * . | . | . | . | .
+         * O | O | . | . | O
+         * . | . | . | . | .
+         * . | . | . | . | .
+         */
+        let mut grid = create_torus(
+            5,
+            5,
+            &[
+                Position { x: 4, y: 2 },
+                Position { x: 0, y: 2 },
+                Position { x: 1, y: 2 },
+            ],
+        );
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
+
+        let changes = generation_calculator.next_generation(&grid);
+
+        /*
+         * . | . | . | . | .
+         * O | . | . | . | .
+         * O | . | . | . | .
+         * O | . | . | . | .
+         * . | . | . | . | .
+         */
+        assert_eq!(4, changes.len());
+        let expected = [
+            Change {
+                position: Position { x: 0, y: 1 },
+                state: ALIVE,
+            },
+            Change {
+                position: Position { x: 1, y: 2 },
+                state: DEAD,
+            },
+            Change {
+                position: Position { x: 4, y: 2 },
+                state: DEAD,
+            },
+            Change {
+                position: Position { x: 0, y: 3 },
+                state: ALIVE,
+            },
+        ];
+        assert_eq!(expected.as_ref(), changes.as_slice());
+
+        advance(&mut grid, &generation_calculator);
+        advance(&mut grid, &generation_calculator);
+        assert_eq!(
+            create_torus(
+                5,
+                5,
+                &[
+                    Position { x: 4, y: 2 },
+                    Position { x: 0, y: 2 },
+                    Position { x: 1, y: 2 },
+                ],
+            ),
+            grid
+        );
+    }
+
+    #[test]
+    fn glider_returns_to_start_on_torus() {
+        /*
+         * . | O | . | . | . | .
+         * . | . | O | . | . | .
+         * O | O | O | . | . | .
+         * . | . | . | . | . | .
+         * . | . | . | . | . | .
+         * . | . | . | . | . | .
+         */
+        let glider = [
+            Position { x: 1, y: 0 },
+            Position { x: 2, y: 1 },
+            Position { x: 0, y: 2 },
+            Position { x: 1, y: 2 },
+            Position { x: 2, y: 2 },
+        ];
+        let mut grid = create_torus(6, 6, &glider);
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
+
+        // A glider travels one cell diagonally every four generations
+        for _ in 0..4 * 6 {
+            advance(&mut grid, &generation_calculator);
+        }
+
+        assert_eq!(create_torus(6, 6, &glider), grid);
+    }
+
+    #[test]
+    fn glider_dies_at_bounded_edge() {
+        let glider = [
+            Position { x: 1, y: 0 },
+            Position { x: 2, y: 1 },
+            Position { x: 0, y: 2 },
+            Position { x: 1, y: 2 },
+            Position { x: 2, y: 2 },
+        ];
+        let mut grid = GridImpl::new(6, 6);
+        for &position in &glider {
+            grid.set_alive_at(position);
+        }
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
+
+        for _ in 0..4 * 6 {
+            advance(&mut grid, &generation_calculator);
+        }
+
+        let torus = create_torus(6, 6, &glider);
+        assert_ne!(torus[..], grid[..]);
+    }
+
+    #[test]
+    fn blinker_across_twisted_klein_bottle_edge_is_mirrored() {
+        /*
+         * O | O | O | . | .
+         * . | . | . | . | .
+         * . | . | . | . | .
+         * . | . | . | . | .
+         * . | . | . | . | .
+         */
+        let grid = create_grid(
+            5,
+            5,
+            Topology::KleinBottle(TwistedEdges::TopAndBottom),
+            &[
+                Position { x: 0, y: 0 },
+                Position { x: 1, y: 0 },
+                Position { x: 2, y: 0 },
+            ],
+        );
+        let generation_calculator = GenerationCalculatorImpl::new(Rule::conway());
+
+        let changes = generation_calculator.next_generation(&grid);
+
+        /*
+         * . | O | . | . | .
+         * . | O | . | . | .
+         * . | . | . | . | .
+         * . | . | . | . | .
+         * . | . | . | O | .
+         */
+        let expected = [
+            Change {
+                position: Position { x: 0, y: 0 },
+                state: DEAD,
+            },
+            Change {
+                position: Position { x: 2, y: 0 },
+                state: DEAD,
+            },
+            Change {
+                position: Position { x: 1, y: 1 },
+                state: ALIVE,
+            },
+            Change {
+                position: Position { x: 3, y: 4 },
+                state: ALIVE,
+            },
+        ];
+        assert_eq!(expected.as_ref(), changes.as_slice());
+    }
+
+    #[test]
+    fn cross_surface_corner_sees_opposite_corner_through_both_edges() {
+        let grid = create_grid(4, 4, Topology::CrossSurface, &[Position { x: 3, y: 3 }]);
+
+        let neighbours = count_neighbours_at(&grid, Position { x: 0, y: 0 });
+
+        // Crossing either twisted edge from the top left corner
+        // lands on the bottom right corner
+        assert_eq!(Some(2), neighbours);
+    }
 }
@@ -0,0 +1,225 @@
+use crate::generation_calculator::{
+    changes_by_cell, count_neighbours_at, Change, GenerationCalculator,
+};
+use crate::grid::{Grid, ALIVE, DEAD};
+use crate::rule::{Rule, RuleParseError};
+use std::fmt;
+use std::str::FromStr;
+
+// Alive cells that do not survive pass through the states after `ALIVE`
+// one generation at a time before they die. Only alive cells count as neighbours
+// and dying cells cannot be born again until they are dead.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct GenerationsRule {
+    rule: Rule,
+    states: u8,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum GenerationsRuleParseError {
+    Rule(RuleParseError),
+    MissingStateCount,
+    InvalidStateCount(String),
+}
+
+impl GenerationsRule {
+    // A state count of 2 behaves like the plain rule
+    pub fn new(rule: Rule, states: u8) -> Self {
+        GenerationsRule {
+            rule,
+            states: states.max(2),
+        }
+    }
+
+    pub fn brians_brain() -> Self {
+        GenerationsRule::new(
+            Rule::new(&[2], &[]).expect("B2/S only has valid neighbour counts"),
+            3,
+        )
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn next_state(&self, state: u8, neighbours: u32) -> u8 {
+        next_decaying_state(
+            state,
+            self.states,
+            self.rule.is_born(neighbours),
+            self.rule.survives(neighbours),
+        )
+    }
+}
+
+// Also used by the other rules whose cells decay through the states after `ALIVE`
+pub fn next_decaying_state(state: u8, states: u8, is_born: bool, survives: bool) -> u8 {
+    match state {
+        DEAD if is_born => ALIVE,
+        DEAD => DEAD,
+        ALIVE if survives => ALIVE,
+        dying if dying + 1 < states => dying + 1,
+        _ => DEAD,
+    }
+}
+
+impl FromStr for GenerationsRule {
+    type Err = GenerationsRuleParseError;
+
+    // Accepts "S/B/C" as in "345/2/4" as well as "B2/S/C3"
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        if rule.matches('/').count() < 2 {
+            return Err(GenerationsRuleParseError::MissingStateCount);
+        }
+        let separator = rule.rfind('/').unwrap();
+        let states = &rule[separator + 1..];
+        let count = states.trim_start_matches(|prefix| "CcGg".contains(prefix));
+        let states = count
+            .parse::<u8>()
+            .ok()
+            .filter(|&states| states >= 2)
+            .ok_or_else(|| GenerationsRuleParseError::InvalidStateCount(states.to_string()))?;
+        let rule = rule[..separator]
+            .parse()
+            .map_err(GenerationsRuleParseError::Rule)?;
+        Ok(GenerationsRule::new(rule, states))
+    }
+}
+
+impl fmt::Display for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/C{}", self.rule, self.states)
+    }
+}
+
+impl fmt::Display for GenerationsRuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenerationsRuleParseError::Rule(error) => write!(f, "{}", error),
+            GenerationsRuleParseError::MissingStateCount => {
+                write!(f, "expected a state count after a second '/'")
+            }
+            GenerationsRuleParseError::InvalidStateCount(states) => {
+                write!(f, "'{}' is not a state count between 2 and 255", states)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GenerationsCalculator {
+    rule: GenerationsRule,
+}
+
+impl GenerationsCalculator {
+    pub fn new(rule: GenerationsRule) -> Self {
+        GenerationsCalculator { rule }
+    }
+}
+
+impl GenerationCalculator for GenerationsCalculator {
+    fn next_generation(&self, grid: &dyn Grid) -> Vec<Change> {
+        changes_by_cell(grid, |position, state| {
+            let neighbours = count_neighbours_at(grid, position).expect("x or y out of bounds");
+            self.rule.next_state(state, neighbours)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::{cell_states, GridImpl, Position};
+    use crate::test_util::step;
+
+    fn states(grid: &GridImpl) -> Vec<(u32, u32, u8)> {
+        cell_states(grid)
+            .into_iter()
+            .map(|cell| (cell.position.x, cell.position.y, cell.state))
+            .collect()
+    }
+
+    #[test]
+    fn parses_survival_birth_state_notation() {
+        let star_wars: GenerationsRule = "345/2/4".parse().unwrap();
+        assert_eq!(
+            GenerationsRule::new(Rule::new(&[2], &[3, 4, 5]).unwrap(), 4),
+            star_wars
+        );
+        let brians_brain: GenerationsRule = "/2/3".parse().unwrap();
+        assert_eq!(GenerationsRule::brians_brain(), brians_brain);
+    }
+
+    #[test]
+    fn parses_prefixed_notation() {
+        let rule: GenerationsRule = "B2/S/C3".parse().unwrap();
+        assert_eq!(GenerationsRule::brians_brain(), rule);
+        assert_eq!("B2/S/C3", rule.to_string());
+    }
+
+    #[test]
+    fn fails_on_invalid_rules() {
+        assert_eq!(
+            Err(GenerationsRuleParseError::MissingStateCount),
+            "B3/S23".parse::<GenerationsRule>()
+        );
+        assert_eq!(
+            Err(GenerationsRuleParseError::InvalidStateCount(
+                "C1".to_string()
+            )),
+            "B3/S23/C1".parse::<GenerationsRule>()
+        );
+        assert_eq!(
+            Err(GenerationsRuleParseError::Rule(
+                RuleParseError::InvalidNeighbourCount('9')
+            )),
+            "B9/S/3".parse::<GenerationsRule>()
+        );
+    }
+
+    #[test]
+    fn dying_cells_decay_before_they_die() {
+        assert_eq!(ALIVE, next_decaying_state(ALIVE, 4, false, true));
+        assert_eq!(2, next_decaying_state(ALIVE, 4, true, false));
+        assert_eq!(3, next_decaying_state(2, 4, true, true));
+        assert_eq!(DEAD, next_decaying_state(3, 4, true, true));
+        assert_eq!(ALIVE, next_decaying_state(DEAD, 4, true, false));
+        assert_eq!(DEAD, next_decaying_state(DEAD, 4, false, true));
+    }
+
+    #[test]
+    fn two_states_behave_like_plain_rule() {
+        let rule = GenerationsRule::new(Rule::conway(), 2);
+        assert_eq!(DEAD, rule.next_state(ALIVE, 1));
+        assert_eq!(ALIVE, rule.next_state(DEAD, 3));
+    }
+
+    #[test]
+    fn moves_brians_brain_spaceship() {
+        // The smallest spaceship of Brian's Brain travels one cell per generation
+        let mut grid = GridImpl::new(8, 4);
+        for &(x, y, state) in &[(2, 1, ALIVE), (2, 2, ALIVE), (1, 1, 2), (1, 2, 2)] {
+            grid.set_state_at(Position { x, y }, state);
+        }
+        let calculator = GenerationsCalculator::new(GenerationsRule::brians_brain());
+
+        step(&mut grid, &calculator);
+
+        let expected = vec![(2, 1, 2), (3, 1, ALIVE), (2, 2, 2), (3, 2, ALIVE)];
+        assert_eq!(expected, states(&grid));
+    }
+
+    #[test]
+    fn dying_cells_block_births() {
+        let mut grid = GridImpl::new(3, 3);
+        grid.set_alive_at(Position { x: 0, y: 1 });
+        grid.set_alive_at(Position { x: 2, y: 1 });
+        grid.set_state_at(Position { x: 1, y: 1 }, 2);
+        let calculator = GenerationsCalculator::new("/2/4".parse().unwrap());
+
+        step(&mut grid, &calculator);
+
+        assert_eq!(3, grid.state_at(Position { x: 1, y: 1 }));
+    }
+}
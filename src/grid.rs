@@ -1,3 +1,6 @@
+use crate::generation_calculator::Change;
+use crate::topology::Topology;
+use std::fmt;
 use std::ops::Deref;
 
 #[cfg(test)]
@@ -5,7 +8,11 @@ extern crate mockers;
 #[cfg(test)]
 use mockers_derive::mocked;
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+// Cells are dead or alive, multi-state rules use the states above `ALIVE` as well
+pub const DEAD: u8 = 0;
+pub const ALIVE: u8 = 1;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub struct Position {
     pub x: u32,
     pub y: u32,
@@ -15,24 +22,54 @@ pub struct Position {
 pub trait Grid {
     fn width(&self) -> u32;
     fn height(&self) -> u32;
+    fn topology(&self) -> Topology;
     fn is_alive_at(&self, position: Position) -> bool;
     fn set_alive_at(&mut self, position: Position);
     fn set_dead_at(&mut self, position: Position);
+
+    // Grids that only store alive and dead cells treat every other state as dead
+    fn state_at(&self, position: Position) -> u8 {
+        if self.is_alive_at(position) {
+            ALIVE
+        } else {
+            DEAD
+        }
+    }
+
+    fn set_state_at(&mut self, position: Position, state: u8) {
+        if state == ALIVE {
+            self.set_alive_at(position);
+        } else {
+            self.set_dead_at(position);
+        }
+    }
+}
+
+// Grids are passed around as trait objects, which shows them by their size
+impl fmt::Debug for dyn Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Grid {}x{}", self.width(), self.height())
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct GridImpl {
-    grid: Vec<bool>,
+    grid: Vec<u8>,
     width: u32,
     height: u32,
+    topology: Topology,
 }
 
 impl GridImpl {
     pub fn new(width: u32, height: u32) -> Self {
+        GridImpl::with_topology(width, height, Topology::Bounded)
+    }
+    pub fn with_topology(width: u32, height: u32, topology: Topology) -> Self {
         GridImpl {
-            grid: vec![false; (width * height) as usize],
+            grid: vec![DEAD; (width * height) as usize],
             width,
             height,
+            topology,
         }
     }
     fn translate_coordinates_to_index(&self, position: Position) -> usize {
@@ -47,22 +84,71 @@ impl Grid for GridImpl {
     fn height(&self) -> u32 {
         self.height
     }
+    fn topology(&self) -> Topology {
+        self.topology
+    }
     fn is_alive_at(&self, position: Position) -> bool {
         let index = self.translate_coordinates_to_index(position);
-        self.grid[index]
+        self.grid[index] == ALIVE
     }
     fn set_alive_at(&mut self, position: Position) {
         let index = self.translate_coordinates_to_index(position);
-        self.grid[index] = true;
+        self.grid[index] = ALIVE;
     }
     fn set_dead_at(&mut self, position: Position) {
         let index = self.translate_coordinates_to_index(position);
-        self.grid[index] = false;
+        self.grid[index] = DEAD;
+    }
+    fn state_at(&self, position: Position) -> u8 {
+        let index = self.translate_coordinates_to_index(position);
+        self.grid[index]
+    }
+    fn set_state_at(&mut self, position: Position, state: u8) {
+        let index = self.translate_coordinates_to_index(position);
+        self.grid[index] = state;
     }
 }
 
+pub fn alive_cells(grid: &dyn Grid) -> Vec<Position> {
+    let width = grid.width();
+    let height = grid.height();
+    let mut alive_cells = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let position = Position { x, y };
+            if grid.is_alive_at(position) {
+                alive_cells.push(position);
+            }
+        }
+    }
+    alive_cells
+}
+
+pub fn apply_changes(grid: &mut dyn Grid, changes: &[Change]) {
+    for change in changes {
+        grid.set_state_at(change.position, change.state);
+    }
+}
+
+// Every cell that is not dead together with its state
+pub fn cell_states(grid: &dyn Grid) -> Vec<Change> {
+    let width = grid.width();
+    let height = grid.height();
+    let mut cells = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let position = Position { x, y };
+            let state = grid.state_at(position);
+            if state != DEAD {
+                cells.push(Change { position, state });
+            }
+        }
+    }
+    cells
+}
+
 impl Deref for GridImpl {
-    type Target = [bool];
+    type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
         self.grid.as_slice()
@@ -85,11 +171,23 @@ mod test {
         assert_eq!(5, grid.height());
     }
 
+    #[test]
+    fn grid_is_bounded_by_default() {
+        let grid = GridImpl::new(10, 5);
+        assert_eq!(Topology::Bounded, grid.topology());
+    }
+
+    #[test]
+    fn grid_has_correct_topology() {
+        let grid = GridImpl::with_topology(10, 5, Topology::Torus);
+        assert_eq!(Topology::Torus, grid.topology());
+    }
+
     #[test]
     fn grid_inits_dead() {
         let grid = GridImpl::new(10, 10);
-        for cell_is_alive in grid.iter() {
-            assert_eq!(false, *cell_is_alive)
+        for &state in grid.iter() {
+            assert_eq!(DEAD, state)
         }
     }
 
@@ -101,6 +199,20 @@ mod test {
         assert_eq!(true, grid.is_alive_at(position));
     }
 
+    #[test]
+    fn lists_alive_cells_row_by_row() {
+        let mut grid = GridImpl::new(4, 3);
+        grid.set_alive_at(Position { x: 3, y: 0 });
+        grid.set_alive_at(Position { x: 0, y: 2 });
+        grid.set_alive_at(Position { x: 1, y: 0 });
+        let expected = vec![
+            Position { x: 1, y: 0 },
+            Position { x: 3, y: 0 },
+            Position { x: 0, y: 2 },
+        ];
+        assert_eq!(expected, alive_cells(&grid));
+    }
+
     #[test]
     fn grid_sets_dead() {
         let mut grid = GridImpl::new(10, 10);
@@ -0,0 +1,906 @@
+use crate::generation_calculator::{Change, GenerationCalculator};
+use crate::grid::{self, Grid};
+use crate::infinite_grid::{Coordinate, InfiniteGrid, PlaneCells, UnsupportedRule, Viewport};
+use crate::rule::Rule;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+type NodeId = usize;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+const MAX_CACHED_NODES: usize = 1 << 22;
+const MAX_SNAPSHOTS: usize = 1 << 16;
+// Steps have to fit the generations_per_step of a calculator
+const MAX_STEP_EXPONENT: u32 = 31;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+struct Quadrants {
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u32,
+    population: u64,
+    quadrants: Option<Quadrants>,
+}
+
+// The cells of one generation. Nodes are shared, so keeping one is cheap
+#[derive(Debug, Clone, Copy)]
+struct Snapshot {
+    root: NodeId,
+    origin: Coordinate,
+    generation: u64,
+}
+
+// Canonicalized quadtree: every distinct square of cells is stored exactly once,
+// so the memoized successor of a square is shared by all of its occurrences
+pub struct HashLife {
+    rule: Rule,
+    nodes: Vec<Node>,
+    canonical: HashMap<Quadrants, NodeId>,
+    successors: HashMap<(NodeId, u32), NodeId>,
+    empty: Vec<NodeId>,
+    root: NodeId,
+    origin: Coordinate,
+    generation: u64,
+}
+
+impl HashLife {
+    pub fn new(rule: Rule) -> Result<Self, UnsupportedRule> {
+        // The quadtree cannot represent a plane that fills up at once
+        if rule.is_born(0) {
+            return Err(UnsupportedRule(rule));
+        }
+        let mut hashlife = HashLife {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            canonical: HashMap::new(),
+            successors: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: Coordinate { x: 0, y: 0 },
+            generation: 0,
+        };
+        hashlife.root = hashlife.empty(3);
+        Ok(hashlife)
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.root = self.empty(3);
+        self.origin = Coordinate { x: 0, y: 0 };
+    }
+
+    pub fn is_alive_at(&self, coordinate: Coordinate) -> bool {
+        let size = 1i64 << self.level(self.root);
+        let x = coordinate.x - self.origin.x;
+        let y = coordinate.y - self.origin.y;
+        if x < 0 || y < 0 || x >= size || y >= size {
+            return false;
+        }
+        let mut node = self.root;
+        let mut level = self.level(node);
+        while level > 0 {
+            level -= 1;
+            let quadrants = self.quadrants(node);
+            let half = 1i64 << level;
+            node = match (x & half != 0, y & half != 0) {
+                (false, false) => quadrants.nw,
+                (true, false) => quadrants.ne,
+                (false, true) => quadrants.sw,
+                (true, true) => quadrants.se,
+            };
+        }
+        node == ALIVE
+    }
+
+    pub fn set_alive_at(&mut self, coordinate: Coordinate) {
+        self.set_cell(coordinate, ALIVE);
+    }
+
+    pub fn set_dead_at(&mut self, coordinate: Coordinate) {
+        self.set_cell(coordinate, DEAD);
+    }
+
+    pub fn alive_cells(&self) -> Vec<Coordinate> {
+        self.alive_cells_of(self.snapshot(), None)
+    }
+
+    // Only walks the parts of the quadtree that overlap the viewport
+    pub fn alive_cells_within(&self, viewport: &Viewport) -> Vec<Coordinate> {
+        self.alive_cells_of(self.snapshot(), Some(viewport))
+    }
+
+    pub fn advance(&mut self, generations: u64) {
+        let mut remaining = generations;
+        let mut step_exponent = 0;
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                self.advance_by_power_of_two(step_exponent);
+            }
+            remaining >>= 1;
+            step_exponent += 1;
+        }
+    }
+
+    pub fn advance_by_power_of_two(&mut self, step_exponent: u32) {
+        while self.level(self.root) < step_exponent + 2 || !self.is_centred(self.root) {
+            self.expand();
+        }
+        // One more ring of empty space, so that nothing the pattern grows into
+        // during the step falls outside of the computed centre
+        self.expand();
+        let level = self.level(self.root);
+        self.root = self.successor(self.root, step_exponent);
+        let quarter = 1i64 << (level - 2);
+        self.origin = Coordinate {
+            x: self.origin.x + quarter,
+            y: self.origin.y + quarter,
+        };
+        self.generation += 1 << step_exponent;
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            root: self.root,
+            origin: self.origin,
+            generation: self.generation,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.root = snapshot.root;
+        self.origin = snapshot.origin;
+        self.generation = snapshot.generation;
+    }
+
+    // The cells 2^step_exponent generations ahead, leaving the current ones as they are
+    fn advanced(&mut self, step_exponent: u32) -> Snapshot {
+        let current = self.snapshot();
+        self.advance_by_power_of_two(step_exponent);
+        let advanced = self.snapshot();
+        self.restore(current);
+        advanced
+    }
+
+    // Takes the cells beyond the viewport from the snapshot and keeps the ones within it
+    fn restore_hidden(&mut self, snapshot: Snapshot, viewport: &Viewport) {
+        let visible_cells = self.alive_cells_within(viewport);
+        let restored_cells = self.alive_cells_of(snapshot, Some(viewport));
+        self.restore(snapshot);
+        for &coordinate in &restored_cells {
+            self.set_dead_at(coordinate);
+        }
+        for &coordinate in &visible_cells {
+            self.set_alive_at(coordinate);
+        }
+    }
+
+    fn retain_within(&mut self, viewport: &Viewport) {
+        let visible_cells = self.alive_cells_within(viewport);
+        self.clear();
+        for &coordinate in &visible_cells {
+            self.set_alive_at(coordinate);
+        }
+    }
+
+    // Starts over with only the nodes that the current cells and the snapshots are made of,
+    // which forgets the memoized successors as well
+    fn compact<'a>(&mut self, snapshots: impl Iterator<Item = &'a mut Snapshot>) {
+        let nodes = std::mem::replace(&mut self.nodes, vec![leaf(0), leaf(1)]);
+        self.canonical.clear();
+        self.successors.clear();
+        self.empty = vec![DEAD];
+        let mut copies = HashMap::new();
+        self.root = self.copy(&nodes, self.root, &mut copies);
+        for snapshot in snapshots {
+            snapshot.root = self.copy(&nodes, snapshot.root, &mut copies);
+        }
+    }
+
+    fn copy(
+        &mut self,
+        nodes: &[Node],
+        node: NodeId,
+        copies: &mut HashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if let Some(&copy) = copies.get(&node) {
+            return copy;
+        }
+        let copy = match nodes[node].quadrants {
+            None => node,
+            Some(Quadrants { nw, ne, sw, se }) => {
+                let nw = self.copy(nodes, nw, copies);
+                let ne = self.copy(nodes, ne, copies);
+                let sw = self.copy(nodes, sw, copies);
+                let se = self.copy(nodes, se, copies);
+                self.join(nw, ne, sw, se)
+            }
+        };
+        copies.insert(node, copy);
+        copy
+    }
+
+    fn alive_cells_of(&self, snapshot: Snapshot, viewport: Option<&Viewport>) -> Vec<Coordinate> {
+        let mut alive_cells = Vec::new();
+        self.collect_alive_cells(snapshot.root, snapshot.origin, viewport, &mut alive_cells);
+        alive_cells.sort_by_key(|coordinate| (coordinate.y, coordinate.x));
+        alive_cells
+    }
+
+    fn level(&self, node: NodeId) -> u32 {
+        self.nodes[node].level
+    }
+
+    fn quadrants(&self, node: NodeId) -> Quadrants {
+        self.nodes[node]
+            .quadrants
+            .expect("Leaves have no quadrants")
+    }
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let quadrants = Quadrants { nw, ne, sw, se };
+        if let Some(&node) = self.canonical.get(&quadrants) {
+            return node;
+        }
+        let population = [nw, ne, sw, se]
+            .iter()
+            .map(|&quadrant| self.nodes[quadrant].population)
+            .sum();
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            level: self.level(nw) + 1,
+            population,
+            quadrants: Some(quadrants),
+        });
+        self.canonical.insert(quadrants, node);
+        node
+    }
+
+    fn empty(&mut self, level: u32) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let smaller = *self.empty.last().unwrap();
+            let node = self.join(smaller, smaller, smaller, smaller);
+            self.empty.push(node);
+        }
+        self.empty[level as usize]
+    }
+
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let border = self.empty(level - 1);
+        let Quadrants { nw, ne, sw, se } = self.quadrants(self.root);
+        let nw = self.join(border, border, border, nw);
+        let ne = self.join(border, border, ne, border);
+        let sw = self.join(border, sw, border, border);
+        let se = self.join(se, border, border, border);
+        self.root = self.join(nw, ne, sw, se);
+        let quarter = 1i64 << (level - 1);
+        self.origin = Coordinate {
+            x: self.origin.x - quarter,
+            y: self.origin.y - quarter,
+        };
+    }
+
+    fn is_centred(&mut self, node: NodeId) -> bool {
+        let centre = self.centre(node);
+        self.nodes[centre].population == self.nodes[node].population
+    }
+
+    fn centre(&mut self, node: NodeId) -> NodeId {
+        let Quadrants { nw, ne, sw, se } = self.quadrants(node);
+        let nw = self.quadrants(nw).se;
+        let ne = self.quadrants(ne).sw;
+        let sw = self.quadrants(sw).ne;
+        let se = self.quadrants(se).nw;
+        self.join(nw, ne, sw, se)
+    }
+
+    fn horizontal_centre(&mut self, west: NodeId, east: NodeId) -> NodeId {
+        let west = self.quadrants(west);
+        let east = self.quadrants(east);
+        self.join(west.ne, east.nw, west.se, east.sw)
+    }
+
+    fn vertical_centre(&mut self, north: NodeId, south: NodeId) -> NodeId {
+        let north = self.quadrants(north);
+        let south = self.quadrants(south);
+        self.join(north.sw, north.se, south.nw, south.ne)
+    }
+
+    // The centre half of a node, advanced by 2^step_exponent generations
+    fn successor(&mut self, node: NodeId, step_exponent: u32) -> NodeId {
+        let level = self.level(node);
+        if self.nodes[node].population == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&successor) = self.successors.get(&(node, step_exponent)) {
+            return successor;
+        }
+        let successor = if level == 2 {
+            self.evolve_leaf_square(node)
+        } else {
+            let Quadrants { nw, ne, sw, se } = self.quadrants(node);
+            let n01 = self.horizontal_centre(nw, ne);
+            let n10 = self.vertical_centre(nw, sw);
+            let n11 = self.centre(node);
+            let n12 = self.vertical_centre(ne, se);
+            let n21 = self.horizontal_centre(sw, se);
+            let squares = [[nw, n01, ne], [n10, n11, n12], [sw, n21, se]];
+
+            let is_full_step = step_exponent == level - 2;
+            let inner_exponent = if is_full_step {
+                step_exponent - 1
+            } else {
+                step_exponent
+            };
+            let mut advanced = [[DEAD; 3]; 3];
+            for (row, squares) in squares.iter().enumerate() {
+                for (column, &square) in squares.iter().enumerate() {
+                    advanced[row][column] = self.successor(square, inner_exponent);
+                }
+            }
+
+            let mut quadrants = [[DEAD; 2]; 2];
+            for row in 0..2 {
+                for column in 0..2 {
+                    let combined = self.join(
+                        advanced[row][column],
+                        advanced[row][column + 1],
+                        advanced[row + 1][column],
+                        advanced[row + 1][column + 1],
+                    );
+                    quadrants[row][column] = if is_full_step {
+                        self.successor(combined, inner_exponent)
+                    } else {
+                        self.centre(combined)
+                    };
+                }
+            }
+            self.join(
+                quadrants[0][0],
+                quadrants[0][1],
+                quadrants[1][0],
+                quadrants[1][1],
+            )
+        };
+        self.successors.insert((node, step_exponent), successor);
+        successor
+    }
+
+    fn evolve_leaf_square(&mut self, node: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        let Quadrants { nw, ne, sw, se } = self.quadrants(node);
+        for &(quadrant, offset_x, offset_y) in &[(nw, 0, 0), (ne, 2, 0), (sw, 0, 2), (se, 2, 2)] {
+            let Quadrants { nw, ne, sw, se } = self.quadrants(quadrant);
+            cells[offset_y][offset_x] = nw == ALIVE;
+            cells[offset_y][offset_x + 1] = ne == ALIVE;
+            cells[offset_y + 1][offset_x] = sw == ALIVE;
+            cells[offset_y + 1][offset_x + 1] = se == ALIVE;
+        }
+        let mut next = [DEAD; 4];
+        for (index, &(x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].iter().enumerate() {
+            let neighbours = cells[y - 1..=y + 1]
+                .iter()
+                .flat_map(|row| &row[x - 1..=x + 1])
+                .filter(|&&is_alive| is_alive)
+                .count() as u32
+                - cells[y][x] as u32;
+            let is_alive = if cells[y][x] {
+                self.rule.survives(neighbours)
+            } else {
+                self.rule.is_born(neighbours)
+            };
+            next[index] = if is_alive { ALIVE } else { DEAD };
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    fn set_cell(&mut self, coordinate: Coordinate, cell: NodeId) {
+        loop {
+            let size = 1i64 << self.level(self.root);
+            let x = coordinate.x - self.origin.x;
+            let y = coordinate.y - self.origin.y;
+            if x >= 0 && y >= 0 && x < size && y < size {
+                break;
+            }
+            self.expand();
+        }
+        let x = coordinate.x - self.origin.x;
+        let y = coordinate.y - self.origin.y;
+        self.root = self.with_cell(self.root, x, y, cell);
+    }
+
+    fn with_cell(&mut self, node: NodeId, x: i64, y: i64, cell: NodeId) -> NodeId {
+        let level = self.level(node);
+        if level == 0 {
+            return cell;
+        }
+        let half = 1i64 << (level - 1);
+        let Quadrants {
+            mut nw,
+            mut ne,
+            mut sw,
+            mut se,
+        } = self.quadrants(node);
+        match (x >= half, y >= half) {
+            (false, false) => nw = self.with_cell(nw, x, y, cell),
+            (true, false) => ne = self.with_cell(ne, x - half, y, cell),
+            (false, true) => sw = self.with_cell(sw, x, y - half, cell),
+            (true, true) => se = self.with_cell(se, x - half, y - half, cell),
+        }
+        self.join(nw, ne, sw, se)
+    }
+
+    fn collect_alive_cells(
+        &self,
+        node: NodeId,
+        origin: Coordinate,
+        viewport: Option<&Viewport>,
+        alive_cells: &mut Vec<Coordinate>,
+    ) {
+        let node_data = self.nodes[node];
+        if node_data.population == 0 {
+            return;
+        }
+        if let Some(viewport) = viewport {
+            let size = 1i64 << node_data.level;
+            let is_beyond = origin.x >= viewport.origin.x + i64::from(viewport.width)
+                || origin.y >= viewport.origin.y + i64::from(viewport.height)
+                || origin.x + size <= viewport.origin.x
+                || origin.y + size <= viewport.origin.y;
+            if is_beyond {
+                return;
+            }
+        }
+        if node_data.level == 0 {
+            alive_cells.push(origin);
+            return;
+        }
+        let half = 1i64 << (node_data.level - 1);
+        let Quadrants { nw, ne, sw, se } = self.quadrants(node);
+        self.collect_alive_cells(nw, origin, viewport, alive_cells);
+        let ne_origin = Coordinate {
+            x: origin.x + half,
+            y: origin.y,
+        };
+        self.collect_alive_cells(ne, ne_origin, viewport, alive_cells);
+        let sw_origin = Coordinate {
+            x: origin.x,
+            y: origin.y + half,
+        };
+        self.collect_alive_cells(sw, sw_origin, viewport, alive_cells);
+        let se_origin = Coordinate {
+            x: origin.x + half,
+            y: origin.y + half,
+        };
+        self.collect_alive_cells(se, se_origin, viewport, alive_cells);
+    }
+}
+
+impl PlaneCells for HashLife {
+    fn is_alive_at(&self, coordinate: Coordinate) -> bool {
+        self.is_alive_at(coordinate)
+    }
+    fn set_alive_at(&mut self, coordinate: Coordinate) {
+        self.set_alive_at(coordinate);
+    }
+    fn set_dead_at(&mut self, coordinate: Coordinate) {
+        self.set_dead_at(coordinate);
+    }
+}
+
+fn leaf(population: u64) -> Node {
+    Node {
+        level: 0,
+        population,
+        quadrants: None,
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum HashLifeError {
+    UnsupportedRule(UnsupportedRule),
+    StepTooLarge(u32),
+}
+
+impl From<UnsupportedRule> for HashLifeError {
+    fn from(error: UnsupportedRule) -> Self {
+        HashLifeError::UnsupportedRule(error)
+    }
+}
+
+impl fmt::Display for HashLifeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HashLifeError::UnsupportedRule(error) => error.fmt(f),
+            HashLifeError::StepTooLarge(step_exponent) => write!(
+                f,
+                "steps of 2^{} generations are too large, the largest is 2^{}",
+                step_exponent, MAX_STEP_EXPONENT
+            ),
+        }
+    }
+}
+
+// Advances a HashLife quadtree 2^step_exponent generations on every call. The quadtree
+// holds the whole plane, its grid edits it directly and only the cells within the viewport
+// are ever listed. Like InfiniteGenerationCalculator, it reports the visible changes and
+// takes the step once the game accepts it.
+pub struct HashLifeCalculator {
+    hashlife: Rc<RefCell<HashLife>>,
+    viewport: Rc<RefCell<Viewport>>,
+    step_exponent: u32,
+    pending_step: RefCell<Option<(Snapshot, Snapshot)>>,
+    // Restoring a generation takes the cells beyond the viewport from here
+    snapshots: RefCell<BTreeMap<u64, Snapshot>>,
+}
+
+impl HashLifeCalculator {
+    pub fn new(viewport: Viewport, rule: Rule, step_exponent: u32) -> Result<Self, HashLifeError> {
+        if step_exponent > MAX_STEP_EXPONENT {
+            return Err(HashLifeError::StepTooLarge(step_exponent));
+        }
+        Ok(HashLifeCalculator {
+            hashlife: Rc::new(RefCell::new(HashLife::new(rule)?)),
+            viewport: Rc::new(RefCell::new(viewport)),
+            step_exponent,
+            pending_step: RefCell::new(None),
+            snapshots: RefCell::new(BTreeMap::new()),
+        })
+    }
+
+    // Shows the quadtree through the viewport of the calculator
+    pub fn grid(&self) -> InfiniteGrid<HashLife> {
+        InfiniteGrid::with_plane(Rc::clone(&self.hashlife), Rc::clone(&self.viewport))
+    }
+
+    fn collect_garbage(&self, hashlife: &mut HashLife) {
+        let mut snapshots = self.snapshots.borrow_mut();
+        loop {
+            hashlife.compact(snapshots.values_mut());
+            if hashlife.node_count() <= MAX_CACHED_NODES / 2 || snapshots.is_empty() {
+                break;
+            }
+            // Forgetting the oldest half of the generations frees the nodes only they use
+            let forgotten = snapshots.len() - snapshots.len() / 2;
+            let generations = snapshots
+                .keys()
+                .cloned()
+                .take(forgotten)
+                .collect::<Vec<_>>();
+            for generation in generations {
+                snapshots.remove(&generation);
+            }
+        }
+    }
+}
+
+impl GenerationCalculator for HashLifeCalculator {
+    fn next_generation(&self, _grid: &dyn Grid) -> Vec<Change> {
+        let mut hashlife = self.hashlife.borrow_mut();
+        let viewport = self.viewport.borrow();
+        let current = hashlife.snapshot();
+        let next = hashlife.advanced(self.step_exponent);
+        let previous_cells = hashlife.alive_cells_of(current, Some(&viewport));
+        let next_cells = hashlife.alive_cells_of(next, Some(&viewport));
+
+        let previous = previous_cells.iter().cloned().collect::<HashSet<_>>();
+        let next_set = next_cells.iter().cloned().collect::<HashSet<_>>();
+        let mut changes = previous_cells
+            .iter()
+            .filter(|coordinate| !next_set.contains(coordinate))
+            .map(|&coordinate| (coordinate, grid::DEAD))
+            .chain(
+                next_cells
+                    .iter()
+                    .filter(|coordinate| !previous.contains(coordinate))
+                    .map(|&coordinate| (coordinate, grid::ALIVE)),
+            )
+            .filter_map(|(coordinate, state)| {
+                viewport
+                    .to_position(coordinate)
+                    .map(|position| Change { position, state })
+            })
+            .collect::<Vec<_>>();
+        changes.sort_by_key(|change| (change.position.y, change.position.x));
+        *self.pending_step.borrow_mut() = Some((current, next));
+        changes
+    }
+
+    fn accept_changes(&self, _changes: &[Change]) {
+        let (previous, next) = match self.pending_step.borrow_mut().take() {
+            Some(step) => step,
+            None => return,
+        };
+        let mut hashlife = self.hashlife.borrow_mut();
+        {
+            let mut snapshots = self.snapshots.borrow_mut();
+            snapshots.split_off(&previous.generation);
+            snapshots.insert(previous.generation, previous);
+            snapshots.insert(next.generation, next);
+            if snapshots.len() > MAX_SNAPSHOTS {
+                let oldest = *snapshots.keys().next().unwrap();
+                snapshots.remove(&oldest);
+            }
+        }
+        // The next cells already include the visible changes the game has applied
+        hashlife.restore(next);
+        if hashlife.node_count() > MAX_CACHED_NODES {
+            self.collect_garbage(&mut hashlife);
+        }
+    }
+
+    fn generations_per_step(&self) -> u32 {
+        1 << self.step_exponent
+    }
+
+    fn restore_generation(&self, generation: u64) {
+        *self.pending_step.borrow_mut() = None;
+        let mut hashlife = self.hashlife.borrow_mut();
+        match self.snapshots.borrow().get(&generation) {
+            Some(&snapshot) => hashlife.restore_hidden(snapshot, &self.viewport.borrow()),
+            // Generations forgotten to save memory keep the current cells beyond the viewport
+            None => hashlife.generation = generation,
+        }
+    }
+
+    fn clear(&self) {
+        self.hashlife
+            .borrow_mut()
+            .retain_within(&self.viewport.borrow());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::Position;
+    use crate::infinite_grid::Plane;
+
+    const VIEWPORT: Viewport = Viewport {
+        origin: Coordinate { x: 0, y: 0 },
+        width: 10,
+        height: 10,
+    };
+
+    const GLIDER: [Coordinate; 5] = [
+        Coordinate { x: 1, y: 0 },
+        Coordinate { x: 2, y: 1 },
+        Coordinate { x: 0, y: 2 },
+        Coordinate { x: 1, y: 2 },
+        Coordinate { x: 2, y: 2 },
+    ];
+
+    const R_PENTOMINO: [Coordinate; 5] = [
+        Coordinate { x: 1, y: 0 },
+        Coordinate { x: 2, y: 0 },
+        Coordinate { x: 0, y: 1 },
+        Coordinate { x: 1, y: 1 },
+        Coordinate { x: 1, y: 2 },
+    ];
+
+    fn create_hashlife(alive_cells: &[Coordinate]) -> HashLife {
+        let mut hashlife = HashLife::new(Rule::conway()).unwrap();
+        for &coordinate in alive_cells {
+            hashlife.set_alive_at(coordinate);
+        }
+        hashlife
+    }
+
+    fn translate(cells: &[Coordinate], x: i64, y: i64) -> Vec<Coordinate> {
+        cells
+            .iter()
+            .map(|coordinate| Coordinate {
+                x: coordinate.x + x,
+                y: coordinate.y + y,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stores_cells_anywhere() {
+        let far_away = Coordinate {
+            x: -123_456_789,
+            y: 987_654_321,
+        };
+        let hashlife = create_hashlife(&[far_away]);
+        assert!(hashlife.is_alive_at(far_away));
+        assert!(!hashlife.is_alive_at(Coordinate { x: 0, y: 0 }));
+        assert_eq!(1, hashlife.population());
+        assert_eq!(vec![far_away], hashlife.alive_cells());
+    }
+
+    #[test]
+    fn sets_cells_dead() {
+        let mut hashlife = create_hashlife(&GLIDER);
+        hashlife.set_dead_at(GLIDER[0]);
+        assert_eq!(4, hashlife.population());
+        assert!(!hashlife.is_alive_at(GLIDER[0]));
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let horizontal = [
+            Coordinate { x: 0, y: 1 },
+            Coordinate { x: 1, y: 1 },
+            Coordinate { x: 2, y: 1 },
+        ];
+        let vertical = [
+            Coordinate { x: 1, y: 0 },
+            Coordinate { x: 1, y: 1 },
+            Coordinate { x: 1, y: 2 },
+        ];
+        let mut hashlife = create_hashlife(&horizontal);
+
+        hashlife.advance(1);
+        assert_eq!(vertical.to_vec(), hashlife.alive_cells());
+
+        hashlife.advance(1);
+        assert_eq!(horizontal.to_vec(), hashlife.alive_cells());
+        assert_eq!(2, hashlife.generation());
+    }
+
+    #[test]
+    fn matches_plane_generation_by_generation() {
+        let mut hashlife = create_hashlife(&R_PENTOMINO);
+        let mut plane = Plane::new();
+        for &coordinate in &R_PENTOMINO {
+            plane.set_alive_at(coordinate);
+        }
+
+        for _ in 0..100 {
+            hashlife.advance(1);
+            let changes = plane.next_generation(&Rule::conway());
+            plane.apply(&changes);
+            assert_eq!(plane.alive_cells(), hashlife.alive_cells());
+        }
+    }
+
+    #[test]
+    fn jumps_match_single_steps() {
+        let mut jumping = create_hashlife(&R_PENTOMINO);
+        let mut stepping = create_hashlife(&R_PENTOMINO);
+
+        jumping.advance(300);
+        for _ in 0..300 {
+            stepping.advance(1);
+        }
+
+        assert_eq!(stepping.alive_cells(), jumping.alive_cells());
+        assert_eq!(300, jumping.generation());
+    }
+
+    #[test]
+    fn r_pentomino_stabilises_with_116_cells() {
+        let mut hashlife = create_hashlife(&R_PENTOMINO);
+        hashlife.advance(1103);
+        assert_eq!(116, hashlife.population());
+    }
+
+    #[test]
+    fn glider_reaches_generation_one_billion() {
+        let mut hashlife = create_hashlife(&GLIDER);
+
+        hashlife.advance(1_000_000_000);
+
+        let expected = translate(&GLIDER, 250_000_000, 250_000_000);
+        assert_eq!(expected, hashlife.alive_cells());
+    }
+
+    #[test]
+    fn follows_other_rules() {
+        // In Seeds (B2/S) every cell dies, a domino gives birth to two cells on either side
+        let mut hashlife = HashLife::new("B2/S".parse().unwrap()).unwrap();
+        hashlife.set_alive_at(Coordinate { x: 0, y: 0 });
+        hashlife.set_alive_at(Coordinate { x: 1, y: 0 });
+
+        hashlife.advance(1);
+
+        let expected = vec![
+            Coordinate { x: 0, y: -1 },
+            Coordinate { x: 1, y: -1 },
+            Coordinate { x: 0, y: 1 },
+            Coordinate { x: 1, y: 1 },
+        ];
+        assert_eq!(expected, hashlife.alive_cells());
+    }
+
+    #[test]
+    fn rejects_b0_rules() {
+        let rule: Rule = "B0/S".parse().unwrap();
+        let error = HashLifeError::UnsupportedRule(UnsupportedRule(rule.clone()));
+        assert_eq!(
+            Err(error),
+            HashLifeCalculator::new(VIEWPORT, rule, 3).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn rejects_steps_too_large() {
+        assert_eq!(
+            Err(HashLifeError::StepTooLarge(32)),
+            HashLifeCalculator::new(VIEWPORT, Rule::conway(), 32).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn lists_only_cells_within_viewport() {
+        let far_away = Coordinate { x: 1 << 40, y: 0 };
+        let hashlife = create_hashlife(&[GLIDER[0], far_away]);
+        assert_eq!(vec![GLIDER[0]], hashlife.alive_cells_within(&VIEWPORT));
+    }
+
+    #[test]
+    fn calculator_jumps_quadtree_of_its_grid() {
+        let generation_calculator = HashLifeCalculator::new(VIEWPORT, Rule::conway(), 3).unwrap();
+        let mut grid = generation_calculator.grid();
+        for coordinate in &GLIDER {
+            grid.set_alive_at(Position {
+                x: coordinate.x as u32,
+                y: coordinate.y as u32,
+            });
+        }
+
+        let changes = generation_calculator.next_generation(&grid);
+        assert_eq!(GLIDER.to_vec(), grid.plane().borrow().alive_cells());
+        for change in &changes {
+            grid.set_state_at(change.position, change.state);
+        }
+        generation_calculator.accept_changes(&changes);
+
+        // 8 generations move the glider two cells down and to the right
+        let expected = translate(&GLIDER, 2, 2);
+        assert_eq!(expected, grid.plane().borrow().alive_cells());
+        assert_eq!(8, grid.plane().borrow().generation());
+        assert_eq!(8, generation_calculator.generations_per_step());
+        for change in &changes {
+            let coordinate = grid.viewport().to_coordinate(change.position);
+            assert_eq!(expected.contains(&coordinate), change.is_alive());
+        }
+        let born = changes.iter().filter(|change| change.is_alive()).count();
+        let died = changes.len() - born;
+        assert_eq!(died, born);
+    }
+
+    #[test]
+    fn calculator_restores_cells_beyond_viewport() {
+        let generation_calculator = HashLifeCalculator::new(VIEWPORT, Rule::conway(), 3).unwrap();
+        let grid = generation_calculator.grid();
+        let hashlife = grid.plane();
+        let beyond = translate(&GLIDER, 20, 0);
+        for &coordinate in &beyond {
+            hashlife.borrow_mut().set_alive_at(coordinate);
+        }
+
+        let changes = generation_calculator.next_generation(&grid);
+        assert!(changes.is_empty());
+        generation_calculator.accept_changes(&changes);
+        assert_eq!(translate(&beyond, 2, 2), hashlife.borrow().alive_cells());
+
+        generation_calculator.restore_generation(0);
+        assert_eq!(beyond, hashlife.borrow().alive_cells());
+        assert_eq!(0, hashlife.borrow().generation());
+
+        generation_calculator.clear();
+        assert!(hashlife.borrow().alive_cells().is_empty());
+    }
+}
@@ -0,0 +1,415 @@
+use crate::generation_calculator::{
+    changes_by_cell, Change, GenerationCalculator, NEIGHBOUR_OFFSETS,
+};
+use crate::grid::{Grid, Position, ALIVE, DEAD};
+use crate::rule::{split_parts, RuleParseError};
+use std::fmt;
+use std::str::FromStr;
+
+const CONFIGURATIONS: usize = 256;
+
+// One neighbourhood per Hensel letter, bit i standing for the neighbour at NEIGHBOUR_OFFSETS[i].
+// Counts above four use the complements of the neighbourhoods with 8 - count neighbours.
+const LETTERS: [&[(char, u8)]; 5] = [
+    &[],
+    &[('c', 0b1000_0000), ('e', 0b0000_0001)],
+    &[
+        ('c', 0b1000_0010),
+        ('e', 0b0100_0001),
+        ('k', 0b1000_0100),
+        ('a', 0b1000_0001),
+        ('i', 0b0100_0100),
+        ('n', 0b0010_0010),
+    ],
+    &[
+        ('c', 0b1010_0010),
+        ('e', 0b0100_0101),
+        ('k', 0b0010_0101),
+        ('a', 0b1100_0001),
+        ('i', 0b1000_0011),
+        ('n', 0b1100_0010),
+        ('y', 0b1010_0100),
+        ('q', 0b0010_0011),
+        ('j', 0b0100_0011),
+        ('r', 0b1100_0100),
+    ],
+    &[
+        ('c', 0b1010_1010),
+        ('e', 0b0101_0101),
+        ('k', 0b1010_0101),
+        ('a', 0b1100_0011),
+        ('i', 0b1100_0110),
+        ('n', 0b1010_0011),
+        ('y', 0b0110_0011),
+        ('q', 0b0010_0111),
+        ('j', 0b0110_0101),
+        ('r', 0b1100_0101),
+        ('t', 0b1010_0110),
+        ('w', 0b1110_0100),
+        ('z', 0b0110_0110),
+    ],
+];
+
+// An isotropic non-totalistic rule, which looks at the shape of a neighbourhood
+// instead of only counting it, e.g. "B2-a/S12" or "B3/S2-i34q"
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct HenselRule {
+    birth: Vec<bool>,
+    survival: Vec<bool>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum HenselRuleParseError {
+    Rule(RuleParseError),
+    InvalidLetter(u32, char),
+    MissingLetters(u32),
+}
+
+impl HenselRule {
+    pub fn is_born(&self, neighbourhood: u8) -> bool {
+        self.birth[neighbourhood as usize]
+    }
+
+    pub fn survives(&self, neighbourhood: u8) -> bool {
+        self.survival[neighbourhood as usize]
+    }
+}
+
+impl FromStr for HenselRule {
+    type Err = HenselRuleParseError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let (birth, survival) = split_parts(rule).map_err(HenselRuleParseError::Rule)?;
+        Ok(HenselRule {
+            birth: parse_neighbourhoods(birth)?,
+            survival: parse_neighbourhoods(survival)?,
+        })
+    }
+}
+
+fn parse_neighbourhoods(part: &str) -> Result<Vec<bool>, HenselRuleParseError> {
+    let mut neighbourhoods = vec![false; CONFIGURATIONS];
+    let mut listed_counts = [false; 9];
+    let mut characters = part.chars().peekable();
+    while let Some(character) = characters.next() {
+        let neighbours = character
+            .to_digit(10)
+            .filter(|&neighbours| neighbours <= 8)
+            .ok_or(HenselRuleParseError::Rule(
+                RuleParseError::InvalidNeighbourCount(character),
+            ))?;
+        if listed_counts[neighbours as usize] {
+            return Err(HenselRuleParseError::Rule(
+                RuleParseError::DuplicateNeighbourCount(neighbours),
+            ));
+        }
+        listed_counts[neighbours as usize] = true;
+
+        let is_excluding = characters.peek() == Some(&'-');
+        if is_excluding {
+            characters.next();
+        }
+        let mut listed = Vec::new();
+        while let Some(&letter) = characters.peek() {
+            if !letter.is_alphabetic() {
+                break;
+            }
+            characters.next();
+            let (_, neighbourhood) = letters(neighbours)
+                .into_iter()
+                .find(|&(known, _)| known == letter)
+                .ok_or(HenselRuleParseError::InvalidLetter(neighbours, letter))?;
+            listed.extend(symmetries(neighbourhood));
+        }
+        if is_excluding && listed.is_empty() {
+            return Err(HenselRuleParseError::MissingLetters(neighbours));
+        }
+
+        // A count without letters stands for all of its neighbourhoods
+        for neighbourhood in 0..=255u8 {
+            if neighbourhood.count_ones() == neighbours {
+                neighbourhoods[neighbourhood as usize] =
+                    listed.is_empty() || listed.contains(&neighbourhood) != is_excluding;
+            }
+        }
+    }
+    Ok(neighbourhoods)
+}
+
+fn letters(neighbours: u32) -> Vec<(char, u8)> {
+    if neighbours <= 4 {
+        LETTERS[neighbours as usize].to_vec()
+    } else {
+        LETTERS[8 - neighbours as usize]
+            .iter()
+            .map(|&(letter, neighbourhood)| (letter, !neighbourhood))
+            .collect()
+    }
+}
+
+// The neighbourhood in all four rotations, each also mirrored
+fn symmetries(neighbourhood: u8) -> Vec<u8> {
+    let mut symmetries = Vec::new();
+    let mut rotated = neighbourhood;
+    for _ in 0..4 {
+        symmetries.push(rotated);
+        symmetries.push(mirrored(rotated));
+        rotated = rotated.rotate_left(2);
+    }
+    symmetries
+}
+
+fn mirrored(neighbourhood: u8) -> u8 {
+    (0..8)
+        .filter(|&bit| neighbourhood & (1 << bit) != 0)
+        .fold(0, |mirrored, bit| mirrored | 1 << ((8 - bit) % 8))
+}
+
+impl fmt::Display for HenselRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        write_neighbourhoods(f, &self.birth)?;
+        write!(f, "/S")?;
+        write_neighbourhoods(f, &self.survival)
+    }
+}
+
+// Writes whichever of the listed or the excluded letters is shorter
+fn write_neighbourhoods(f: &mut fmt::Formatter, neighbourhoods: &[bool]) -> fmt::Result {
+    for neighbours in 0..=8 {
+        let letters = letters(neighbours);
+        let (listed, excluded): (Vec<_>, Vec<_>) = letters
+            .iter()
+            .partition(|&&(_, neighbourhood)| neighbourhoods[neighbourhood as usize]);
+        let is_listed = |neighbourhood: u8| {
+            neighbourhood.count_ones() == neighbours && neighbourhoods[neighbourhood as usize]
+        };
+        if excluded.is_empty() && (0..=255u8).any(is_listed) {
+            write!(f, "{}", neighbours)?;
+        } else if listed.len() > excluded.len() {
+            write!(f, "{}-", neighbours)?;
+            for (letter, _) in excluded {
+                write!(f, "{}", letter)?;
+            }
+        } else if !listed.is_empty() {
+            write!(f, "{}", neighbours)?;
+            for (letter, _) in listed {
+                write!(f, "{}", letter)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for HenselRuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HenselRuleParseError::Rule(error) => write!(f, "{}", error),
+            HenselRuleParseError::InvalidLetter(neighbours, letter) => write!(
+                f,
+                "'{}' is not a Hensel letter for {} neighbours",
+                letter, neighbours
+            ),
+            HenselRuleParseError::MissingLetters(neighbours) => {
+                write!(f, "expected letters after '{}-'", neighbours)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HenselGenerationCalculator {
+    rule: HenselRule,
+}
+
+impl HenselGenerationCalculator {
+    pub fn new(rule: HenselRule) -> Self {
+        HenselGenerationCalculator { rule }
+    }
+}
+
+impl GenerationCalculator for HenselGenerationCalculator {
+    fn next_generation(&self, grid: &dyn Grid) -> Vec<Change> {
+        changes_by_cell(grid, |position, state| {
+            let neighbourhood = neighbourhood_at(grid, position).expect("x or y out of bounds");
+            match state {
+                ALIVE if !self.rule.survives(neighbourhood) => DEAD,
+                ALIVE => ALIVE,
+                _ if self.rule.is_born(neighbourhood) => ALIVE,
+                state => state,
+            }
+        })
+    }
+}
+
+// The alive neighbours as bits in the order of NEIGHBOUR_OFFSETS
+pub fn neighbourhood_at(grid: &dyn Grid, position: Position) -> Option<u8> {
+    let width = grid.width();
+    let height = grid.height();
+    if position.x >= width || position.y >= height {
+        return None;
+    }
+
+    let topology = grid.topology();
+    let neighbourhood = NEIGHBOUR_OFFSETS
+        .iter()
+        .enumerate()
+        .filter(|&(_, &(delta_x, delta_y))| {
+            topology
+                .resolve(
+                    width,
+                    height,
+                    i64::from(position.x) + delta_x,
+                    i64::from(position.y) + delta_y,
+                )
+                .is_some_and(|neighbour| grid.is_alive_at(neighbour))
+        })
+        .fold(0, |neighbourhood, (bit, _)| neighbourhood | 1 << bit);
+    Some(neighbourhood)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::GridImpl;
+    use crate::test_util::{alive_coordinates, assert_plays_like_life, step};
+
+    const NORTH: u8 = 0b0000_0001;
+    const NORTH_EAST: u8 = 0b0000_0010;
+    const EAST: u8 = 0b0000_0100;
+    const SOUTH_EAST: u8 = 0b0000_1000;
+    const SOUTH: u8 = 0b0001_0000;
+    const WEST: u8 = 0b0100_0000;
+    const NORTH_WEST: u8 = 0b1000_0000;
+
+    fn create_grid(width: u32, height: u32, alive: &[(u32, u32)]) -> GridImpl {
+        let mut grid = GridImpl::new(width, height);
+        for &(x, y) in alive {
+            grid.set_alive_at(Position { x, y });
+        }
+        grid
+    }
+
+    #[test]
+    fn letters_split_every_count_into_shapes() {
+        for neighbours in 1..8 {
+            let mut seen = Vec::new();
+            for (_, neighbourhood) in letters(neighbours) {
+                assert_eq!(neighbours, neighbourhood.count_ones());
+                let shape = symmetries(neighbourhood);
+                assert!(shape
+                    .iter()
+                    .all(|neighbourhood| !seen.contains(neighbourhood)));
+                seen.extend(shape);
+            }
+            seen.sort();
+            seen.dedup();
+            let expected = (0..=255u8)
+                .filter(|neighbourhood| neighbourhood.count_ones() == neighbours)
+                .count();
+            assert_eq!(expected, seen.len());
+        }
+    }
+
+    #[test]
+    fn applies_letters_in_every_orientation() {
+        let rule: HenselRule = "B2-a/S12".parse().unwrap();
+        for &adjacent in &[
+            NORTH | NORTH_EAST,
+            NORTH | NORTH_WEST,
+            EAST | SOUTH_EAST,
+            WEST | NORTH_WEST,
+        ] {
+            assert!(!rule.is_born(adjacent));
+        }
+        assert!(rule.is_born(NORTH | SOUTH));
+        assert!(rule.is_born(NORTH | EAST));
+        assert!(rule.is_born(NORTH_WEST | SOUTH_EAST));
+        assert!(rule.survives(NORTH | NORTH_EAST));
+    }
+
+    #[test]
+    fn totalistic_rule_plays_like_life() {
+        let calculator = HenselGenerationCalculator::new("B3/S23".parse().unwrap());
+        assert_plays_like_life(&calculator);
+        assert_eq!(
+            "B3/S23".parse::<HenselRule>(),
+            "B3cekainyqjr/S2cekain3".parse()
+        );
+    }
+
+    #[test]
+    fn keeps_domino_still_in_b2_minus_a() {
+        // Every neighbour of a domino sees it as a 2a shape, so nothing is born
+        let mut grid = create_grid(6, 5, &[(2, 2), (3, 2)]);
+        let calculator = HenselGenerationCalculator::new("B2-a/S12".parse().unwrap());
+
+        step(&mut grid, &calculator);
+
+        assert_eq!(vec![(2, 2), (3, 2)], alive_coordinates(&grid));
+    }
+
+    #[test]
+    fn fills_and_clears_block_in_b2_minus_a() {
+        let mut grid = create_grid(6, 6, &[(2, 2), (3, 3)]);
+        let calculator = HenselGenerationCalculator::new("B2-a/S12".parse().unwrap());
+
+        step(&mut grid, &calculator);
+        assert_eq!(
+            vec![(2, 2), (3, 2), (2, 3), (3, 3)],
+            alive_coordinates(&grid)
+        );
+
+        step(&mut grid, &calculator);
+        assert!(alive_coordinates(&grid).is_empty());
+    }
+
+    #[test]
+    fn plays_tlife() {
+        let calculator = HenselGenerationCalculator::new("B3/S2-i34q".parse().unwrap());
+        let block = [(2, 2), (3, 2), (2, 3), (3, 3)];
+        let mut grid = create_grid(6, 6, &block);
+        step(&mut grid, &calculator);
+        assert_eq!(block.to_vec(), alive_coordinates(&grid));
+
+        // The middle of a blinker sees a 2i shape and dies
+        let mut grid = create_grid(5, 5, &[(1, 2), (2, 2), (3, 2)]);
+        step(&mut grid, &calculator);
+        assert_eq!(vec![(2, 1), (2, 3)], alive_coordinates(&grid));
+        step(&mut grid, &calculator);
+        assert!(alive_coordinates(&grid).is_empty());
+    }
+
+    #[test]
+    fn writes_shortest_notation() {
+        for &rule in &["B2-a/S12", "B3/S2-i34q", "B2cn3-ce/S0", "B/S8"] {
+            assert_eq!(rule, rule.parse::<HenselRule>().unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn fails_on_invalid_letters() {
+        assert_eq!(
+            Err(HenselRuleParseError::InvalidLetter(2, 'x')),
+            "B2x/S".parse::<HenselRule>()
+        );
+        assert_eq!(
+            Err(HenselRuleParseError::InvalidLetter(0, 'c')),
+            "B0c/S".parse::<HenselRule>()
+        );
+        assert_eq!(
+            Err(HenselRuleParseError::MissingLetters(2)),
+            "B2-/S".parse::<HenselRule>()
+        );
+        assert_eq!(
+            Err(HenselRuleParseError::Rule(
+                RuleParseError::DuplicateNeighbourCount(2)
+            )),
+            "B2a2c/S".parse::<HenselRule>()
+        );
+        assert_eq!(
+            Err(HenselRuleParseError::Rule(RuleParseError::MixedNotation)),
+            "B2a/12".parse::<HenselRule>()
+        );
+    }
+}
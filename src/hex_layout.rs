@@ -0,0 +1,132 @@
+use crate::grid::Position;
+use crate::grid_info::GridInfo;
+
+// Pointy-topped hexagonal cells on the board, with odd rows shifted half a cell to the right.
+// The hexagons of neighbouring rows interlock, so every hexagon is a third taller than its row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HexLayout {
+    columns: u32,
+    rows: u32,
+    cell_width: f64,
+    row_height: f64,
+}
+
+impl HexLayout {
+    pub fn new(grid_info: &GridInfo) -> Self {
+        HexLayout {
+            columns: grid_info.columns,
+            rows: grid_info.rows,
+            cell_width: f64::from(grid_info.width) / (f64::from(grid_info.columns) + 0.5),
+            row_height: f64::from(grid_info.height) / f64::from(grid_info.rows),
+        }
+    }
+
+    pub fn centre(&self, position: &Position) -> (f64, f64) {
+        let shift = if position.y.is_multiple_of(2) {
+            0.5
+        } else {
+            1.0
+        };
+        (
+            (f64::from(position.x) + shift) * self.cell_width,
+            (f64::from(position.y) + 0.5) * self.row_height,
+        )
+    }
+
+    // Starting at the top and going clockwise
+    pub fn corners(&self, position: &Position) -> Vec<(f64, f64)> {
+        let (x, y) = self.centre(position);
+        let half_width = self.cell_width / 2.0;
+        let third_height = self.row_height / 3.0;
+        vec![
+            (x, y - 2.0 * third_height),
+            (x + half_width, y - third_height),
+            (x + half_width, y + third_height),
+            (x, y + 2.0 * third_height),
+            (x - half_width, y + third_height),
+            (x - half_width, y - third_height),
+        ]
+    }
+
+    // The cell whose centre is closest, measured as if the hexagons were regular,
+    // which makes the closest centre the one of the hexagon containing the point
+    pub fn cell_at(&self, x: f64, y: f64) -> Option<Position> {
+        let width = (f64::from(self.columns) + 0.5) * self.cell_width;
+        let height = f64::from(self.rows) * self.row_height;
+        if x < 0.0 || y < 0.0 || x > width || y > height {
+            return None;
+        }
+        let row = (y / self.row_height) as u32;
+        let column = (x / self.cell_width) as u32;
+        let mut closest: Option<(f64, Position)> = None;
+        for candidate_y in row.saturating_sub(1)..(row + 2).min(self.rows) {
+            for candidate_x in column.saturating_sub(1)..(column + 2).min(self.columns) {
+                let candidate = Position {
+                    x: candidate_x,
+                    y: candidate_y,
+                };
+                let (centre_x, centre_y) = self.centre(&candidate);
+                let delta_x = (x - centre_x) / self.cell_width * 3f64.sqrt();
+                let delta_y = (y - centre_y) / self.row_height * 1.5;
+                let distance = delta_x * delta_x + delta_y * delta_y;
+                if closest.is_none_or(|(closest_distance, _)| distance < closest_distance) {
+                    closest = Some((distance, candidate));
+                }
+            }
+        }
+        closest.map(|(_, position)| position)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_layout() -> HexLayout {
+        HexLayout::new(&GridInfo {
+            width: 105,
+            height: 90,
+            rows: 6,
+            columns: 10,
+        })
+    }
+
+    #[test]
+    fn shifts_odd_rows() {
+        let layout = create_layout();
+        assert_eq!((5.0, 7.5), layout.centre(&Position { x: 0, y: 0 }));
+        assert_eq!((20.0, 22.5), layout.centre(&Position { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn interlocks_neighbouring_rows() {
+        let layout = create_layout();
+        let upper = layout.corners(&Position { x: 1, y: 0 });
+        let lower = layout.corners(&Position { x: 1, y: 1 });
+        // The lower right edge of a hexagon is the upper left edge of the one below it
+        assert_eq!((upper[3], upper[2]), (lower[5], lower[0]));
+    }
+
+    #[test]
+    fn finds_cell_at_every_centre() {
+        let layout = create_layout();
+        for y in 0..6 {
+            for x in 0..10 {
+                let position = Position { x, y };
+                let (centre_x, centre_y) = layout.centre(&position);
+                assert_eq!(Some(position), layout.cell_at(centre_x, centre_y));
+            }
+        }
+    }
+
+    #[test]
+    fn finds_cell_near_interlocking_corners() {
+        let layout = create_layout();
+        // Just above the bottom corner of (1, 0), which reaches into the odd row below
+        assert_eq!(Some(Position { x: 1, y: 0 }), layout.cell_at(15.0, 16.5));
+        // Beside it, the hexagons of the odd row take over
+        assert_eq!(Some(Position { x: 0, y: 1 }), layout.cell_at(12.0, 16.5));
+        assert_eq!(Some(Position { x: 1, y: 1 }), layout.cell_at(18.0, 16.5));
+        assert_eq!(None, layout.cell_at(106.0, 10.0));
+    }
+}
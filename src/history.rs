@@ -0,0 +1,323 @@
+use crate::generation_calculator::Change;
+use crate::grid::Grid;
+use crate::infinite_grid::Coordinate;
+use std::collections::VecDeque;
+
+// Every batch is stored together with the changes reverting it,
+// as cells with more than two states cannot simply be flipped back
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Batch {
+    changes: Vec<Change>,
+    reverted: Vec<Change>,
+    // The generations before and after, for batches computed by stepping
+    generations: Option<(u64, u64)>,
+    // The origins of the viewport before and after, for batches that moved it
+    origins: Option<(Coordinate, Coordinate)>,
+}
+
+// The changes undoing or redoing a batch,
+// along with the generation the board is at afterwards if the batch was a step
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Revision {
+    pub changes: Vec<Change>,
+    pub generation: Option<u64>,
+    // The origin of the viewport the changes are seen from and the one it is left at,
+    // if the batch moved the viewport
+    pub origins: Option<(Coordinate, Coordinate)>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct History {
+    undo_stack: VecDeque<Batch>,
+    redo_stack: Vec<Batch>,
+    capacity: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, changes: &[Change], reverted: &[Change]) {
+        self.push_batch(changes, reverted, None, None);
+    }
+
+    // Records changes that took the board from one generation to another
+    pub fn record_step(&mut self, changes: &[Change], reverted: &[Change], from: u64, to: u64) {
+        self.push_batch(changes, reverted, Some((from, to)), None);
+    }
+
+    // Records moving the viewport of a board larger than the grid
+    pub fn record_move(&mut self, from: Coordinate, to: Coordinate) {
+        self.push_batch(&[], &[], None, Some((from, to)));
+    }
+
+    // Records a step to a generation that was seen from another origin,
+    // with the changes seen from there after the viewport was moved
+    pub fn record_jump(
+        &mut self,
+        changes: &[Change],
+        reverted: &[Change],
+        generations: (u64, u64),
+        origins: (Coordinate, Coordinate),
+    ) {
+        self.push_batch(changes, reverted, Some(generations), Some(origins));
+    }
+
+    pub fn undo(&mut self) -> Option<Revision> {
+        let batch = self.undo_stack.pop_back()?;
+        let revision = Revision {
+            changes: batch.reverted.clone(),
+            generation: batch.generations.map(|(from, _)| from),
+            origins: batch.origins.map(|(from, to)| (to, from)),
+        };
+        self.redo_stack.push(batch);
+        Some(revision)
+    }
+
+    pub fn redo(&mut self) -> Option<Revision> {
+        let batch = self.redo_stack.pop()?;
+        let revision = Revision {
+            changes: batch.changes.clone(),
+            generation: batch.generations.map(|(_, to)| to),
+            origins: batch.origins.map(|(_, to)| (to, to)),
+        };
+        self.push_undo(batch);
+        Some(revision)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    fn push_batch(
+        &mut self,
+        changes: &[Change],
+        reverted: &[Change],
+        generations: Option<(u64, u64)>,
+        origins: Option<(Coordinate, Coordinate)>,
+    ) {
+        let moves_viewport = origins.is_some_and(|(from, to)| from != to);
+        if (changes.is_empty() && !moves_viewport) || self.capacity == 0 {
+            return;
+        }
+        self.redo_stack.clear();
+        self.push_undo(Batch {
+            changes: changes.to_vec(),
+            reverted: reverted.to_vec(),
+            generations,
+            origins,
+        });
+    }
+
+    fn push_undo(&mut self, batch: Batch) {
+        if self.undo_stack.len() == self.capacity {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(batch);
+    }
+}
+
+// The changes restoring the grid's current states, meant to be taken before the changes are applied
+pub fn reverted(changes: &[Change], grid: &dyn Grid) -> Vec<Change> {
+    changes
+        .iter()
+        .rev()
+        .map(|change| Change {
+            position: change.position,
+            state: grid.state_at(change.position),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::{GridImpl, Position, ALIVE, DEAD};
+
+    fn change(x: u32, is_alive: bool) -> Change {
+        Change {
+            position: Position { x, y: 0 },
+            state: if is_alive { ALIVE } else { DEAD },
+        }
+    }
+
+    // Records two-state changes, which are reverted by flipping them
+    fn record(history: &mut History, changes: &[Change]) {
+        let reverted = changes
+            .iter()
+            .rev()
+            .map(|recorded| change(recorded.position.x, !recorded.is_alive()))
+            .collect::<Vec<_>>();
+        history.record(changes, &reverted);
+    }
+
+    fn edit(changes: Vec<Change>) -> Option<Revision> {
+        Some(Revision {
+            changes,
+            generation: None,
+            origins: None,
+        })
+    }
+
+    #[test]
+    fn empty_history_cannot_undo_or_redo() {
+        let mut history = History::new(10);
+        assert!(!history.can_undo());
+        assert!(!history.can_redo());
+        assert_eq!(None, history.undo());
+        assert_eq!(None, history.redo());
+    }
+
+    #[test]
+    fn undo_inverts_last_batch() {
+        let mut history = History::new(10);
+        record(&mut history, &[change(1, true)]);
+        record(&mut history, &[change(2, true), change(3, false)]);
+
+        assert_eq!(
+            edit(vec![change(3, true), change(2, false)]),
+            history.undo()
+        );
+        assert_eq!(edit(vec![change(1, false)]), history.undo());
+        assert_eq!(None, history.undo());
+    }
+
+    #[test]
+    fn redo_reapplies_undone_batches() {
+        let mut history = History::new(10);
+        record(&mut history, &[change(1, true)]);
+        record(&mut history, &[change(2, false)]);
+        history.undo();
+        history.undo();
+
+        assert_eq!(edit(vec![change(1, true)]), history.redo());
+        assert_eq!(edit(vec![change(2, false)]), history.redo());
+        assert_eq!(None, history.redo());
+        assert_eq!(edit(vec![change(2, true)]), history.undo());
+    }
+
+    #[test]
+    fn recording_discards_redo() {
+        let mut history = History::new(10);
+        record(&mut history, &[change(1, true)]);
+        history.undo();
+        record(&mut history, &[change(2, true)]);
+
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn ignores_empty_batches() {
+        let mut history = History::new(10);
+        record(&mut history, &[]);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn forgets_oldest_batch_when_full() {
+        let mut history = History::new(2);
+        record(&mut history, &[change(1, true)]);
+        record(&mut history, &[change(2, true)]);
+        record(&mut history, &[change(3, true)]);
+
+        assert_eq!(edit(vec![change(3, false)]), history.undo());
+        assert_eq!(edit(vec![change(2, false)]), history.undo());
+        assert_eq!(None, history.undo());
+    }
+
+    #[test]
+    fn restores_generations_of_steps() {
+        let mut history = History::new(10);
+        record(&mut history, &[change(1, true)]);
+        history.record_step(&[change(2, true)], &[change(2, false)], 4, 5);
+
+        let undone = Revision {
+            changes: vec![change(2, false)],
+            generation: Some(4),
+            origins: None,
+        };
+        assert_eq!(Some(undone), history.undo());
+        assert_eq!(edit(vec![change(1, false)]), history.undo());
+        history.redo();
+        assert_eq!(
+            Some(5),
+            history.redo().and_then(|revision| revision.generation)
+        );
+    }
+
+    #[test]
+    fn moves_viewport_back_and_forth() {
+        let from = Coordinate { x: 0, y: 0 };
+        let to = Coordinate { x: -3, y: 7 };
+        let mut history = History::new(10);
+        history.record_move(from, to);
+        history.record_move(to, to);
+
+        let undone = Revision {
+            changes: Vec::new(),
+            generation: None,
+            origins: Some((to, from)),
+        };
+        assert_eq!(Some(undone), history.undo());
+        assert_eq!(None, history.undo());
+        assert_eq!(
+            Some(Some((to, to))),
+            history.redo().map(|revision| revision.origins)
+        );
+    }
+
+    #[test]
+    fn sees_changes_of_jumps_from_target_origin() {
+        let from = Coordinate { x: 5, y: 5 };
+        let to = Coordinate { x: 0, y: 0 };
+        let mut history = History::new(10);
+        history.record_jump(&[change(2, true)], &[change(2, false)], (12, 4), (from, to));
+
+        let undone = Revision {
+            changes: vec![change(2, false)],
+            generation: Some(12),
+            origins: Some((to, from)),
+        };
+        assert_eq!(Some(undone), history.undo());
+        let redone = Revision {
+            changes: vec![change(2, true)],
+            generation: Some(4),
+            origins: Some((to, to)),
+        };
+        assert_eq!(Some(redone), history.redo());
+    }
+
+    #[test]
+    fn reverts_to_previous_states() {
+        let mut grid = GridImpl::new(5, 1);
+        grid.set_state_at(Position { x: 1, y: 0 }, 3);
+        let changes = [
+            Change {
+                position: Position { x: 1, y: 0 },
+                state: 4,
+            },
+            change(2, true),
+        ];
+        let mut history = History::new(10);
+        history.record(&changes, &reverted(&changes, &grid));
+
+        let expected = vec![
+            change(2, false),
+            Change {
+                position: Position { x: 1, y: 0 },
+                state: 3,
+            },
+        ];
+        assert_eq!(edit(expected), history.undo());
+        assert_eq!(edit(changes.to_vec()), history.redo());
+    }
+}
@@ -0,0 +1,733 @@
+use crate::generation_calculator::{Change, GenerationCalculator, NEIGHBOUR_OFFSETS};
+use crate::grid::{Grid, Position, ALIVE, DEAD};
+use crate::rule::Rule;
+use crate::topology::Topology;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct Coordinate {
+    pub x: i64,
+    pub y: i64,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Viewport {
+    pub origin: Coordinate,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Viewport {
+    pub fn move_by(&mut self, columns: i64, rows: i64) {
+        self.origin.x += columns;
+        self.origin.y += rows;
+    }
+
+    pub fn to_coordinate(&self, position: Position) -> Coordinate {
+        Coordinate {
+            x: self.origin.x + i64::from(position.x),
+            y: self.origin.y + i64::from(position.y),
+        }
+    }
+
+    pub fn to_position(&self, coordinate: Coordinate) -> Option<Position> {
+        let x = coordinate.x - self.origin.x;
+        let y = coordinate.y - self.origin.y;
+        if x < 0 || y < 0 || x >= i64::from(self.width) || y >= i64::from(self.height) {
+            None
+        } else {
+            Some(Position {
+                x: x as u32,
+                y: y as u32,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct Plane {
+    alive_cells: HashSet<Coordinate>,
+}
+
+impl Plane {
+    pub fn new() -> Self {
+        Plane::default()
+    }
+
+    pub fn is_alive_at(&self, coordinate: Coordinate) -> bool {
+        self.alive_cells.contains(&coordinate)
+    }
+
+    pub fn set_alive_at(&mut self, coordinate: Coordinate) {
+        self.alive_cells.insert(coordinate);
+    }
+
+    pub fn set_dead_at(&mut self, coordinate: Coordinate) {
+        self.alive_cells.remove(&coordinate);
+    }
+
+    pub fn population(&self) -> usize {
+        self.alive_cells.len()
+    }
+
+    pub fn alive_cells(&self) -> Vec<Coordinate> {
+        let mut alive_cells = self.alive_cells.iter().cloned().collect::<Vec<_>>();
+        alive_cells.sort_by_key(|coordinate| (coordinate.y, coordinate.x));
+        alive_cells
+    }
+
+    pub fn bounding_box(&self) -> Option<(Coordinate, Coordinate)> {
+        let mut cells = self.alive_cells.iter();
+        let first = *cells.next()?;
+        Some(cells.fold((first, first), |(min, max), cell| {
+            (
+                Coordinate {
+                    x: min.x.min(cell.x),
+                    y: min.y.min(cell.y),
+                },
+                Coordinate {
+                    x: max.x.max(cell.x),
+                    y: max.y.max(cell.y),
+                },
+            )
+        }))
+    }
+
+    // Only live cells and their neighbours can change, so everything else is never visited
+    pub fn next_generation(&self, rule: &Rule) -> Vec<(Coordinate, bool)> {
+        let mut neighbour_counts: HashMap<Coordinate, u32> = HashMap::new();
+        for cell in &self.alive_cells {
+            neighbour_counts.entry(*cell).or_insert(0);
+            for &(delta_x, delta_y) in NEIGHBOUR_OFFSETS.iter() {
+                let neighbour = Coordinate {
+                    x: cell.x + delta_x,
+                    y: cell.y + delta_y,
+                };
+                *neighbour_counts.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+
+        let mut changes = neighbour_counts
+            .into_iter()
+            .filter_map(|(coordinate, neighbours)| {
+                let is_alive = self.is_alive_at(coordinate);
+                if is_alive && !rule.survives(neighbours) {
+                    Some((coordinate, false))
+                } else if !is_alive && rule.is_born(neighbours) {
+                    Some((coordinate, true))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        changes.sort_by_key(|(coordinate, _)| (coordinate.y, coordinate.x));
+        changes
+    }
+
+    pub fn apply(&mut self, changes: &[(Coordinate, bool)]) {
+        for &(coordinate, is_alive) in changes {
+            if is_alive {
+                self.set_alive_at(coordinate);
+            } else {
+                self.set_dead_at(coordinate);
+            }
+        }
+    }
+}
+
+// The cells of an infinite plane, stored the way the calculator advancing them needs
+pub trait PlaneCells {
+    fn is_alive_at(&self, coordinate: Coordinate) -> bool;
+    fn set_alive_at(&mut self, coordinate: Coordinate);
+    fn set_dead_at(&mut self, coordinate: Coordinate);
+}
+
+impl PlaneCells for Plane {
+    fn is_alive_at(&self, coordinate: Coordinate) -> bool {
+        self.is_alive_at(coordinate)
+    }
+    fn set_alive_at(&mut self, coordinate: Coordinate) {
+        self.set_alive_at(coordinate);
+    }
+    fn set_dead_at(&mut self, coordinate: Coordinate) {
+        self.set_dead_at(coordinate);
+    }
+}
+
+pub struct InfiniteGrid<P = Plane> {
+    plane: Rc<RefCell<P>>,
+    viewport: Rc<RefCell<Viewport>>,
+}
+
+impl InfiniteGrid {
+    pub fn new(viewport: Viewport) -> Self {
+        InfiniteGrid::with_plane(
+            Rc::new(RefCell::new(Plane::new())),
+            Rc::new(RefCell::new(viewport)),
+        )
+    }
+}
+
+impl<P> InfiniteGrid<P> {
+    // Both are shared, e.g. with the calculator that keeps the plane
+    pub fn with_plane(plane: Rc<RefCell<P>>, viewport: Rc<RefCell<Viewport>>) -> Self {
+        InfiniteGrid { plane, viewport }
+    }
+
+    pub fn plane(&self) -> Rc<RefCell<P>> {
+        Rc::clone(&self.plane)
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.viewport.borrow().clone()
+    }
+
+    // Moving the shared viewport makes the grid show another part of the plane
+    pub fn shared_viewport(&self) -> Rc<RefCell<Viewport>> {
+        Rc::clone(&self.viewport)
+    }
+}
+
+impl<P: PlaneCells> Grid for InfiniteGrid<P> {
+    fn width(&self) -> u32 {
+        self.viewport.borrow().width
+    }
+    fn height(&self) -> u32 {
+        self.viewport.borrow().height
+    }
+    fn topology(&self) -> Topology {
+        // Seen through the viewport, cells beyond its edges are unknown rather than wrapped
+        Topology::Bounded
+    }
+    fn is_alive_at(&self, position: Position) -> bool {
+        let coordinate = self.viewport.borrow().to_coordinate(position);
+        self.plane.borrow().is_alive_at(coordinate)
+    }
+    fn set_alive_at(&mut self, position: Position) {
+        let coordinate = self.viewport.borrow().to_coordinate(position);
+        self.plane.borrow_mut().set_alive_at(coordinate);
+    }
+    fn set_dead_at(&mut self, position: Position) {
+        let coordinate = self.viewport.borrow().to_coordinate(position);
+        self.plane.borrow_mut().set_dead_at(coordinate);
+    }
+}
+
+const MAX_STORED_CELLS: usize = 1 << 20;
+
+// The cells of a plane beyond the viewport of its grid, which the game can neither see nor
+// restore. Their calculator keeps the plane of every generation it steps through, so that
+// undoing or rewinding the visible cells can put the hidden ones back as well, wherever
+// the viewport has been moved since.
+pub struct HiddenCells {
+    plane: Rc<RefCell<Plane>>,
+    viewport: Rc<RefCell<Viewport>>,
+    generation: u64,
+    pending_step: Option<(Vec<(Coordinate, bool)>, u32)>,
+    generations: BTreeMap<u64, Vec<Coordinate>>,
+    stored_cells: usize,
+}
+
+impl HiddenCells {
+    pub fn new(grid: &InfiniteGrid) -> Self {
+        HiddenCells {
+            plane: grid.plane(),
+            viewport: grid.shared_viewport(),
+            generation: 0,
+            pending_step: None,
+            generations: BTreeMap::new(),
+            stored_cells: 0,
+        }
+    }
+
+    // Keeps the changes of a step until the game accepts it
+    // and reports the visible ones, which the game applies itself
+    pub fn prepare_step(
+        &mut self,
+        changes: Vec<(Coordinate, bool)>,
+        generations: u32,
+    ) -> Vec<Change> {
+        let visible_changes = {
+            let viewport = self.viewport.borrow();
+            changes
+                .iter()
+                .filter_map(|&(coordinate, is_alive)| {
+                    viewport.to_position(coordinate).map(|position| Change {
+                        position,
+                        state: if is_alive { ALIVE } else { DEAD },
+                    })
+                })
+                .collect()
+        };
+        self.pending_step = Some((changes, generations));
+        visible_changes
+    }
+
+    // Applies the changes beyond the viewport once the game has applied the visible ones.
+    // Edits are accepted as well, but leave nothing to apply.
+    pub fn accept_step(&mut self) {
+        let (changes, generations) = match self.pending_step.take() {
+            Some(step) => step,
+            None => return,
+        };
+        let later_generations = self.generations.split_off(&(self.generation + 1));
+        self.stored_cells -= later_generations.values().map(Vec::len).sum::<usize>();
+
+        // The generation stepped from is stored as well, with any edits made since reaching it
+        let mut previous_cells = self.plane.borrow().alive_cells.clone();
+        {
+            let viewport = self.viewport.borrow();
+            let mut plane = self.plane.borrow_mut();
+            for &(coordinate, is_alive) in &changes {
+                match viewport.to_position(coordinate) {
+                    // Already applied by the game
+                    Some(_) if is_alive => {
+                        previous_cells.remove(&coordinate);
+                    }
+                    Some(_) => {
+                        previous_cells.insert(coordinate);
+                    }
+                    None if is_alive => plane.set_alive_at(coordinate),
+                    None => plane.set_dead_at(coordinate),
+                }
+            }
+        }
+        let generation = self.generation;
+        self.store(generation, previous_cells.into_iter().collect());
+        self.generation += u64::from(generations);
+        let generation = self.generation;
+        let cells = self.plane.borrow().alive_cells();
+        self.store(generation, cells);
+    }
+
+    // Generations that were forgotten to save memory keep the current hidden cells
+    pub fn restore(&mut self, generation: u64) {
+        self.pending_step = None;
+        if let Some(cells) = self.generations.get(&generation) {
+            self.remove_hidden_cells();
+            let viewport = self.viewport.borrow();
+            let mut plane = self.plane.borrow_mut();
+            for &coordinate in cells {
+                if viewport.to_position(coordinate).is_none() {
+                    plane.set_alive_at(coordinate);
+                }
+            }
+        }
+        self.generation = generation;
+    }
+
+    // Earlier generations keep their hidden cells, so rewinding brings them back
+    pub fn clear(&mut self) {
+        self.remove_hidden_cells();
+    }
+
+    fn remove_hidden_cells(&self) {
+        let viewport = self.viewport.borrow();
+        self.plane
+            .borrow_mut()
+            .alive_cells
+            .retain(|&coordinate| viewport.to_position(coordinate).is_some());
+    }
+
+    fn store(&mut self, generation: u64, cells: Vec<Coordinate>) {
+        self.stored_cells += cells.len();
+        if let Some(previous_cells) = self.generations.insert(generation, cells) {
+            self.stored_cells -= previous_cells.len();
+        }
+        while self.stored_cells > MAX_STORED_CELLS && self.generations.len() > 1 {
+            let oldest = *self.generations.keys().next().expect("stored generations");
+            if let Some(cells) = self.generations.remove(&oldest) {
+                self.stored_cells -= cells.len();
+            }
+        }
+    }
+}
+
+// Rules giving birth to cells without neighbours would fill the whole plane at once
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct UnsupportedRule(pub Rule);
+
+impl fmt::Display for UnsupportedRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "rule {} gives birth to cells without neighbours, \
+             which would fill the infinite plane",
+            self.0
+        )
+    }
+}
+
+// Advances the plane shared with an InfiniteGrid. The changes visible through
+// the grid's viewport are reported, the ones beyond it are applied once the game
+// accepts the step.
+pub struct InfiniteGenerationCalculator {
+    plane: Rc<RefCell<Plane>>,
+    hidden_cells: RefCell<HiddenCells>,
+    rule: Rule,
+}
+
+impl InfiniteGenerationCalculator {
+    pub fn new(grid: &InfiniteGrid, rule: Rule) -> Result<Self, UnsupportedRule> {
+        if rule.is_born(0) {
+            return Err(UnsupportedRule(rule));
+        }
+        Ok(InfiniteGenerationCalculator {
+            plane: grid.plane(),
+            hidden_cells: RefCell::new(HiddenCells::new(grid)),
+            rule,
+        })
+    }
+}
+
+impl GenerationCalculator for InfiniteGenerationCalculator {
+    fn next_generation(&self, _grid: &dyn Grid) -> Vec<Change> {
+        let changes = self.plane.borrow().next_generation(&self.rule);
+        self.hidden_cells.borrow_mut().prepare_step(changes, 1)
+    }
+
+    fn accept_changes(&self, _changes: &[Change]) {
+        self.hidden_cells.borrow_mut().accept_step();
+    }
+
+    fn restore_generation(&self, generation: u64) {
+        self.hidden_cells.borrow_mut().restore(generation);
+    }
+
+    fn clear(&self) {
+        self.hidden_cells.borrow_mut().clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generation_calculator::GenerationCalculatorImpl;
+    use crate::grid::GridImpl;
+    use crate::test_util::step;
+
+    const VIEWPORT: Viewport = Viewport {
+        origin: Coordinate { x: -5, y: 10 },
+        width: 8,
+        height: 6,
+    };
+
+    const GLIDER: [Coordinate; 5] = [
+        Coordinate { x: 1, y: 0 },
+        Coordinate { x: 2, y: 1 },
+        Coordinate { x: 0, y: 2 },
+        Coordinate { x: 1, y: 2 },
+        Coordinate { x: 2, y: 2 },
+    ];
+
+    fn translate(cells: &[Coordinate], generations: i64) -> Vec<Coordinate> {
+        cells
+            .iter()
+            .map(|coordinate| Coordinate {
+                x: coordinate.x + generations,
+                y: coordinate.y + generations,
+            })
+            .collect()
+    }
+
+    fn create_plane(alive_cells: &[Coordinate]) -> Plane {
+        let mut plane = Plane::new();
+        for &coordinate in alive_cells {
+            plane.set_alive_at(coordinate);
+        }
+        plane
+    }
+
+    #[test]
+    fn viewport_translates_to_coordinate() {
+        let coordinate = VIEWPORT.to_coordinate(Position { x: 2, y: 3 });
+        assert_eq!(Coordinate { x: -3, y: 13 }, coordinate);
+    }
+
+    #[test]
+    fn viewport_translates_to_position() {
+        let position = VIEWPORT.to_position(Coordinate { x: 2, y: 15 });
+        assert_eq!(Some(Position { x: 7, y: 5 }), position);
+    }
+
+    #[test]
+    fn viewport_does_not_translate_outside_coordinates() {
+        assert_eq!(None, VIEWPORT.to_position(Coordinate { x: -6, y: 10 }));
+        assert_eq!(None, VIEWPORT.to_position(Coordinate { x: -5, y: 9 }));
+        assert_eq!(None, VIEWPORT.to_position(Coordinate { x: 3, y: 10 }));
+        assert_eq!(None, VIEWPORT.to_position(Coordinate { x: -5, y: 16 }));
+    }
+
+    #[test]
+    fn grid_has_viewport_size() {
+        let grid = InfiniteGrid::new(VIEWPORT);
+        assert_eq!(8, grid.width());
+        assert_eq!(6, grid.height());
+    }
+
+    #[test]
+    fn grid_sets_alive_on_plane() {
+        let mut grid = InfiniteGrid::new(VIEWPORT);
+        let position = Position { x: 1, y: 2 };
+        grid.set_alive_at(position);
+        assert_eq!(true, grid.is_alive_at(position));
+        assert_eq!(
+            true,
+            grid.plane()
+                .borrow()
+                .is_alive_at(Coordinate { x: -4, y: 12 })
+        );
+    }
+
+    #[test]
+    fn grid_sets_dead_on_plane() {
+        let mut grid = InfiniteGrid::new(VIEWPORT);
+        let position = Position { x: 1, y: 2 };
+        grid.set_alive_at(position);
+        grid.set_dead_at(position);
+        assert_eq!(false, grid.is_alive_at(position));
+        assert_eq!(0, grid.plane().borrow().population());
+    }
+
+    #[test]
+    fn plane_stores_cells_far_apart() {
+        let plane = create_plane(&[
+            Coordinate {
+                x: -1_000_000_000_000,
+                y: 3,
+            },
+            Coordinate {
+                x: 1_000_000_000_000,
+                y: -3,
+            },
+        ]);
+        assert_eq!(2, plane.population());
+        let expected = (
+            Coordinate {
+                x: -1_000_000_000_000,
+                y: -3,
+            },
+            Coordinate {
+                x: 1_000_000_000_000,
+                y: 3,
+            },
+        );
+        assert_eq!(Some(expected), plane.bounding_box());
+    }
+
+    #[test]
+    fn empty_plane_has_no_bounding_box() {
+        assert_eq!(None, Plane::new().bounding_box());
+    }
+
+    #[test]
+    fn plane_matches_bounded_calculator_away_from_edges() {
+        let mut plane = create_plane(&GLIDER);
+        let mut grid = GridImpl::new(10, 10);
+        for coordinate in &GLIDER {
+            grid.set_alive_at(Position {
+                x: coordinate.x as u32,
+                y: coordinate.y as u32,
+            });
+        }
+        let rule = Rule::conway();
+        let generation_calculator = GenerationCalculatorImpl::new(rule.clone());
+
+        for _ in 0..8 {
+            let plane_changes = plane.next_generation(&rule);
+            let grid_changes = generation_calculator.next_generation(&grid);
+            let expected = grid_changes
+                .iter()
+                .map(|change| {
+                    let coordinate = Coordinate {
+                        x: i64::from(change.position.x),
+                        y: i64::from(change.position.y),
+                    };
+                    (coordinate, change.is_alive())
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(expected, plane_changes);
+
+            plane.apply(&plane_changes);
+            for change in grid_changes {
+                if change.is_alive() {
+                    grid.set_alive_at(change.position);
+                } else {
+                    grid.set_dead_at(change.position);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn glider_travels_forever() {
+        let mut plane = create_plane(&GLIDER);
+        let rule = Rule::conway();
+
+        for _ in 0..4 * 1000 {
+            let changes = plane.next_generation(&rule);
+            plane.apply(&changes);
+        }
+
+        let moved_glider = GLIDER
+            .iter()
+            .map(|coordinate| Coordinate {
+                x: coordinate.x + 1000,
+                y: coordinate.y + 1000,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(moved_glider, plane.alive_cells());
+    }
+
+    #[test]
+    fn calculator_only_reports_visible_changes() {
+        let grid = InfiniteGrid::new(Viewport {
+            origin: Coordinate { x: 0, y: 0 },
+            width: 3,
+            height: 3,
+        });
+        {
+            let plane = grid.plane();
+            let mut plane = plane.borrow_mut();
+            // Blinker straddling the right edge of the viewport
+            plane.set_alive_at(Coordinate { x: 2, y: 1 });
+            plane.set_alive_at(Coordinate { x: 3, y: 1 });
+            plane.set_alive_at(Coordinate { x: 4, y: 1 });
+        }
+        let generation_calculator =
+            InfiniteGenerationCalculator::new(&grid, Rule::conway()).unwrap();
+        let initial_plane = grid.plane().borrow().clone();
+
+        let changes = generation_calculator.next_generation(&grid);
+
+        let expected = vec![Change {
+            position: Position { x: 2, y: 1 },
+            state: DEAD,
+        }];
+        assert_eq!(expected, changes);
+        assert_eq!(initial_plane, *grid.plane().borrow());
+
+        generation_calculator.accept_changes(&changes);
+
+        // The hidden cells change once the step is accepted, the visible one is left to the game
+        let expected_plane = create_plane(&[
+            Coordinate { x: 2, y: 1 },
+            Coordinate { x: 3, y: 0 },
+            Coordinate { x: 3, y: 1 },
+            Coordinate { x: 3, y: 2 },
+        ]);
+        assert_eq!(expected_plane, *grid.plane().borrow());
+    }
+
+    fn glider_grid() -> InfiniteGrid {
+        let mut grid = InfiniteGrid::new(Viewport {
+            origin: Coordinate { x: 0, y: 0 },
+            width: 4,
+            height: 4,
+        });
+        for coordinate in &GLIDER {
+            grid.set_alive_at(grid.viewport().to_position(*coordinate).unwrap());
+        }
+        grid
+    }
+
+    #[test]
+    fn calculator_restores_hidden_cells_of_earlier_generations() {
+        let mut grid = glider_grid();
+        let generation_calculator =
+            InfiniteGenerationCalculator::new(&grid, Rule::conway()).unwrap();
+        for _ in 0..8 {
+            step(&mut grid, &generation_calculator);
+        }
+        let after_eight = grid.plane().borrow().alive_cells();
+        for _ in 0..4 {
+            step(&mut grid, &generation_calculator);
+        }
+        assert_eq!(None, grid.viewport().to_position(translate(&GLIDER, 3)[0]));
+
+        // Like the game does, the visible cells are restored separately
+        generation_calculator.restore_generation(8);
+        for &coordinate in &translate(&GLIDER, 3) {
+            if let Some(position) = grid.viewport().to_position(coordinate) {
+                grid.set_dead_at(position);
+            }
+        }
+        for &coordinate in &after_eight {
+            if let Some(position) = grid.viewport().to_position(coordinate) {
+                grid.set_alive_at(position);
+            }
+        }
+
+        assert_eq!(after_eight, grid.plane().borrow().alive_cells());
+    }
+
+    #[test]
+    fn calculator_clears_hidden_cells() {
+        let mut grid = glider_grid();
+        let generation_calculator =
+            InfiniteGenerationCalculator::new(&grid, Rule::conway()).unwrap();
+        for _ in 0..12 {
+            step(&mut grid, &generation_calculator);
+        }
+        assert_ne!(0, grid.plane().borrow().population());
+
+        generation_calculator.clear();
+
+        assert!(grid
+            .plane()
+            .borrow()
+            .alive_cells()
+            .iter()
+            .all(|&coordinate| grid.viewport().to_position(coordinate).is_some()));
+    }
+
+    #[test]
+    fn moving_viewport_shows_other_cells() {
+        let grid = glider_grid();
+        grid.shared_viewport().borrow_mut().move_by(1, 2);
+        // Only the bottom row of the glider is left in view, shifted up and to the left
+        let expected = vec![Position { x: 0, y: 0 }, Position { x: 1, y: 0 }];
+        assert_eq!(expected, crate::grid::alive_cells(&grid));
+    }
+
+    #[test]
+    fn calculator_restores_hidden_cells_after_moving_viewport() {
+        let mut grid = glider_grid();
+        let generation_calculator =
+            InfiniteGenerationCalculator::new(&grid, Rule::conway()).unwrap();
+        let initial_cells = grid.plane().borrow().alive_cells();
+        for _ in 0..4 {
+            step(&mut grid, &generation_calculator);
+        }
+
+        grid.shared_viewport().borrow_mut().move_by(2, 2);
+        // The game restores the cells it sees through the moved viewport
+        generation_calculator.restore_generation(0);
+        for &coordinate in &translate(&GLIDER, 1) {
+            if let Some(position) = grid.viewport().to_position(coordinate) {
+                grid.set_dead_at(position);
+            }
+        }
+        for &coordinate in &initial_cells {
+            if let Some(position) = grid.viewport().to_position(coordinate) {
+                grid.set_alive_at(position);
+            }
+        }
+
+        assert_eq!(initial_cells, grid.plane().borrow().alive_cells());
+    }
+
+    #[test]
+    fn calculator_rejects_b0_rules() {
+        let grid = InfiniteGrid::new(VIEWPORT);
+        let rule: Rule = "B03/S23".parse().unwrap();
+        let expected = Err(UnsupportedRule(rule.clone()));
+        assert_eq!(
+            expected,
+            InfiniteGenerationCalculator::new(&grid, rule).map(|_| ())
+        );
+    }
+}
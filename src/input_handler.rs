@@ -1,7 +1,13 @@
+use crate::camera::Camera;
 use crate::coordinate_translator::CoordinateTranslator;
 use crate::grid::Position;
 use crate::grid_info::GridInfo;
-use crate::interactive_game::InteractiveGame;
+use crate::hex_layout::HexLayout;
+use crate::interactive_game::{GenerationOverflow, InteractiveGame};
+use crate::neighbourhood::Neighbourhood;
+use crate::soup::{Region, Soup};
+use std::cell::RefCell;
+use std::rc::Rc;
 
 #[cfg(test)]
 extern crate mockers;
@@ -11,44 +17,116 @@ use mockers_derive::mocked;
 #[cfg_attr(test, mocked)]
 pub trait ClickableInputHandler {
     fn on_click(&mut self, x: u32, y: u32);
-    fn on_timer(&mut self);
+    fn on_select_state(&mut self, state: u8);
+    fn on_zoom(&mut self, x: u32, y: u32, factor: f64);
+    fn on_pan(&mut self, dx: i32, dy: i32);
+    fn on_move_viewport(&mut self, columns: i32, rows: i32);
+    fn on_timer(&mut self) -> Result<(), GenerationOverflow>;
+    fn on_randomise(&mut self, soup: &Soup);
+}
+
+// Strokes painted by dragging the pointer across the board
+#[cfg_attr(test, mocked)]
+pub trait PointerInputHandler {
+    fn on_pointer_down(&mut self, x: u32, y: u32);
+    fn on_pointer_move(&mut self, x: u32, y: u32);
+    fn on_pointer_up(&mut self);
 }
 
 pub struct ClickableInputHandlerImpl {
-    game: Box<InteractiveGame>,
-    coordinate_translator: Box<CoordinateTranslator>,
+    game: Box<dyn InteractiveGame>,
+    coordinate_translator: Box<dyn CoordinateTranslator>,
     grid_info: GridInfo,
+    stroke_cell: Option<Position>,
+    camera: Option<Rc<RefCell<Camera>>>,
+    neighbourhood: Neighbourhood,
 }
 
 impl ClickableInputHandlerImpl {
     pub fn new(
-        game: Box<InteractiveGame>,
-        coordinate_translator: Box<CoordinateTranslator>,
+        game: Box<dyn InteractiveGame>,
+        coordinate_translator: Box<dyn CoordinateTranslator>,
         grid_info: GridInfo,
     ) -> Self {
         ClickableInputHandlerImpl {
             game,
             coordinate_translator,
             grid_info,
+            stroke_cell: None,
+            camera: None,
+            neighbourhood: Neighbourhood::Moore,
         }
     }
 
+    // The camera is expected to be shared with the coordinate translator and the presenter
+    pub fn with_camera(mut self, camera: Rc<RefCell<Camera>>) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+
+    // Hit-tests hexagonal cells for the hexagonal neighbourhood, like the presenter draws them
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    // Edits and queries that do not depend on where the pointer is go to the game directly
+    pub fn game(&self) -> &dyn InteractiveGame {
+        &*self.game
+    }
+
+    pub fn game_mut(&mut self) -> &mut dyn InteractiveGame {
+        &mut *self.game
+    }
+
     fn get_cell_location_from_coordinates(&self, x: u32, y: u32) -> Option<(u32, u32)> {
         let global_position = Position { x, y };
-        if let Some(position) = self.coordinate_translator.to_local(&global_position) {
-            if position.x > self.grid_info.width || position.y > self.grid_info.height {
-                None
-            } else {
-                let cell_width = self.grid_info.width / self.grid_info.columns;
-                let cell_height = self.grid_info.height / self.grid_info.rows;
-                let cell_x = position.x / cell_width;
-                let cell_y = position.y / cell_height;
-                Some((cell_x, cell_y))
-            }
-        } else {
+        let point = self.coordinate_translator.to_local(&global_position)?;
+        if self.neighbourhood == Neighbourhood::Hexagonal {
+            return HexLayout::new(&self.grid_info)
+                .cell_at(point.x, point.y)
+                .map(|cell| (cell.x, cell.y));
+        }
+        // Cells are as wide as the board divided by the columns, which need not be whole pixels
+        let columns = f64::from(self.grid_info.columns);
+        let rows = f64::from(self.grid_info.rows);
+        let cell_x = (point.x * columns / f64::from(self.grid_info.width)).floor();
+        let cell_y = (point.y * rows / f64::from(self.grid_info.height)).floor();
+        if cell_x < 0.0 || cell_y < 0.0 || cell_x >= columns || cell_y >= rows {
             None
+        } else {
+            Some((cell_x as u32, cell_y as u32))
+        }
+    }
+}
+
+// Every cell on the line between two cells, excluding the first one.
+// Pointer samples can lie several cells apart when it is moved quickly.
+fn cells_between(from: Position, to: Position) -> Vec<Position> {
+    let (mut x, mut y) = (i64::from(from.x), i64::from(from.y));
+    let (to_x, to_y) = (i64::from(to.x), i64::from(to.y));
+    let dx = (to_x - x).abs();
+    let dy = -(to_y - y).abs();
+    let step_x = if x < to_x { 1 } else { -1 };
+    let step_y = if y < to_y { 1 } else { -1 };
+    let mut error = dx + dy;
+    let mut cells = Vec::new();
+    while (x, y) != (to_x, to_y) {
+        let doubled_error = 2 * error;
+        if doubled_error >= dy {
+            error += dy;
+            x += step_x;
         }
+        if doubled_error <= dx {
+            error += dx;
+            y += step_y;
+        }
+        cells.push(Position {
+            x: x as u32,
+            y: y as u32,
+        });
     }
+    cells
 }
 
 impl ClickableInputHandler for ClickableInputHandlerImpl {
@@ -60,59 +138,153 @@ impl ClickableInputHandler for ClickableInputHandlerImpl {
         }
     }
 
-    fn on_timer(&mut self) {
-        self.game.next_generation();
+    fn on_select_state(&mut self, state: u8) {
+        self.game.select_state(state);
+    }
+
+    fn on_zoom(&mut self, x: u32, y: u32, factor: f64) {
+        let camera = match self.camera {
+            Some(ref camera) => camera,
+            None => return,
+        };
+        camera.borrow_mut().zoom(&Position { x, y }, factor);
+        self.game.redraw();
+    }
+
+    fn on_pan(&mut self, dx: i32, dy: i32) {
+        let camera = match self.camera {
+            Some(ref camera) => camera,
+            None => return,
+        };
+        camera.borrow_mut().pan(dx, dy);
+        self.game.redraw();
+    }
+
+    fn on_move_viewport(&mut self, columns: i32, rows: i32) {
+        self.game.move_viewport(i64::from(columns), i64::from(rows));
+    }
+
+    fn on_timer(&mut self) -> Result<(), GenerationOverflow> {
+        self.game.next_generation()
+    }
+
+    fn on_randomise(&mut self, soup: &Soup) {
+        let region = Region {
+            origin: Position { x: 0, y: 0 },
+            width: self.grid_info.columns,
+            height: self.grid_info.rows,
+        };
+        self.game.randomise(soup, &region);
+    }
+}
+
+impl PointerInputHandler for ClickableInputHandlerImpl {
+    fn on_pointer_down(&mut self, x: u32, y: u32) {
+        if let Some((x, y)) = self.get_cell_location_from_coordinates(x, y) {
+            let position = Position { x, y };
+            self.game.start_stroke(&position);
+            self.stroke_cell = Some(position);
+        }
+    }
+
+    fn on_pointer_move(&mut self, x: u32, y: u32) {
+        let last_cell = match self.stroke_cell {
+            Some(last_cell) => last_cell,
+            None => return,
+        };
+        if let Some((x, y)) = self.get_cell_location_from_coordinates(x, y) {
+            let position = Position { x, y };
+            if position != last_cell {
+                self.game
+                    .continue_stroke(&cells_between(last_cell, position));
+                self.stroke_cell = Some(position);
+            }
+        }
+    }
+
+    fn on_pointer_up(&mut self) {
+        if self.stroke_cell.take().is_some() {
+            self.game.end_stroke();
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::coordinate_translator::CoordinateTranslatorMock;
+    use crate::coordinate_translator::{
+        CoordinateTranslatorMock, CoordinateTranslatorMockHandle, Point,
+        ScalingCoordinateTranslator,
+    };
     use crate::grid::Position;
     use crate::grid_info::GridInfo;
-    use crate::interactive_game::InteractiveGameMock;
+    use crate::interactive_game::{InteractiveGameMock, InteractiveGameMockHandle};
+    use crate::soup::Symmetry;
 
-    use mockers::matchers::ANY;
-    use mockers::Scenario;
+    use mockers::matchers::{check, ANY};
+    use mockers::{Scenario, Sequence};
 
     fn create_mock() -> (
         Scenario,
         InteractiveGameMock,
+        InteractiveGameMockHandle,
         CoordinateTranslatorMock,
+        CoordinateTranslatorMockHandle,
         GridInfo,
     ) {
         let scenario = Scenario::new();
-        let game = scenario.create_mock_for::<InteractiveGame>();
-        let coordinate_translator = scenario.create_mock_for::<CoordinateTranslator>();
+        let (game, game_handle) = scenario.create_mock_for::<dyn InteractiveGame>();
+        let (coordinate_translator, coordinate_translator_handle) =
+            scenario.create_mock_for::<dyn CoordinateTranslator>();
         let grid_info = GridInfo {
             width: 10,
             height: 8,
             rows: 4,
             columns: 5,
         };
-        (scenario, game, coordinate_translator, grid_info)
+        (
+            scenario,
+            game,
+            game_handle,
+            coordinate_translator,
+            coordinate_translator_handle,
+            grid_info,
+        )
     }
 
     #[test]
     fn calls_next_gen_on_timer() {
-        let (scenario, game, coordinate_translator, grid_info) = create_mock();
-        scenario.expect(game.next_generation_call().and_return(()));
+        let (
+            scenario,
+            game,
+            game_handle,
+            coordinate_translator,
+            _coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
+        scenario.expect(game_handle.next_generation().and_return(Ok(())));
         let mut input_handler = ClickableInputHandlerImpl::new(
             Box::new(game),
             Box::new(coordinate_translator),
             grid_info,
         );
-        input_handler.on_timer();
+        assert_eq!(Ok(()), input_handler.on_timer());
     }
 
     #[test]
     fn ignores_out_of_bounds_clicks() {
-        let (scenario, game, coordinate_translator, grid_info) = create_mock();
+        let (
+            scenario,
+            game,
+            _game_handle,
+            coordinate_translator,
+            coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
         scenario.expect(
-            coordinate_translator
-                .to_local_call(ANY)
-                .and_call_clone(|&pos| Some(pos))
+            coordinate_translator_handle
+                .to_local(ANY)
+                .and_call_clone(|&pos| Some(Point::from(pos)))
                 .times(3),
         );
 
@@ -126,13 +298,46 @@ mod test {
         input_handler.on_click(11, 8);
     }
 
+    #[test]
+    fn ignores_clicks_on_far_edge() {
+        let (
+            scenario,
+            game,
+            _game_handle,
+            coordinate_translator,
+            coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
+        scenario.expect(
+            coordinate_translator_handle
+                .to_local(ANY)
+                .and_call_clone(|&pos| Some(Point::from(pos)))
+                .times(2),
+        );
+
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        );
+        input_handler.on_click(10, 3);
+        input_handler.on_click(3, 8);
+    }
+
     #[test]
     fn ignores_untranslated_clicks() {
-        let (scenario, game, coordinate_translator, grid_info) = create_mock();
+        let (
+            scenario,
+            game,
+            _game_handle,
+            coordinate_translator,
+            coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
         const POSITION: Position = Position { x: 3, y: 2 };
         scenario.expect(
-            coordinate_translator
-                .to_local_call(&POSITION)
+            coordinate_translator_handle
+                .to_local(&POSITION)
                 .and_return(None),
         );
 
@@ -146,13 +351,20 @@ mod test {
 
     #[test]
     fn toggles_cell_on_click() {
-        let (scenario, game, coordinate_translator, grid_info) = create_mock();
+        let (
+            scenario,
+            game,
+            game_handle,
+            coordinate_translator,
+            coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
         const POSITION: Position = Position { x: 1, y: 2 };
-        scenario.expect(game.toggle_cell_call(&POSITION).and_return(()));
+        scenario.expect(game_handle.toggle_cell(&POSITION).and_return(()));
         scenario.expect(
-            coordinate_translator
-                .to_local_call(ANY)
-                .and_call(|&pos| Some(pos)),
+            coordinate_translator_handle
+                .to_local(ANY)
+                .and_call(|&pos| Some(Point::from(pos))),
         );
         let mut input_handler = ClickableInputHandlerImpl::new(
             Box::new(game),
@@ -161,4 +373,298 @@ mod test {
         );
         input_handler.on_click(2, 5);
     }
+
+    #[test]
+    fn selects_state_of_game() {
+        let (
+            scenario,
+            game,
+            game_handle,
+            coordinate_translator,
+            _coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
+        scenario.expect(game_handle.select_state(2).and_return(()));
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        );
+        input_handler.on_select_state(2);
+    }
+
+    #[test]
+    fn fills_whole_board_on_randomise() {
+        let (
+            scenario,
+            game,
+            game_handle,
+            coordinate_translator,
+            _coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
+        const SOUP: Soup = Soup {
+            seed: 42,
+            density: 0.5,
+            symmetry: Symmetry::D4,
+        };
+        const REGION: Region = Region {
+            origin: Position { x: 0, y: 0 },
+            width: 5,
+            height: 4,
+        };
+        // Soup holds a float and therefore only implements PartialEq
+        scenario.expect(
+            game_handle
+                .randomise(check(|soup: &&Soup| **soup == SOUP), &REGION)
+                .and_return(()),
+        );
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        );
+        input_handler.on_randomise(&SOUP);
+    }
+
+    #[test]
+    fn paints_line_between_pointer_samples() {
+        let (
+            scenario,
+            game,
+            game_handle,
+            coordinate_translator,
+            coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
+        scenario.expect(
+            coordinate_translator_handle
+                .to_local(ANY)
+                .and_call_clone(|&pos| Some(Point::from(pos)))
+                .times(4),
+        );
+        let mut seq = Sequence::new();
+        seq.expect(
+            game_handle
+                .start_stroke(&Position { x: 0, y: 0 })
+                .and_return(()),
+        );
+        seq.expect(
+            game_handle
+                .continue_stroke(check(|cells: &&[Position]| {
+                    *cells
+                        == [
+                            Position { x: 1, y: 1 },
+                            Position { x: 2, y: 1 },
+                            Position { x: 3, y: 2 },
+                        ]
+                }))
+                .and_return(()),
+        );
+        seq.expect(game_handle.end_stroke().and_return(()));
+        scenario.expect(seq);
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        );
+        input_handler.on_pointer_down(1, 1);
+        // Moving within the same cell does not paint anything
+        input_handler.on_pointer_move(0, 1);
+        input_handler.on_pointer_move(7, 5);
+        input_handler.on_pointer_move(12, 5);
+        input_handler.on_pointer_up();
+    }
+
+    #[test]
+    fn ignores_pointer_without_stroke() {
+        let (
+            scenario,
+            game,
+            _game_handle,
+            coordinate_translator,
+            coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
+        scenario.expect(
+            coordinate_translator_handle
+                .to_local(ANY)
+                .and_call_clone(|&pos| Some(Point::from(pos)))
+                .times(1),
+        );
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        );
+        input_handler.on_pointer_move(3, 3);
+        input_handler.on_pointer_up();
+        input_handler.on_pointer_down(11, 3);
+        input_handler.on_pointer_move(3, 3);
+        input_handler.on_pointer_up();
+    }
+
+    #[test]
+    fn interpolates_cells_between_samples() {
+        let cells = |from: (u32, u32), to: (u32, u32)| {
+            cells_between(
+                Position {
+                    x: from.0,
+                    y: from.1,
+                },
+                Position { x: to.0, y: to.1 },
+            )
+            .into_iter()
+            .map(|position| (position.x, position.y))
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![(3, 2), (2, 2), (1, 2)], cells((4, 2), (1, 2)));
+        assert_eq!(vec![(1, 1), (2, 2)], cells((0, 0), (2, 2)));
+        assert_eq!(vec![(2, 3), (2, 2)], cells((3, 4), (2, 2)));
+        assert!(cells((1, 1), (1, 1)).is_empty());
+    }
+
+    #[test]
+    fn maps_clicks_through_zoomed_camera() {
+        let (scenario, game, game_handle, _, _, grid_info) = create_mock();
+        let camera = Rc::new(RefCell::new(Camera::new(&grid_info)));
+        let coordinate_translator = ScalingCoordinateTranslator::new(Box::new(camera.clone()));
+        let mut seq = Sequence::new();
+        seq.expect(game_handle.redraw().and_return(()));
+        seq.expect(game_handle.redraw().and_return(()));
+        seq.expect(
+            game_handle
+                .toggle_cell(&Position { x: 3, y: 2 })
+                .and_return(()),
+        );
+        scenario.expect(seq);
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        )
+        .with_camera(camera);
+
+        input_handler.on_zoom(5, 4, 2.5);
+        input_handler.on_pan(-1, 0);
+        // Lands on board position (7.4, 4.4) at a scale of 2.5
+        input_handler.on_click(10, 5);
+    }
+
+    #[test]
+    fn maps_clicks_to_cells_of_fractional_size() {
+        let (scenario, game, game_handle, _, _, _) = create_mock();
+        let grid_info = GridInfo {
+            width: 10,
+            height: 10,
+            rows: 3,
+            columns: 3,
+        };
+        let camera = Rc::new(RefCell::new(Camera::new(&grid_info)));
+        let coordinate_translator = ScalingCoordinateTranslator::new(Box::new(camera.clone()));
+        let mut seq = Sequence::new();
+        seq.expect(game_handle.redraw().and_return(()));
+        seq.expect(
+            game_handle
+                .toggle_cell(&Position { x: 2, y: 2 })
+                .and_return(()),
+        );
+        seq.expect(
+            game_handle
+                .toggle_cell(&Position { x: 1, y: 0 })
+                .and_return(()),
+        );
+        scenario.expect(seq);
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        )
+        .with_camera(camera);
+
+        input_handler.on_zoom(0, 0, 1.5);
+        // Lands on board position (9.33, 9.33), in the last cell of 3.33 pixels
+        input_handler.on_click(14, 14);
+        // Lands on board position (4, 0.67)
+        input_handler.on_click(6, 1);
+    }
+
+    #[test]
+    fn ignores_zoom_without_camera() {
+        let (
+            _scenario,
+            game,
+            _game_handle,
+            coordinate_translator,
+            _coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        );
+        input_handler.on_zoom(5, 4, 2.0);
+        input_handler.on_pan(3, 3);
+    }
+
+    #[test]
+    fn moves_viewport_of_game() {
+        let (
+            scenario,
+            game,
+            game_handle,
+            coordinate_translator,
+            _coordinate_translator_handle,
+            grid_info,
+        ) = create_mock();
+        scenario.expect(game_handle.move_viewport(-3, 7).and_return(()));
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        );
+
+        input_handler.on_move_viewport(-3, 7);
+    }
+
+    #[test]
+    fn maps_clicks_to_hexagonal_cells() {
+        let (scenario, game, game_handle, coordinate_translator, coordinate_translator_handle, _) =
+            create_mock();
+        let grid_info = GridInfo {
+            width: 105,
+            height: 90,
+            rows: 6,
+            columns: 10,
+        };
+        scenario.expect(
+            coordinate_translator_handle
+                .to_local(ANY)
+                .and_call_clone(|&pos| Some(Point::from(pos)))
+                .times(2),
+        );
+        let mut seq = Sequence::new();
+        // Both clicks lie in the second row of squares, but in different hexagons
+        seq.expect(
+            game_handle
+                .toggle_cell(&Position { x: 0, y: 1 })
+                .and_return(()),
+        );
+        seq.expect(
+            game_handle
+                .toggle_cell(&Position { x: 1, y: 0 })
+                .and_return(()),
+        );
+        scenario.expect(seq);
+        let mut input_handler = ClickableInputHandlerImpl::new(
+            Box::new(game),
+            Box::new(coordinate_translator),
+            grid_info,
+        )
+        .with_neighbourhood(Neighbourhood::Hexagonal);
+
+        input_handler.on_click(12, 16);
+        input_handler.on_click(15, 16);
+    }
 }
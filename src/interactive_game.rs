@@ -1,5 +1,15 @@
 use crate::generation_calculator::{Change, GenerationCalculator};
-use crate::grid::{Grid, Position};
+use crate::grid::{apply_changes, cell_states, Grid, Position, ALIVE, DEAD};
+use crate::history::{self, History, Revision};
+use crate::infinite_grid::{Coordinate, Viewport};
+use crate::pattern::Pattern;
+use crate::period_detector::{PeriodDetector, Stabilisation};
+use crate::soup::{Region, Soup};
+use crate::timeline::{Timeline, TimelinePosition};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
 
 #[cfg(test)]
 extern crate mockers;
@@ -15,14 +25,63 @@ pub trait Presenter {
 #[cfg_attr(test, mocked)]
 pub trait InteractiveGame {
     fn accept_changes(&mut self, changes: &[Change]);
-    fn next_generation(&mut self);
+    fn next_generation(&mut self) -> Result<(), GenerationOverflow>;
+    fn select_state(&mut self, state: u8);
     fn toggle_cell(&mut self, position: &Position);
+    fn start_stroke(&mut self, position: &Position);
+    fn continue_stroke(&mut self, positions: &[Position]);
+    fn end_stroke(&mut self);
+    fn place_pattern(&mut self, pattern: &Pattern, offset: &Position);
+    fn clear(&mut self);
+    fn randomise(&mut self, soup: &Soup, region: &Region);
+    fn to_pattern(&self) -> Pattern;
+    fn redraw(&mut self);
+    fn move_viewport(&mut self, columns: i64, rows: i64);
+    fn undo(&mut self);
+    fn redo(&mut self);
+    fn step_back(&mut self, generations: u64);
+    fn jump_to_generation(&mut self, generation: u64);
+    fn timeline_position(&self) -> TimelinePosition;
+    fn stabilisation(&self) -> Option<Stabilisation>;
 }
 
+// The next step would take the generation beyond what the counter holds
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct GenerationOverflow;
+
+const HISTORY_CAPACITY: usize = 200;
+const TIMELINE_KEYFRAME_INTERVAL: u64 = 32;
+const TIMELINE_MAX_STORED_CELLS: usize = 1 << 20;
+const MAX_DETECTED_PERIOD: u32 = 1024;
+
 pub struct InteractiveGameImpl {
     grid: Box<dyn Grid>,
     generation_calculator: Box<dyn GenerationCalculator>,
     presenter: Box<dyn Presenter>,
+    history: History,
+    timeline: Timeline,
+    period_detector: PeriodDetector,
+    generation: u64,
+    generations_per_step: u64,
+    brush: u8,
+    stroke: Option<Stroke>,
+    viewport: Option<Rc<RefCell<Viewport>>>,
+}
+
+// Cells painted since the stroke started, which are undone together. Generations computed
+// meanwhile are recorded after the stroke, so they do not end up in the middle of its undo step.
+struct Stroke {
+    state: u8,
+    changes: Vec<Change>,
+    reverted: Vec<Change>,
+    steps: Vec<Step>,
+}
+
+struct Step {
+    changes: Vec<Change>,
+    reverted: Vec<Change>,
+    from: u64,
+    to: u64,
 }
 impl InteractiveGameImpl {
     pub fn new(
@@ -30,108 +89,540 @@ impl InteractiveGameImpl {
         generation_calculator: Box<dyn GenerationCalculator>,
         mut presenter: Box<dyn Presenter>,
     ) -> Self {
-        let width = grid.width();
-        let height = grid.height();
-        let mut alive_cells = Vec::new();
-        for y in 0..height {
-            for x in 0..width {
-                let position = Position { x, y };
-                if grid.is_alive_at(position) {
-                    alive_cells.push(position);
-                }
-            }
-        }
-        presenter.init_board(&alive_cells);
+        let cells = cell_states(&*grid);
+        draw_board(&mut *presenter, &cells);
+        let mut period_detector = PeriodDetector::new(MAX_DETECTED_PERIOD, &cells);
+        period_detector.restart(0);
+        let mut timeline = Timeline::new(TIMELINE_KEYFRAME_INTERVAL, TIMELINE_MAX_STORED_CELLS);
+        timeline.start(0, cells);
+        let generations_per_step = u64::from(generation_calculator.generations_per_step());
         InteractiveGameImpl {
             grid,
             generation_calculator,
             presenter,
+            history: History::new(HISTORY_CAPACITY),
+            timeline,
+            period_detector,
+            generation: 0,
+            generations_per_step,
+            brush: ALIVE,
+            stroke: None,
+            viewport: None,
+        }
+    }
+
+    pub fn with_timeline(mut self, mut timeline: Timeline) -> Self {
+        timeline.start(self.step(), cell_states(&*self.grid));
+        self.timeline = timeline;
+        if let Some(origin) = self.origin() {
+            self.timeline.move_to(self.step(), origin, &*self.grid);
+        }
+        self
+    }
+
+    // The viewport is expected to be shared with the infinite grid
+    pub fn with_viewport(mut self, viewport: Rc<RefCell<Viewport>>) -> Self {
+        let origin = viewport.borrow().origin;
+        self.timeline.move_to(self.step(), origin, &*self.grid);
+        self.viewport = Some(viewport);
+        self
+    }
+
+    // The timeline counts steps of the calculator, which may span several generations
+    fn step(&self) -> u64 {
+        self.generation / self.generations_per_step
+    }
+
+    // Cells in the state of the brush are cleared, all others are set to it
+    fn toggled_state(&self, state: u8) -> u8 {
+        if state == self.brush {
+            DEAD
+        } else {
+            self.brush
+        }
+    }
+
+    fn origin(&self) -> Option<Coordinate> {
+        self.viewport
+            .as_ref()
+            .map(|viewport| viewport.borrow().origin)
+    }
+
+    fn move_grid_to(&mut self, origin: Coordinate) {
+        if let Some(ref viewport) = self.viewport {
+            viewport.borrow_mut().origin = origin;
+        }
+    }
+
+    // The grid shows other cells after moving, which the timeline and the period detector start from
+    fn show_from(&mut self, origin: Coordinate) {
+        self.move_grid_to(origin);
+        let cells = cell_states(&*self.grid);
+        self.timeline.move_to(self.step(), origin, &*self.grid);
+        self.period_detector = PeriodDetector::new(MAX_DETECTED_PERIOD, &cells);
+        self.period_detector.restart(self.generation);
+        draw_board(&mut *self.presenter, &cells);
+    }
+
+    fn apply(&mut self, changes: &[Change]) {
+        apply_changes(&mut *self.grid, changes);
+        self.generation_calculator.accept_changes(changes);
+        self.period_detector.apply(changes);
+    }
+
+    fn paint(&mut self, positions: &[Position]) {
+        let state = match self.stroke {
+            Some(ref stroke) => stroke.state,
+            None => return,
+        };
+        let mut changes: Vec<Change> = Vec::new();
+        for &position in positions {
+            let change = Change { position, state };
+            if self.grid.state_at(position) != state && !changes.contains(&change) {
+                changes.push(change);
+            }
+        }
+        if changes.is_empty() {
+            return;
+        }
+        let mut reverted = history::reverted(&changes, &*self.grid);
+        self.timeline.amend(&changes);
+        self.apply(&changes);
+        self.period_detector.restart(self.generation);
+        self.presenter.present_changes(&changes);
+        if let Some(ref mut stroke) = self.stroke {
+            stroke.changes.extend(changes);
+            reverted.append(&mut stroke.reverted);
+            stroke.reverted = reverted;
+        }
+    }
+
+    // Applies an edit that can be undone by applying the reverted changes
+    fn edit(&mut self, changes: &[Change], reverted: &[Change]) {
+        self.end_stroke();
+        self.history.record(changes, reverted);
+        self.timeline.amend(changes);
+        self.apply(changes);
+        self.period_detector.restart(self.generation);
+    }
+
+    // Undone or redone steps move the board to a generation the timeline already holds,
+    // while edits become part of the timeline's next generation
+    fn revise(&mut self, revision: &Revision) {
+        if let Some((origin, _)) = revision.origins {
+            self.move_grid_to(origin);
+        }
+        match revision.generation {
+            Some(generation) => {
+                self.timeline.discard_edits();
+                self.generation_calculator.restore_generation(generation);
+                self.generation = generation;
+            }
+            None => self.timeline.amend(&revision.changes),
+        }
+        self.apply(&revision.changes);
+        self.period_detector.restart(self.generation);
+        match revision.origins {
+            Some((_, origin)) => self.show_from(origin),
+            None => self.presenter.present_changes(&revision.changes),
         }
     }
 }
 
 impl InteractiveGame for InteractiveGameImpl {
     fn accept_changes(&mut self, changes: &[Change]) {
-        for change in changes {
-            if change.is_alive {
-                self.grid.set_alive_at(change.position);
-            } else {
-                self.grid.set_dead_at(change.position);
-            }
-        }
+        let reverted = history::reverted(changes, &*self.grid);
+        self.edit(changes, &reverted);
     }
 
-    fn next_generation(&mut self) {
-        let changes = self.generation_calculator.next_generation(&*self.grid);
+    fn next_generation(&mut self) -> Result<(), GenerationOverflow> {
+        let next_generation = self
+            .generation
+            .checked_add(self.generations_per_step)
+            .ok_or(GenerationOverflow)?;
+        let replayed_changes = self.timeline.replay(self.step()).map(<[Change]>::to_vec);
+        let is_replay = replayed_changes.is_some();
+        let changes = replayed_changes
+            .unwrap_or_else(|| self.generation_calculator.next_generation(&*self.grid));
         if !changes.is_empty() {
             self.presenter.present_changes(&changes);
-            self.accept_changes(&changes);
+            let step = Step {
+                changes: changes.clone(),
+                reverted: history::reverted(&changes, &*self.grid),
+                from: self.generation,
+                to: next_generation,
+            };
+            match self.stroke {
+                Some(ref mut stroke) => stroke.steps.push(step),
+                None => self
+                    .history
+                    .record_step(&step.changes, &step.reverted, step.from, step.to),
+            }
         }
+        // Calculators may wait for the step to be accepted even if none of the changes are visible
+        self.apply(&changes);
+        if !is_replay {
+            self.timeline.record(self.step(), &changes, &*self.grid);
+        }
+        self.generation = next_generation;
+        if is_replay {
+            self.generation_calculator
+                .restore_generation(self.generation);
+        }
+        self.period_detector.record(self.generation);
+        Ok(())
+    }
+
+    fn select_state(&mut self, state: u8) {
+        self.end_stroke();
+        self.brush = state;
     }
 
     fn toggle_cell(&mut self, position: &Position) {
-        let should_be_alive = !self.grid.is_alive_at(*position);
-        let change = Change {
+        let state = self.grid.state_at(*position);
+        let changes = [Change {
             position: *position,
-            is_alive: should_be_alive,
-        };
-        let changes = [change];
-        self.accept_changes(&changes);
+            state: self.toggled_state(state),
+        }];
+        let reverted = [Change {
+            position: *position,
+            state,
+        }];
+        self.edit(&changes, &reverted);
         self.presenter.present_changes(&changes);
     }
+
+    fn start_stroke(&mut self, position: &Position) {
+        self.end_stroke();
+        self.stroke = Some(Stroke {
+            state: self.toggled_state(self.grid.state_at(*position)),
+            changes: Vec::new(),
+            reverted: Vec::new(),
+            steps: Vec::new(),
+        });
+        self.paint(&[*position]);
+    }
+
+    fn continue_stroke(&mut self, positions: &[Position]) {
+        self.paint(positions);
+    }
+
+    fn end_stroke(&mut self) {
+        if let Some(stroke) = self.stroke.take() {
+            self.history.record(&stroke.changes, &stroke.reverted);
+            for step in stroke.steps {
+                self.history
+                    .record_step(&step.changes, &step.reverted, step.from, step.to);
+            }
+        }
+    }
+
+    fn place_pattern(&mut self, pattern: &Pattern, offset: &Position) {
+        let changes = pattern.placement_changes(&*self.grid, *offset);
+        self.accept_changes(&changes);
+        self.redraw();
+    }
+
+    fn clear(&mut self) {
+        let changes = cell_states(&*self.grid)
+            .into_iter()
+            .map(|cell| Change {
+                position: cell.position,
+                state: DEAD,
+            })
+            .collect::<Vec<_>>();
+        self.accept_changes(&changes);
+        self.generation_calculator.clear();
+        self.redraw();
+    }
+
+    fn randomise(&mut self, soup: &Soup, region: &Region) {
+        let changes = soup.changes(&*self.grid, *region);
+        self.accept_changes(&changes);
+        self.redraw();
+    }
+
+    fn to_pattern(&self) -> Pattern {
+        Pattern::from_grid(&*self.grid)
+    }
+
+    fn redraw(&mut self) {
+        draw_board(&mut *self.presenter, &cell_states(&*self.grid));
+    }
+
+    // Shows another part of a board larger than the grid, which is undone like an edit
+    fn move_viewport(&mut self, columns: i64, rows: i64) {
+        let from = match self.origin() {
+            Some(origin) => origin,
+            None => return,
+        };
+        let to = Coordinate {
+            x: from.x + columns,
+            y: from.y + rows,
+        };
+        if to == from {
+            return;
+        }
+        self.end_stroke();
+        self.history.record_move(from, to);
+        self.show_from(to);
+    }
+
+    fn undo(&mut self) {
+        self.end_stroke();
+        if let Some(revision) = self.history.undo() {
+            self.revise(&revision);
+        }
+    }
+
+    fn redo(&mut self) {
+        self.end_stroke();
+        if let Some(revision) = self.history.redo() {
+            self.revise(&revision);
+        }
+    }
+
+    fn step_back(&mut self, generations: u64) {
+        let generation = self.generation.saturating_sub(generations).max(
+            self.timeline
+                .earliest_generation()
+                .saturating_mul(self.generations_per_step),
+        );
+        self.jump_to_generation(generation);
+    }
+
+    fn jump_to_generation(&mut self, generation: u64) {
+        self.end_stroke();
+        let step = generation / self.generations_per_step;
+        let generation = step * self.generations_per_step;
+        let target = match self.timeline.state_at(step) {
+            Some(target) => target,
+            None => return,
+        };
+        // Generations seen from another origin are returned to together with the viewport
+        let origins = match (self.origin(), self.timeline.origin_at(step)) {
+            (Some(from), Some(to)) if from != to => Some((from, to)),
+            _ => None,
+        };
+        if let Some((_, origin)) = origins {
+            self.move_grid_to(origin);
+        }
+        let changes = differences(&cell_states(&*self.grid), &target);
+        self.timeline.discard_edits();
+        self.generation_calculator.restore_generation(generation);
+        let reverted = history::reverted(&changes, &*self.grid);
+        match origins {
+            Some(origins) => self.history.record_jump(
+                &changes,
+                &reverted,
+                (self.generation, generation),
+                origins,
+            ),
+            None => self
+                .history
+                .record_step(&changes, &reverted, self.generation, generation),
+        }
+        self.apply(&changes);
+        self.generation = generation;
+        self.period_detector.restart(generation);
+        match origins {
+            Some((_, origin)) => self.show_from(origin),
+            None if !changes.is_empty() => self.presenter.present_changes(&changes),
+            None => {}
+        }
+    }
+
+    fn timeline_position(&self) -> TimelinePosition {
+        TimelinePosition {
+            earliest: self
+                .timeline
+                .earliest_generation()
+                .saturating_mul(self.generations_per_step),
+            current: self.generation,
+            latest: self
+                .timeline
+                .latest_generation()
+                .saturating_mul(self.generations_per_step)
+                .max(self.generation),
+        }
+    }
+
+    fn stabilisation(&self) -> Option<Stabilisation> {
+        self.period_detector.stabilisation()
+    }
+}
+
+impl fmt::Display for GenerationOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the generation counter cannot count any further")
+    }
+}
+
+// Dying cells of multi-state rules are drawn over the freshly initialised board
+fn draw_board(presenter: &mut dyn Presenter, cells: &[Change]) {
+    presenter.init_board(&alive_positions(cells));
+    let dying_cells = cells
+        .iter()
+        .filter(|cell| !cell.is_alive())
+        .cloned()
+        .collect::<Vec<_>>();
+    if !dying_cells.is_empty() {
+        presenter.present_changes(&dying_cells);
+    }
+}
+
+fn alive_positions(cells: &[Change]) -> Vec<Position> {
+    cells
+        .iter()
+        .filter(|cell| cell.is_alive())
+        .map(|cell| cell.position)
+        .collect()
+}
+
+// Both sides list every cell that is not dead
+fn differences(current: &[Change], target: &[Change]) -> Vec<Change> {
+    let target_states = target
+        .iter()
+        .map(|cell| (cell.position, cell.state))
+        .collect::<HashMap<_, _>>();
+    let current_states = current
+        .iter()
+        .map(|cell| (cell.position, cell.state))
+        .collect::<HashMap<_, _>>();
+    let mut changes = current
+        .iter()
+        .filter(|cell| !target_states.contains_key(&cell.position))
+        .map(|cell| Change {
+            position: cell.position,
+            state: DEAD,
+        })
+        .chain(
+            target
+                .iter()
+                .filter(|cell| current_states.get(&cell.position) != Some(&cell.state))
+                .cloned(),
+        )
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|change| (change.position.y, change.position.x));
+    changes
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::generation_calculator::GenerationCalculatorMock;
-    use crate::grid::{GridMock, Position};
+    use crate::generation_calculator::{
+        GenerationCalculatorImpl, GenerationCalculatorMock, GenerationCalculatorMockHandle,
+    };
+    use crate::grid::{GridImpl, GridMock, GridMockHandle, Position};
+    use crate::hashlife::{HashLife, HashLifeCalculator};
+    use crate::infinite_grid::{
+        Coordinate, InfiniteGenerationCalculator, InfiniteGrid, Plane, PlaneCells, Viewport,
+    };
+    use crate::period_detector::Periodicity;
+    use crate::rule::Rule;
+    use crate::soup::Symmetry;
     use mockers::matchers::ANY;
     use mockers::{Scenario, Sequence};
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     const CHANGES: [Change; 3] = [
         Change {
             position: Position { x: 20, y: 30 },
-            is_alive: false,
+            state: DEAD,
         },
         Change {
             position: Position { x: 123, y: 432 },
-            is_alive: true,
+            state: ALIVE,
         },
         Change {
             position: Position { x: 223, y: 42 },
-            is_alive: true,
+            state: ALIVE,
         },
     ];
     const ALIVE_INITIALIZED_CELLS: [Position; 2] =
         [Position { x: 300, y: 123 }, Position { x: 111, y: 222 }];
 
-    fn create_mock() -> (Scenario, GridMock, GenerationCalculatorMock, PresenterMock) {
+    // Every calculator is asked how many generations it computes per step
+    fn create_generation_calculator(
+        scenario: &Scenario,
+    ) -> (GenerationCalculatorMock, GenerationCalculatorMockHandle) {
+        let (generation_calculator, generation_calculator_handle) =
+            scenario.create_mock_for::<dyn GenerationCalculator>();
+        scenario.expect(
+            generation_calculator_handle
+                .generations_per_step()
+                .and_return_clone(1)
+                .times(..),
+        );
+        (generation_calculator, generation_calculator_handle)
+    }
+
+    type Mocks = (
+        Scenario,
+        GridMock,
+        GridMockHandle,
+        GenerationCalculatorMock,
+        GenerationCalculatorMockHandle,
+        PresenterMock,
+        PresenterMockHandle,
+    );
+
+    fn create_mock() -> Mocks {
         let scenario = Scenario::new();
-        let grid = scenario.create_mock_for::<Grid>();
-        let generation_calculator = scenario.create_mock_for::<GenerationCalculator>();
-        let presenter = scenario.create_mock_for::<Presenter>();
+        let (grid, grid_handle) = scenario.create_mock_for::<dyn Grid>();
+        let (generation_calculator, generation_calculator_handle) =
+            create_generation_calculator(&scenario);
+        let (presenter, presenter_handle) = scenario.create_mock_for::<dyn Presenter>();
         const WIDTH: u32 = 1000;
         const HEIGHT: u32 = 800;
-        scenario.expect(grid.width_call().and_return(WIDTH));
-        scenario.expect(grid.height_call().and_return(HEIGHT));
-        scenario.expect(grid.is_alive_at_call(ANY).and_return_clone(false).times(..));
+        scenario.expect(grid_handle.width().and_return(WIDTH));
+        scenario.expect(grid_handle.height().and_return(HEIGHT));
+        scenario.expect(
+            grid_handle
+                .is_alive_at(ANY)
+                .and_return_clone(false)
+                .times(..),
+        );
+        scenario.expect(grid_handle.state_at(ANY).and_return_clone(DEAD).times(..));
+        scenario.expect(
+            generation_calculator_handle
+                .accept_changes(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
         for alive_pos in &ALIVE_INITIALIZED_CELLS {
-            scenario.expect(grid.is_alive_at_call(*alive_pos).and_return(true));
+            scenario.expect(grid_handle.state_at(*alive_pos).and_return(ALIVE));
         }
 
         scenario.expect(
-            presenter
-                .init_board_call(ALIVE_INITIALIZED_CELLS.as_ref())
+            presenter_handle
+                .init_board(ALIVE_INITIALIZED_CELLS.as_ref())
                 .and_return(()),
         );
 
-        (scenario, grid, generation_calculator, presenter)
+        (
+            scenario,
+            grid,
+            grid_handle,
+            generation_calculator,
+            generation_calculator_handle,
+            presenter,
+            presenter_handle,
+        )
     }
 
     #[test]
     fn inits_presenter_with_grid_data() {
-        let (_scenario, grid, generation_calculator, presenter) = create_mock();
+        let (
+            _scenario,
+            grid,
+            _grid_handle,
+            generation_calculator,
+            _generation_calculator_handle,
+            presenter,
+            _presenter_handle,
+        ) = create_mock();
         let _game = InteractiveGameImpl::new(
             Box::new(grid),
             Box::new(generation_calculator),
@@ -141,10 +632,18 @@ mod test {
 
     #[test]
     fn does_not_present_stable_generation() {
-        let (scenario, grid, generation_calculator, presenter) = create_mock();
+        let (
+            scenario,
+            grid,
+            _grid_handle,
+            generation_calculator,
+            generation_calculator_handle,
+            presenter,
+            _presenter_handle,
+        ) = create_mock();
         scenario.expect(
-            generation_calculator
-                .next_generation_call(ANY)
+            generation_calculator_handle
+                .next_generation(ANY)
                 .and_return(Vec::new()),
         );
 
@@ -153,18 +652,26 @@ mod test {
             Box::new(generation_calculator),
             Box::new(presenter),
         );
-        game.next_generation();
+        game.next_generation().unwrap();
     }
 
     #[test]
     fn applies_changes() {
-        let (scenario, grid, generation_calculator, presenter) = create_mock();
+        let (
+            scenario,
+            grid,
+            grid_handle,
+            generation_calculator,
+            _generation_calculator_handle,
+            presenter,
+            _presenter_handle,
+        ) = create_mock();
         for change in &CHANGES {
-            if change.is_alive {
-                scenario.expect(grid.set_alive_at_call(change.position).and_return(()))
-            } else {
-                scenario.expect(grid.set_dead_at_call(change.position).and_return(()))
-            }
+            scenario.expect(
+                grid_handle
+                    .set_state_at(change.position, change.state)
+                    .and_return(()),
+            );
         }
 
         let mut game = InteractiveGameImpl::new(
@@ -175,18 +682,61 @@ mod test {
         game.accept_changes(CHANGES.as_ref());
     }
 
+    #[test]
+    fn notifies_generation_calculator_of_accepted_changes() {
+        let scenario = Scenario::new();
+        let (grid, grid_handle) = scenario.create_mock_for::<dyn Grid>();
+        let (generation_calculator, generation_calculator_handle) =
+            create_generation_calculator(&scenario);
+        let (presenter, presenter_handle) = scenario.create_mock_for::<dyn Presenter>();
+        scenario.expect(grid_handle.width().and_return(0));
+        scenario.expect(grid_handle.height().and_return(0));
+        scenario.expect(presenter_handle.init_board(ANY).and_return(()));
+        scenario.expect(
+            grid_handle
+                .set_state_at(ANY, ANY)
+                .and_return_clone(())
+                .times(3),
+        );
+        scenario.expect(grid_handle.state_at(ANY).and_return_clone(DEAD).times(..));
+        scenario.expect(
+            generation_calculator_handle
+                .accept_changes(CHANGES.as_ref())
+                .and_return(()),
+        );
+
+        let mut game = InteractiveGameImpl::new(
+            Box::new(grid),
+            Box::new(generation_calculator),
+            Box::new(presenter),
+        );
+        game.accept_changes(CHANGES.as_ref());
+    }
+
     #[test]
     fn toggles_dead_cell() {
-        let (scenario, grid, generation_calculator, presenter) = create_mock();
+        let (
+            scenario,
+            grid,
+            grid_handle,
+            generation_calculator,
+            _generation_calculator_handle,
+            presenter,
+            presenter_handle,
+        ) = create_mock();
         const CHANGE: Change = Change {
             position: Position { x: 23, y: 74 },
-            is_alive: true,
+            state: ALIVE,
         };
 
-        scenario.expect(grid.set_alive_at_call(CHANGE.position).and_return(()));
         scenario.expect(
-            presenter
-                .present_changes_call([CHANGE].as_ref())
+            grid_handle
+                .set_state_at(CHANGE.position, ALIVE)
+                .and_return(()),
+        );
+        scenario.expect(
+            presenter_handle
+                .present_changes([CHANGE].as_ref())
                 .and_return(()),
         );
 
@@ -200,17 +750,29 @@ mod test {
 
     #[test]
     fn toggles_living_cell() {
-        let (scenario, grid, generation_calculator, presenter) = create_mock();
+        let (
+            scenario,
+            grid,
+            grid_handle,
+            generation_calculator,
+            _generation_calculator_handle,
+            presenter,
+            presenter_handle,
+        ) = create_mock();
         const CHANGE: Change = Change {
             position: Position { x: 300, y: 123 },
-            is_alive: false,
+            state: DEAD,
         };
         let mut seq = Sequence::new();
-        seq.expect(grid.is_alive_at_call(CHANGE.position).and_return(true));
-        seq.expect(grid.set_dead_at_call(CHANGE.position).and_return(()));
+        seq.expect(grid_handle.state_at(CHANGE.position).and_return(ALIVE));
+        seq.expect(
+            grid_handle
+                .set_state_at(CHANGE.position, DEAD)
+                .and_return(()),
+        );
         seq.expect(
-            presenter
-                .present_changes_call([CHANGE].as_ref())
+            presenter_handle
+                .present_changes([CHANGE].as_ref())
                 .and_return(()),
         );
         scenario.expect(seq);
@@ -224,31 +786,44 @@ mod test {
 
     #[test]
     fn toggles_cell_dead_again() {
-        let (scenario, grid, generation_calculator, presenter) = create_mock();
+        let (
+            scenario,
+            grid,
+            grid_handle,
+            generation_calculator,
+            _generation_calculator_handle,
+            presenter,
+            presenter_handle,
+        ) = create_mock();
         const ALIVE_CHANGE: Change = Change {
             position: Position { x: 23, y: 74 },
-            is_alive: true,
+            state: ALIVE,
         };
         let mut seq = Sequence::new();
+        seq.expect(grid_handle.state_at(ALIVE_CHANGE.position).and_return(DEAD));
         seq.expect(
-            grid.is_alive_at_call(ALIVE_CHANGE.position)
-                .and_return(false),
+            grid_handle
+                .set_state_at(ALIVE_CHANGE.position, ALIVE)
+                .and_return(()),
         );
-        seq.expect(grid.set_alive_at_call(ALIVE_CHANGE.position).and_return(()));
         seq.expect(
-            presenter
-                .present_changes_call([ALIVE_CHANGE].as_ref())
+            presenter_handle
+                .present_changes([ALIVE_CHANGE].as_ref())
                 .and_return(()),
         );
         const DEAD_CHANGE: Change = Change {
             position: Position { x: 23, y: 74 },
-            is_alive: false,
+            state: DEAD,
         };
-        seq.expect(grid.is_alive_at_call(DEAD_CHANGE.position).and_return(true));
-        seq.expect(grid.set_dead_at_call(DEAD_CHANGE.position).and_return(()));
+        seq.expect(grid_handle.state_at(DEAD_CHANGE.position).and_return(ALIVE));
+        seq.expect(
+            grid_handle
+                .set_state_at(DEAD_CHANGE.position, DEAD)
+                .and_return(()),
+        );
         seq.expect(
-            presenter
-                .present_changes_call([DEAD_CHANGE].as_ref())
+            presenter_handle
+                .present_changes([DEAD_CHANGE].as_ref())
                 .and_return(()),
         );
         scenario.expect(seq);
@@ -263,30 +838,735 @@ mod test {
 
     #[test]
     fn presents_next_generation() {
-        let (scenario, grid, generation_calculator, presenter) = create_mock();
+        let (
+            scenario,
+            grid,
+            grid_handle,
+            generation_calculator,
+            generation_calculator_handle,
+            presenter,
+            presenter_handle,
+        ) = create_mock();
         scenario.expect(
-            generation_calculator
-                .next_generation_call(ANY)
+            generation_calculator_handle
+                .next_generation(ANY)
                 .and_return(CHANGES.to_vec()),
         );
         scenario.expect(
-            presenter
-                .present_changes_call(CHANGES.as_ref())
+            presenter_handle
+                .present_changes(CHANGES.as_ref())
                 .and_return(()),
         );
 
         for change in &CHANGES {
-            if change.is_alive {
-                scenario.expect(grid.set_alive_at_call(change.position).and_return(()));
-            } else {
-                scenario.expect(grid.set_dead_at_call(change.position).and_return(()));
-            }
+            scenario.expect(
+                grid_handle
+                    .set_state_at(change.position, change.state)
+                    .and_return(()),
+            );
         }
         let mut game = InteractiveGameImpl::new(
             Box::new(grid),
             Box::new(generation_calculator),
             Box::new(presenter),
         );
-        game.next_generation();
+        game.next_generation().unwrap();
+    }
+
+    fn create_game_with_grid(
+        grid: GridImpl,
+        redrawn_cells: &'static [Position],
+    ) -> (Scenario, InteractiveGameImpl) {
+        let scenario = Scenario::new();
+        let (generation_calculator, generation_calculator_handle) =
+            create_generation_calculator(&scenario);
+        let (presenter, presenter_handle) = scenario.create_mock_for::<dyn Presenter>();
+        scenario.expect(
+            generation_calculator_handle
+                .accept_changes(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        scenario.expect(
+            generation_calculator_handle
+                .clear()
+                .and_return_clone(())
+                .times(..),
+        );
+        let mut seq = Sequence::new();
+        seq.expect(presenter_handle.init_board(ANY).and_return(()));
+        seq.expect(presenter_handle.init_board(redrawn_cells).and_return(()));
+        scenario.expect(seq);
+        let game = InteractiveGameImpl::new(
+            Box::new(grid),
+            Box::new(generation_calculator),
+            Box::new(presenter),
+        );
+        (scenario, game)
+    }
+
+    #[test]
+    fn places_pattern_and_redraws() {
+        let mut grid = GridImpl::new(10, 10);
+        grid.set_alive_at(Position { x: 1, y: 1 });
+        const REDRAWN_CELLS: [Position; 3] = [
+            Position { x: 1, y: 1 },
+            Position { x: 5, y: 6 },
+            Position { x: 6, y: 6 },
+        ];
+        let (_scenario, mut game) = create_game_with_grid(grid, &REDRAWN_CELLS);
+        let pattern = Pattern {
+            width: 2,
+            height: 1,
+            alive_cells: vec![Position { x: 0, y: 0 }, Position { x: 1, y: 0 }],
+            ..Default::default()
+        };
+
+        game.place_pattern(&pattern, &Position { x: 5, y: 6 });
+
+        assert_eq!(REDRAWN_CELLS.to_vec(), game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn clears_board_and_redraws() {
+        let mut grid = GridImpl::new(10, 10);
+        grid.set_alive_at(Position { x: 1, y: 1 });
+        grid.set_alive_at(Position { x: 7, y: 3 });
+        let (_scenario, mut game) = create_game_with_grid(grid, &[]);
+
+        game.clear();
+
+        assert!(game.to_pattern().alive_cells.is_empty());
+    }
+
+    #[test]
+    fn fills_region_with_soup_and_redraws() {
+        let mut grid = GridImpl::new(10, 10);
+        grid.set_alive_at(Position { x: 5, y: 5 });
+        const REDRAWN_CELLS: [Position; 5] = [
+            Position { x: 1, y: 1 },
+            Position { x: 2, y: 1 },
+            Position { x: 1, y: 2 },
+            Position { x: 2, y: 2 },
+            Position { x: 5, y: 5 },
+        ];
+        let (_scenario, mut game) = create_game_with_grid(grid, &REDRAWN_CELLS);
+        let region = Region {
+            origin: Position { x: 1, y: 1 },
+            width: 2,
+            height: 2,
+        };
+
+        game.randomise(&Soup::new(1, 1.0, Symmetry::C1), &region);
+
+        assert_eq!(REDRAWN_CELLS.to_vec(), game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn converts_board_to_pattern() {
+        let mut grid = GridImpl::new(3, 2);
+        grid.set_alive_at(Position { x: 2, y: 1 });
+        let scenario = Scenario::new();
+        let (generation_calculator, _generation_calculator_handle) =
+            create_generation_calculator(&scenario);
+        let (presenter, presenter_handle) = scenario.create_mock_for::<dyn Presenter>();
+        scenario.expect(presenter_handle.init_board(ANY).and_return(()));
+        let game = InteractiveGameImpl::new(
+            Box::new(grid.clone()),
+            Box::new(generation_calculator),
+            Box::new(presenter),
+        );
+
+        assert_eq!(Pattern::from_grid(&grid), game.to_pattern());
+    }
+
+    #[test]
+    fn undoes_and_redoes_toggle() {
+        const SET_ALIVE: [Change; 1] = [Change {
+            position: Position { x: 2, y: 3 },
+            state: ALIVE,
+        }];
+        const SET_DEAD: [Change; 1] = [Change {
+            position: Position { x: 2, y: 3 },
+            state: DEAD,
+        }];
+        let scenario = Scenario::new();
+        let (generation_calculator, generation_calculator_handle) =
+            create_generation_calculator(&scenario);
+        let (presenter, presenter_handle) = scenario.create_mock_for::<dyn Presenter>();
+        scenario.expect(
+            generation_calculator_handle
+                .accept_changes(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        let mut seq = Sequence::new();
+        seq.expect(presenter_handle.init_board(ANY).and_return(()));
+        seq.expect(
+            presenter_handle
+                .present_changes(SET_ALIVE.as_ref())
+                .and_return(()),
+        );
+        seq.expect(
+            presenter_handle
+                .present_changes(SET_DEAD.as_ref())
+                .and_return(()),
+        );
+        seq.expect(
+            presenter_handle
+                .present_changes(SET_ALIVE.as_ref())
+                .and_return(()),
+        );
+        scenario.expect(seq);
+        let mut game = InteractiveGameImpl::new(
+            Box::new(GridImpl::new(5, 5)),
+            Box::new(generation_calculator),
+            Box::new(presenter),
+        );
+
+        game.toggle_cell(&SET_ALIVE[0].position);
+        game.undo();
+        assert!(game.to_pattern().alive_cells.is_empty());
+        game.redo();
+        assert_eq!(vec![SET_ALIVE[0].position], game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn undoes_generation() {
+        let scenario = Scenario::new();
+        let (generation_calculator, generation_calculator_handle) =
+            create_generation_calculator(&scenario);
+        let (presenter, presenter_handle) = scenario.create_mock_for::<dyn Presenter>();
+        scenario.expect(
+            generation_calculator_handle
+                .accept_changes(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        scenario.expect(
+            generation_calculator_handle
+                .next_generation(ANY)
+                .and_return(CHANGES.to_vec()),
+        );
+        scenario.expect(
+            generation_calculator_handle
+                .restore_generation(0)
+                .and_return(()),
+        );
+        scenario.expect(
+            presenter_handle
+                .init_board(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        scenario.expect(
+            presenter_handle
+                .present_changes(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        let mut grid = GridImpl::new(500, 500);
+        grid.set_alive_at(CHANGES[0].position);
+        let mut game = InteractiveGameImpl::new(
+            Box::new(grid.clone()),
+            Box::new(generation_calculator),
+            Box::new(presenter),
+        );
+
+        game.next_generation().unwrap();
+        assert_ne!(Pattern::from_grid(&grid), game.to_pattern());
+        game.undo();
+
+        assert_eq!(Pattern::from_grid(&grid), game.to_pattern());
+    }
+
+    #[test]
+    fn ignores_undo_without_history() {
+        let (
+            _scenario,
+            grid,
+            _grid_handle,
+            generation_calculator,
+            _generation_calculator_handle,
+            presenter,
+            _presenter_handle,
+        ) = create_mock();
+        let mut game = InteractiveGameImpl::new(
+            Box::new(grid),
+            Box::new(generation_calculator),
+            Box::new(presenter),
+        );
+        game.undo();
+        game.redo();
+    }
+
+    fn create_blinker_game() -> (Scenario, InteractiveGameImpl) {
+        let scenario = Scenario::new();
+        let (presenter, presenter_handle) = scenario.create_mock_for::<dyn Presenter>();
+        scenario.expect(
+            presenter_handle
+                .init_board(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        scenario.expect(
+            presenter_handle
+                .present_changes(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        let mut grid = GridImpl::new(5, 5);
+        for x in 1..4 {
+            grid.set_alive_at(Position { x, y: 2 });
+        }
+        let game = InteractiveGameImpl::new(
+            Box::new(grid),
+            Box::new(GenerationCalculatorImpl::new(Rule::conway())),
+            Box::new(presenter),
+        )
+        .with_timeline(Timeline::new(4, 1000));
+        (scenario, game)
+    }
+
+    fn horizontal_blinker() -> Vec<Position> {
+        (1..4).map(|x| Position { x, y: 2 }).collect()
+    }
+
+    fn vertical_blinker() -> Vec<Position> {
+        (1..4).map(|y| Position { x: 2, y }).collect()
+    }
+
+    #[test]
+    fn steps_back_through_generations() {
+        let (_scenario, mut game) = create_blinker_game();
+        for _ in 0..5 {
+            game.next_generation().unwrap();
+        }
+        assert_eq!(vertical_blinker(), game.to_pattern().alive_cells);
+
+        game.step_back(1);
+
+        assert_eq!(horizontal_blinker(), game.to_pattern().alive_cells);
+        let expected = TimelinePosition {
+            earliest: 0,
+            current: 4,
+            latest: 5,
+        };
+        assert_eq!(expected, game.timeline_position());
+    }
+
+    #[test]
+    fn jumps_to_generation_and_replays_forward() {
+        let (_scenario, mut game) = create_blinker_game();
+        for _ in 0..10 {
+            game.next_generation().unwrap();
+        }
+
+        game.jump_to_generation(3);
+        assert_eq!(vertical_blinker(), game.to_pattern().alive_cells);
+        game.next_generation().unwrap();
+
+        assert_eq!(horizontal_blinker(), game.to_pattern().alive_cells);
+        assert_eq!(4, game.timeline_position().current);
+        assert_eq!(10, game.timeline_position().latest);
+    }
+
+    #[test]
+    fn editing_the_past_discards_the_future() {
+        let (_scenario, mut game) = create_blinker_game();
+        for _ in 0..6 {
+            game.next_generation().unwrap();
+        }
+        game.jump_to_generation(2);
+
+        game.toggle_cell(&Position { x: 1, y: 2 });
+        game.next_generation().unwrap();
+
+        assert_eq!(3, game.timeline_position().latest);
+        assert!(game.to_pattern().alive_cells.is_empty());
+    }
+
+    #[test]
+    fn undoing_generation_restores_its_number() {
+        let (_scenario, mut game) = create_blinker_game();
+        game.next_generation().unwrap();
+
+        game.undo();
+        assert_eq!(0, game.timeline_position().current);
+        assert_eq!(horizontal_blinker(), game.to_pattern().alive_cells);
+        game.next_generation().unwrap();
+        assert_eq!(1, game.timeline_position().current);
+        assert_eq!(vertical_blinker(), game.to_pattern().alive_cells);
+
+        game.undo();
+        game.redo();
+        assert_eq!(1, game.timeline_position().current);
+        assert_eq!(vertical_blinker(), game.to_pattern().alive_cells);
+        game.next_generation().unwrap();
+        assert_eq!(2, game.timeline_position().current);
+        assert_eq!(horizontal_blinker(), game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn undoing_jump_returns_to_generation() {
+        let (_scenario, mut game) = create_blinker_game();
+        for _ in 0..3 {
+            game.next_generation().unwrap();
+        }
+        game.jump_to_generation(0);
+
+        game.undo();
+
+        assert_eq!(3, game.timeline_position().current);
+        assert_eq!(vertical_blinker(), game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn ignores_jumps_outside_of_timeline() {
+        let (_scenario, mut game) = create_blinker_game();
+        game.next_generation().unwrap();
+
+        game.jump_to_generation(5);
+
+        assert_eq!(1, game.timeline_position().current);
+        assert_eq!(vertical_blinker(), game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn step_back_stops_at_earliest_generation() {
+        let (_scenario, mut game) = create_blinker_game();
+        for _ in 0..3 {
+            game.next_generation().unwrap();
+        }
+
+        game.step_back(10);
+
+        assert_eq!(0, game.timeline_position().current);
+        assert_eq!(horizontal_blinker(), game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn reports_stabilisation() {
+        let (_scenario, mut game) = create_blinker_game();
+        game.next_generation().unwrap();
+        assert_eq!(None, game.stabilisation());
+
+        game.next_generation().unwrap();
+
+        let expected = Stabilisation {
+            generation: 0,
+            periodicity: Periodicity::Oscillator { period: 2 },
+        };
+        assert_eq!(Some(expected), game.stabilisation());
+    }
+
+    #[test]
+    fn editing_restarts_period_detection() {
+        let (_scenario, mut game) = create_blinker_game();
+        for _ in 0..3 {
+            game.next_generation().unwrap();
+        }
+
+        game.toggle_cell(&Position { x: 2, y: 1 });
+        assert_eq!(None, game.stabilisation());
+        game.next_generation().unwrap();
+        game.next_generation().unwrap();
+
+        let expected = Stabilisation {
+            generation: 4,
+            periodicity: Periodicity::StillLife,
+        };
+        assert_eq!(Some(expected), game.stabilisation());
+    }
+
+    fn cells(positions: &[(u32, u32)]) -> Vec<Position> {
+        positions.iter().map(|&(x, y)| Position { x, y }).collect()
+    }
+
+    #[test]
+    fn paints_stroke_as_single_undo_step() {
+        let (_scenario, mut game) = create_blinker_game();
+
+        game.start_stroke(&Position { x: 0, y: 0 });
+        game.continue_stroke(&cells(&[(1, 0), (2, 0)]));
+        game.continue_stroke(&cells(&[(2, 0), (3, 0)]));
+        game.end_stroke();
+
+        let mut expected = cells(&[(0, 0), (1, 0), (2, 0), (3, 0)]);
+        expected.extend(horizontal_blinker());
+        assert_eq!(expected, game.to_pattern().alive_cells);
+        game.undo();
+        assert_eq!(horizontal_blinker(), game.to_pattern().alive_cells);
+        game.redo();
+        assert_eq!(expected, game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn stroke_starting_on_alive_cell_erases() {
+        let (_scenario, mut game) = create_blinker_game();
+
+        game.start_stroke(&Position { x: 1, y: 2 });
+        game.continue_stroke(&cells(&[(2, 2), (2, 3)]));
+        game.end_stroke();
+
+        assert_eq!(cells(&[(3, 2)]), game.to_pattern().alive_cells);
+    }
+
+    #[test]
+    fn toggles_and_paints_selected_state() {
+        let (_scenario, mut game) = create_blinker_game();
+        game.select_state(2);
+
+        game.toggle_cell(&Position { x: 0, y: 0 });
+        game.start_stroke(&Position { x: 1, y: 2 });
+        game.continue_stroke(&cells(&[(2, 2)]));
+        game.end_stroke();
+
+        let pattern = game.to_pattern();
+        assert_eq!(cells(&[(3, 2)]), pattern.alive_cells);
+        let painted = cells(&[(0, 0), (1, 2), (2, 2)])
+            .into_iter()
+            .map(|position| Change { position, state: 2 })
+            .collect::<Vec<_>>();
+        assert_eq!(painted, pattern.multi_state_cells);
+
+        game.toggle_cell(&Position { x: 0, y: 0 });
+        game.start_stroke(&Position { x: 1, y: 2 });
+        game.end_stroke();
+        assert_eq!(painted[2..].to_vec(), game.to_pattern().multi_state_cells);
+    }
+
+    #[test]
+    fn clears_cells_of_every_state() {
+        let (_scenario, mut game) = create_blinker_game();
+        game.select_state(2);
+        game.toggle_cell(&Position { x: 0, y: 0 });
+
+        game.clear();
+
+        let pattern = game.to_pattern();
+        assert!(pattern.alive_cells.is_empty());
+        assert!(pattern.multi_state_cells.is_empty());
+    }
+
+    #[test]
+    fn ignores_painting_without_stroke() {
+        let (_scenario, mut game) = create_blinker_game();
+        game.start_stroke(&Position { x: 0, y: 0 });
+        game.end_stroke();
+
+        game.continue_stroke(&cells(&[(4, 4)]));
+
+        assert!(!game
+            .to_pattern()
+            .alive_cells
+            .contains(&Position { x: 4, y: 4 }));
+    }
+
+    #[test]
+    fn stroke_spanning_generations_is_single_undo_step() {
+        let (_scenario, mut game) = create_blinker_game();
+
+        game.start_stroke(&Position { x: 0, y: 0 });
+        game.continue_stroke(&cells(&[(1, 0)]));
+        game.next_generation().unwrap();
+        game.continue_stroke(&cells(&[(0, 4), (1, 4)]));
+        game.next_generation().unwrap();
+        game.end_stroke();
+        game.undo();
+        game.undo();
+
+        let mut expected = cells(&[(0, 0), (1, 0)]);
+        expected.extend(horizontal_blinker());
+        expected.extend(cells(&[(0, 4), (1, 4)]));
+        assert_eq!(expected, game.to_pattern().alive_cells);
+        assert_eq!(0, game.timeline_position().current);
+        game.undo();
+        assert_eq!(horizontal_blinker(), game.to_pattern().alive_cells);
+        game.redo();
+        assert_eq!(expected, game.to_pattern().alive_cells);
+    }
+
+    type InfiniteGame<P> = (
+        Scenario,
+        InteractiveGameImpl,
+        Rc<RefCell<P>>,
+        Rc<RefCell<Viewport>>,
+    );
+
+    const INFINITE_VIEWPORT: Viewport = Viewport {
+        origin: Coordinate { x: 0, y: 0 },
+        width: 4,
+        height: 4,
+    };
+
+    fn create_infinite_game() -> InfiniteGame<Plane> {
+        let grid = InfiniteGrid::new(INFINITE_VIEWPORT);
+        let generation_calculator =
+            InfiniteGenerationCalculator::new(&grid, Rule::conway()).unwrap();
+        create_infinite_game_with(grid, Box::new(generation_calculator))
+    }
+
+    fn create_hashlife_game(step_exponent: u32) -> InfiniteGame<HashLife> {
+        let generation_calculator =
+            HashLifeCalculator::new(INFINITE_VIEWPORT, Rule::conway(), step_exponent).unwrap();
+        let grid = generation_calculator.grid();
+        create_infinite_game_with(grid, Box::new(generation_calculator))
+    }
+
+    fn create_infinite_game_with<P: PlaneCells + 'static>(
+        mut grid: InfiniteGrid<P>,
+        generation_calculator: Box<dyn GenerationCalculator>,
+    ) -> InfiniteGame<P> {
+        let scenario = Scenario::new();
+        let (presenter, presenter_handle) = scenario.create_mock_for::<dyn Presenter>();
+        scenario.expect(
+            presenter_handle
+                .init_board(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        scenario.expect(
+            presenter_handle
+                .present_changes(ANY)
+                .and_return_clone(())
+                .times(..),
+        );
+        // A glider leaving the viewport to the bottom right
+        for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid.set_alive_at(Position { x, y });
+        }
+        let plane = grid.plane();
+        let viewport = grid.shared_viewport();
+        let game =
+            InteractiveGameImpl::new(Box::new(grid), generation_calculator, Box::new(presenter))
+                .with_viewport(Rc::clone(&viewport));
+        (scenario, game, plane, viewport)
+    }
+
+    #[test]
+    fn rewinds_cells_beyond_infinite_grid() {
+        let (_scenario, mut game, plane, _viewport) = create_infinite_game();
+        let mut cells_by_generation = vec![plane.borrow().alive_cells()];
+        for _ in 0..12 {
+            game.next_generation().unwrap();
+            cells_by_generation.push(plane.borrow().alive_cells());
+        }
+
+        game.jump_to_generation(0);
+        assert_eq!(cells_by_generation[0], plane.borrow().alive_cells());
+        for _ in 0..12 {
+            game.next_generation().unwrap();
+        }
+        assert_eq!(cells_by_generation[12], plane.borrow().alive_cells());
+        for _ in 0..3 {
+            game.undo();
+            let generation = game.timeline_position().current as usize;
+            assert_eq!(
+                cells_by_generation[generation],
+                plane.borrow().alive_cells()
+            );
+        }
+    }
+
+    #[test]
+    fn clears_cells_beyond_infinite_grid() {
+        let (_scenario, mut game, plane, _viewport) = create_infinite_game();
+        for _ in 0..12 {
+            game.next_generation().unwrap();
+        }
+
+        game.clear();
+
+        assert_eq!(0, plane.borrow().population());
+    }
+
+    #[test]
+    fn undoes_moving_viewport_like_edit() {
+        let (_scenario, mut game, plane, viewport) = create_infinite_game();
+        for _ in 0..4 {
+            game.next_generation().unwrap();
+        }
+        let cells = plane.borrow().alive_cells();
+
+        game.move_viewport(2, -1);
+        assert_eq!(Coordinate { x: 2, y: -1 }, viewport.borrow().origin);
+        assert_eq!(cells, plane.borrow().alive_cells());
+        game.undo();
+        assert_eq!(Coordinate { x: 0, y: 0 }, viewport.borrow().origin);
+        assert_eq!(4, game.timeline_position().current);
+        game.undo();
+        assert_eq!(3, game.timeline_position().current);
+        game.redo();
+        game.redo();
+        assert_eq!(Coordinate { x: 2, y: -1 }, viewport.borrow().origin);
+        assert_eq!(cells, plane.borrow().alive_cells());
+    }
+
+    #[test]
+    fn rewinds_to_generations_seen_before_moving_viewport() {
+        let (_scenario, mut game, plane, viewport) = create_infinite_game();
+        let mut cells_by_generation = vec![plane.borrow().alive_cells()];
+        for _ in 0..4 {
+            game.next_generation().unwrap();
+            cells_by_generation.push(plane.borrow().alive_cells());
+        }
+        game.move_viewport(3, 3);
+        for _ in 0..4 {
+            game.next_generation().unwrap();
+            cells_by_generation.push(plane.borrow().alive_cells());
+        }
+
+        game.jump_to_generation(2);
+        assert_eq!(Coordinate { x: 0, y: 0 }, viewport.borrow().origin);
+        assert_eq!(cells_by_generation[2], plane.borrow().alive_cells());
+        game.jump_to_generation(6);
+        assert_eq!(Coordinate { x: 3, y: 3 }, viewport.borrow().origin);
+        assert_eq!(cells_by_generation[6], plane.borrow().alive_cells());
+        game.undo();
+        assert_eq!(Coordinate { x: 0, y: 0 }, viewport.borrow().origin);
+        assert_eq!(cells_by_generation[2], plane.borrow().alive_cells());
+        game.undo();
+        assert_eq!(Coordinate { x: 3, y: 3 }, viewport.borrow().origin);
+        assert_eq!(cells_by_generation[8], plane.borrow().alive_cells());
+    }
+
+    #[test]
+    fn counts_generations_of_calculator_steps() {
+        let (_scenario, mut game, plane, _viewport) = create_hashlife_game(2);
+        let initial_cells = plane.borrow().alive_cells();
+        game.next_generation().unwrap();
+        let stepped_cells = plane.borrow().alive_cells();
+        game.next_generation().unwrap();
+        assert_eq!(8, game.timeline_position().current);
+        assert_eq!(8, game.timeline_position().latest);
+        let expected = Stabilisation {
+            generation: 0,
+            periodicity: Periodicity::Spaceship {
+                dx: 1,
+                dy: 1,
+                period: 4,
+            },
+        };
+        assert_eq!(Some(expected), game.stabilisation());
+
+        game.undo();
+        assert_eq!(4, game.timeline_position().current);
+        assert_eq!(stepped_cells, plane.borrow().alive_cells());
+
+        game.jump_to_generation(3);
+        assert_eq!(0, game.timeline_position().current);
+        assert_eq!(initial_cells, plane.borrow().alive_cells());
+    }
+
+    #[test]
+    fn counts_generations_beyond_32_bits() {
+        let (_scenario, mut game, _plane, _viewport) = create_hashlife_game(31);
+        for _ in 0..3 {
+            game.next_generation().unwrap();
+        }
+        assert_eq!(3 << 31, game.timeline_position().current);
+        game.step_back(1 << 31);
+        assert_eq!(2 << 31, game.timeline_position().current);
     }
 }
@@ -0,0 +1,394 @@
+use crate::generation_calculator::{changes_by_cell, Change, GenerationCalculator};
+use crate::generations::next_decaying_state;
+use crate::grid::{Grid, Position};
+use crate::neighbourhood::Neighbourhood;
+use std::fmt;
+use std::str::FromStr;
+
+const MAX_RANGE: u32 = 500;
+
+// A rule counting the neighbours within a range of more than one cell,
+// written like Bosco's Rule "R5,C0,M1,S34..58,B34..45,NM".
+// C is the number of states as in Generations rules, with 0 standing for 2,
+// and M1 counts the cell itself as its own neighbour.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LargerThanLifeRule {
+    range: u32,
+    states: u8,
+    includes_middle: bool,
+    survival: (u32, u32),
+    birth: (u32, u32),
+    neighbourhood: Neighbourhood,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum LargerThanLifeRuleParseError {
+    MissingPart(char),
+    DuplicatePart(char),
+    UnknownPart(String),
+    InvalidValue(char, String),
+}
+
+impl LargerThanLifeRule {
+    pub fn bosco() -> Self {
+        LargerThanLifeRule {
+            range: 5,
+            states: 2,
+            includes_middle: true,
+            survival: (34, 58),
+            birth: (34, 45),
+            neighbourhood: Neighbourhood::Moore,
+        }
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn next_state(&self, state: u8, neighbours: u32) -> u8 {
+        let (min_birth, max_birth) = self.birth;
+        let (min_survival, max_survival) = self.survival;
+        next_decaying_state(
+            state,
+            self.states,
+            neighbours >= min_birth && neighbours <= max_birth,
+            neighbours >= min_survival && neighbours <= max_survival,
+        )
+    }
+
+    // Looks up every neighbourhood in a summed-area table,
+    // so the range only matters when building the table
+    fn neighbour_counts(&self, grid: &dyn Grid) -> Vec<u32> {
+        let table = SummedAreaTable::new(grid, self.range);
+        let range = self.range;
+        let mut counts = Vec::with_capacity((grid.width() * grid.height()) as usize);
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                // The table is padded by the range, so (x, y) is at (x + range, y + range)
+                let mut count = match self.neighbourhood {
+                    Neighbourhood::Moore => table.sum(x, y, x + 2 * range, y + 2 * range),
+                    Neighbourhood::Hexagonal => unreachable!("Larger than Life rules are square"),
+                    Neighbourhood::VonNeumann => (0..=2 * range)
+                        .map(|row| {
+                            let distance = row.abs_diff(range);
+                            let width = range - distance;
+                            table.sum(x + range - width, y + row, x + range + width, y + row)
+                        })
+                        .sum(),
+                };
+                if !self.includes_middle && grid.is_alive_at(Position { x, y }) {
+                    count -= 1;
+                }
+                counts.push(count);
+            }
+        }
+        counts
+    }
+}
+
+impl FromStr for LargerThanLifeRule {
+    type Err = LargerThanLifeRuleParseError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        for part in rule.trim().split(',').map(str::trim) {
+            let mut characters = part.chars();
+            let prefix = characters
+                .next()
+                .map(|prefix| prefix.to_ascii_uppercase())
+                .filter(|prefix| "RCMSBN".contains(*prefix))
+                .ok_or_else(|| LargerThanLifeRuleParseError::UnknownPart(part.to_string()))?;
+            if parts.iter().any(|&(listed, _)| listed == prefix) {
+                return Err(LargerThanLifeRuleParseError::DuplicatePart(prefix));
+            }
+            parts.push((prefix, characters.as_str()));
+        }
+        let value = |prefix: char| {
+            parts
+                .iter()
+                .find(|&&(listed, _)| listed == prefix)
+                .map(|&(_, value)| value)
+                .ok_or(LargerThanLifeRuleParseError::MissingPart(prefix))
+        };
+        let invalid = |prefix: char, value: &str| {
+            LargerThanLifeRuleParseError::InvalidValue(prefix, value.to_string())
+        };
+
+        let range = value('R')?;
+        let range = range
+            .parse::<u32>()
+            .ok()
+            .filter(|&range| (1..=MAX_RANGE).contains(&range))
+            .ok_or_else(|| invalid('R', range))?;
+        let states = value('C')?;
+        let states = match states.parse::<u8>() {
+            Ok(0) => 2,
+            Ok(states) if states >= 2 => states,
+            _ => return Err(invalid('C', states)),
+        };
+        let includes_middle = match value('M')? {
+            "0" => false,
+            "1" => true,
+            middle => return Err(invalid('M', middle)),
+        };
+        let survival = value('S')?;
+        let survival = parse_count_range(survival).ok_or_else(|| invalid('S', survival))?;
+        let birth = value('B')?;
+        let birth = parse_count_range(birth).ok_or_else(|| invalid('B', birth))?;
+        // The neighbourhood may be left out, in which case it is Moore's
+        let neighbourhood = match value('N') {
+            Ok("M") | Ok("m") | Err(_) => Neighbourhood::Moore,
+            Ok("N") | Ok("n") => Neighbourhood::VonNeumann,
+            Ok(neighbourhood) => return Err(invalid('N', neighbourhood)),
+        };
+        Ok(LargerThanLifeRule {
+            range,
+            states,
+            includes_middle,
+            survival,
+            birth,
+            neighbourhood,
+        })
+    }
+}
+
+// Either a single count or an inclusive range like "34..58"
+fn parse_count_range(counts: &str) -> Option<(u32, u32)> {
+    let mut bounds = counts.splitn(2, "..");
+    let min = bounds.next()?.parse().ok()?;
+    let max = match bounds.next() {
+        Some(max) => max.parse().ok()?,
+        None => min,
+    };
+    if min <= max {
+        Some((min, max))
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for LargerThanLifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let states = if self.states == 2 { 0 } else { self.states };
+        let neighbourhood = match self.neighbourhood {
+            Neighbourhood::Moore => 'M',
+            Neighbourhood::VonNeumann => 'N',
+            Neighbourhood::Hexagonal => unreachable!("Larger than Life rules are square"),
+        };
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},N{}",
+            self.range,
+            states,
+            if self.includes_middle { 1 } else { 0 },
+            self.survival.0,
+            self.survival.1,
+            self.birth.0,
+            self.birth.1,
+            neighbourhood
+        )
+    }
+}
+
+impl fmt::Display for LargerThanLifeRuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LargerThanLifeRuleParseError::MissingPart(prefix) => {
+                write!(f, "expected a '{}' part", prefix)
+            }
+            LargerThanLifeRuleParseError::DuplicatePart(prefix) => {
+                write!(f, "'{}' part is listed twice", prefix)
+            }
+            LargerThanLifeRuleParseError::UnknownPart(part) => {
+                write!(f, "'{}' is not a part of a Larger than Life rule", part)
+            }
+            LargerThanLifeRuleParseError::InvalidValue(prefix, value) => {
+                write!(f, "'{}' is not a valid value for '{}'", value, prefix)
+            }
+        }
+    }
+}
+
+// Alive cells summed over every rectangle starting at the top left corner of the board,
+// which is padded by the range on each side. Cells of the padding are resolved
+// through the grid's topology, so neighbourhoods wrap around the same way the grid does.
+struct SummedAreaTable {
+    width: usize,
+    sums: Vec<u32>,
+}
+
+impl SummedAreaTable {
+    fn new(grid: &dyn Grid, padding: u32) -> Self {
+        let board_width = grid.width();
+        let board_height = grid.height();
+        let topology = grid.topology();
+        let width = (board_width + 2 * padding) as usize + 1;
+        let height = (board_height + 2 * padding) as usize + 1;
+        let mut sums = vec![0; width * height];
+        for y in 1..height {
+            let mut row_sum = 0;
+            for x in 1..width {
+                let is_alive = topology
+                    .resolve(
+                        board_width,
+                        board_height,
+                        x as i64 - 1 - i64::from(padding),
+                        y as i64 - 1 - i64::from(padding),
+                    )
+                    .is_some_and(|position| grid.is_alive_at(position));
+                if is_alive {
+                    row_sum += 1;
+                }
+                sums[y * width + x] = sums[(y - 1) * width + x] + row_sum;
+            }
+        }
+        SummedAreaTable { width, sums }
+    }
+
+    // Alive cells in the padded board from (left, top) to (right, bottom), both included
+    fn sum(&self, left: u32, top: u32, right: u32, bottom: u32) -> u32 {
+        let (left, top) = (left as usize, top as usize);
+        let (right, bottom) = (right as usize + 1, bottom as usize + 1);
+        self.sums[bottom * self.width + right] + self.sums[top * self.width + left]
+            - self.sums[top * self.width + right]
+            - self.sums[bottom * self.width + left]
+    }
+}
+
+#[derive(Debug)]
+pub struct LargerThanLifeCalculator {
+    rule: LargerThanLifeRule,
+}
+
+impl LargerThanLifeCalculator {
+    pub fn new(rule: LargerThanLifeRule) -> Self {
+        LargerThanLifeCalculator { rule }
+    }
+}
+
+impl GenerationCalculator for LargerThanLifeCalculator {
+    fn next_generation(&self, grid: &dyn Grid) -> Vec<Change> {
+        let counts = self.rule.neighbour_counts(grid);
+        changes_by_cell(grid, |position, state| {
+            let neighbours = counts[(position.y * grid.width() + position.x) as usize];
+            self.rule.next_state(state, neighbours)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::GridImpl;
+    use crate::test_util::assert_plays_like_life;
+    use crate::topology::Topology;
+
+    fn create_grid(width: u32, height: u32, topology: Topology) -> GridImpl {
+        let mut grid = GridImpl::with_topology(width, height, topology);
+        for y in 0..height {
+            for x in 0..width {
+                if (x * 7 + y * 3 + x * y) % 5 < 2 {
+                    grid.set_alive_at(Position { x, y });
+                }
+            }
+        }
+        grid
+    }
+
+    fn count_by_hand(grid: &dyn Grid, rule: &LargerThanLifeRule, position: Position) -> u32 {
+        let range = i64::from(rule.range);
+        let mut count = 0;
+        for delta_y in -range..=range {
+            for delta_x in -range..=range {
+                let is_middle = delta_x == 0 && delta_y == 0;
+                let is_outside = rule.neighbourhood == Neighbourhood::VonNeumann
+                    && delta_x.abs() + delta_y.abs() > range;
+                if is_outside || (is_middle && !rule.includes_middle) {
+                    continue;
+                }
+                let neighbour = grid.topology().resolve(
+                    grid.width(),
+                    grid.height(),
+                    i64::from(position.x) + delta_x,
+                    i64::from(position.y) + delta_y,
+                );
+                if neighbour.is_some_and(|neighbour| grid.is_alive_at(neighbour)) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn parses_bosco() {
+        let rule: LargerThanLifeRule = "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap();
+        assert_eq!(LargerThanLifeRule::bosco(), rule);
+        assert_eq!("R5,C0,M1,S34..58,B34..45,NM", rule.to_string());
+    }
+
+    #[test]
+    fn parses_single_counts_and_default_neighbourhood() {
+        let rule: LargerThanLifeRule = "r2, c3, m0, s4, b5..6".parse().unwrap();
+        assert_eq!("R2,C3,M0,S4..4,B5..6,NM", rule.to_string());
+        assert_eq!(3, rule.states());
+        let rule: LargerThanLifeRule = "R3,C0,M0,S2..3,B3..3,NN".parse().unwrap();
+        assert_eq!(Neighbourhood::VonNeumann, rule.neighbourhood);
+    }
+
+    #[test]
+    fn fails_on_invalid_rules() {
+        assert_eq!(
+            Err(LargerThanLifeRuleParseError::MissingPart('B')),
+            "R5,C0,M1,S34..58".parse::<LargerThanLifeRule>()
+        );
+        assert_eq!(
+            Err(LargerThanLifeRuleParseError::DuplicatePart('S')),
+            "R5,C0,M1,S34..58,S1,B34..45".parse::<LargerThanLifeRule>()
+        );
+        assert_eq!(
+            Err(LargerThanLifeRuleParseError::UnknownPart("X1".to_string())),
+            "R5,C0,M1,S34..58,B34..45,X1".parse::<LargerThanLifeRule>()
+        );
+        assert_eq!(
+            Err(LargerThanLifeRuleParseError::InvalidValue(
+                'S',
+                "58..34".to_string()
+            )),
+            "R5,C0,M1,S58..34,B34..45".parse::<LargerThanLifeRule>()
+        );
+        assert_eq!(
+            Err(LargerThanLifeRuleParseError::InvalidValue(
+                'R',
+                "0".to_string()
+            )),
+            "R0,C0,M1,S34..58,B34..45".parse::<LargerThanLifeRule>()
+        );
+    }
+
+    #[test]
+    fn counts_moore_and_von_neumann_neighbourhoods() {
+        for &topology in &[Topology::Bounded, Topology::Torus] {
+            let grid = create_grid(13, 9, topology);
+            for &rule in &["R3,C0,M1,S1,B1,NM", "R2,C0,M0,S1,B1,NN"] {
+                let rule: LargerThanLifeRule = rule.parse().unwrap();
+                let counts = rule.neighbour_counts(&grid);
+                for y in 0..grid.height() {
+                    for x in 0..grid.width() {
+                        let position = Position { x, y };
+                        assert_eq!(
+                            count_by_hand(&grid, &rule, position),
+                            counts[(y * grid.width() + x) as usize]
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn range_one_plays_like_life() {
+        let calculator = LargerThanLifeCalculator::new("R1,C0,M0,S2..3,B3,NM".parse().unwrap());
+        assert_plays_like_life(&calculator);
+    }
+}
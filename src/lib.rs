@@ -1,28 +1,53 @@
-#![feature(
-    proc_macro,
-    wasm_custom_section,
-    wasm_import_module,
-    crate_in_paths,
-    rust_2018_preview,
-    const_vec_new
-)]
+#![cfg_attr(all(test, feature = "bench"), feature(test))]
+// The tests compare booleans with assert_eq! like any other value
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
 
 #[cfg(test)]
 extern crate mockers;
 
 #[cfg(test)]
 extern crate mockers_derive;
+
+#[cfg(feature = "wasm")]
 extern crate wasm_bindgen;
 
+#[cfg(feature = "tui")]
+extern crate termion;
+
 #[macro_use]
 #[cfg(test)]
 extern crate lazy_static;
 
+pub mod active_region;
+pub mod bit_grid;
+pub mod camera;
 pub mod canvas;
-mod coordinate_translator;
+pub mod census;
+pub mod coordinate_translator;
+#[cfg(feature = "wasm")]
 pub mod entrypoint;
-mod generation_calculator;
-mod grid;
-mod grid_info;
-mod input_handler;
-mod interactive_game;
+pub mod generation_calculator;
+pub mod generations;
+pub mod grid;
+pub mod grid_info;
+pub mod hashlife;
+pub mod hensel;
+pub mod hex_layout;
+pub mod history;
+pub mod infinite_grid;
+pub mod input_handler;
+pub mod interactive_game;
+pub mod larger_than_life;
+pub mod neighbourhood;
+pub mod pattern;
+pub mod period_detector;
+pub mod rule;
+pub mod rule_table;
+pub mod soup;
+pub mod soup_search;
+#[cfg(feature = "tui")]
+pub mod terminal;
+#[cfg(test)]
+mod test_util;
+pub mod timeline;
+pub mod topology;
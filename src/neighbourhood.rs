@@ -0,0 +1,262 @@
+use crate::generation_calculator::{
+    changes_by_cell, Change, GenerationCalculator, NEIGHBOUR_OFFSETS,
+};
+use crate::grid::{Grid, Position, ALIVE, DEAD};
+use crate::rule::{Rule, RuleParseError};
+use std::fmt;
+use std::str::FromStr;
+
+const VON_NEUMANN_OFFSETS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+// Odd rows are shifted half a cell to the right,
+// so the cells above and below lie to the left in even rows and to the right in odd ones
+const EVEN_ROW_HEXAGONAL_OFFSETS: [(i64, i64); 6] =
+    [(-1, -1), (0, -1), (1, 0), (0, 1), (-1, 1), (-1, 0)];
+const ODD_ROW_HEXAGONAL_OFFSETS: [(i64, i64); 6] =
+    [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 0)];
+
+// Which of the cells around a cell count as its neighbours
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub enum Neighbourhood {
+    // The square of cells within the range in both directions
+    #[default]
+    Moore,
+    // The diamond of cells whose horizontal and vertical distances add up to at most the range
+    VonNeumann,
+    // The six cells touching a hexagonal cell, laid out in offset rows
+    Hexagonal,
+}
+
+impl Neighbourhood {
+    pub fn offsets(self, y: u32) -> &'static [(i64, i64)] {
+        match self {
+            Neighbourhood::Moore => &NEIGHBOUR_OFFSETS,
+            Neighbourhood::VonNeumann => &VON_NEUMANN_OFFSETS,
+            Neighbourhood::Hexagonal if y.is_multiple_of(2) => &EVEN_ROW_HEXAGONAL_OFFSETS,
+            Neighbourhood::Hexagonal => &ODD_ROW_HEXAGONAL_OFFSETS,
+        }
+    }
+
+    pub fn size(self) -> u32 {
+        self.offsets(0).len() as u32
+    }
+}
+
+pub fn count_neighbours_at(
+    grid: &dyn Grid,
+    position: Position,
+    neighbourhood: Neighbourhood,
+) -> Option<u32> {
+    let width = grid.width();
+    let height = grid.height();
+    if position.x >= width || position.y >= height {
+        return None;
+    }
+
+    let topology = grid.topology();
+    let neighbours = neighbourhood
+        .offsets(position.y)
+        .iter()
+        .filter_map(|&(delta_x, delta_y)| {
+            topology.resolve(
+                width,
+                height,
+                i64::from(position.x) + delta_x,
+                i64::from(position.y) + delta_y,
+            )
+        })
+        .filter(|&neighbour| grid.is_alive_at(neighbour))
+        .count();
+    Some(neighbours as u32)
+}
+
+// A totalistic rule on another neighbourhood than Moore's,
+// marked by a trailing 'V' for von Neumann or 'H' for hexagonal as in "B2/S34H"
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct NeighbourhoodRule {
+    rule: Rule,
+    neighbourhood: Neighbourhood,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum NeighbourhoodRuleParseError {
+    Rule(RuleParseError),
+    TooManyNeighbours(u32),
+}
+
+impl NeighbourhoodRule {
+    pub fn new(rule: Rule, neighbourhood: Neighbourhood) -> Self {
+        NeighbourhoodRule {
+            rule,
+            neighbourhood,
+        }
+    }
+
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+}
+
+impl FromStr for NeighbourhoodRule {
+    type Err = NeighbourhoodRuleParseError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        let (rule, neighbourhood) = match rule.chars().last() {
+            Some('V') | Some('v') => (&rule[..rule.len() - 1], Neighbourhood::VonNeumann),
+            Some('H') | Some('h') => (&rule[..rule.len() - 1], Neighbourhood::Hexagonal),
+            _ => (rule, Neighbourhood::Moore),
+        };
+        let rule: Rule = rule.parse().map_err(NeighbourhoodRuleParseError::Rule)?;
+        let size = neighbourhood.size();
+        if let Some(neighbours) =
+            (size + 1..=8).find(|&neighbours| rule.is_born(neighbours) || rule.survives(neighbours))
+        {
+            return Err(NeighbourhoodRuleParseError::TooManyNeighbours(neighbours));
+        }
+        Ok(NeighbourhoodRule::new(rule, neighbourhood))
+    }
+}
+
+impl fmt::Display for NeighbourhoodRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suffix = match self.neighbourhood {
+            Neighbourhood::Moore => "",
+            Neighbourhood::VonNeumann => "V",
+            Neighbourhood::Hexagonal => "H",
+        };
+        write!(f, "{}{}", self.rule, suffix)
+    }
+}
+
+impl fmt::Display for NeighbourhoodRuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NeighbourhoodRuleParseError::Rule(error) => write!(f, "{}", error),
+            NeighbourhoodRuleParseError::TooManyNeighbours(neighbours) => write!(
+                f,
+                "neighbour count {} is larger than the neighbourhood",
+                neighbours
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NeighbourhoodGenerationCalculator {
+    rule: NeighbourhoodRule,
+}
+
+impl NeighbourhoodGenerationCalculator {
+    pub fn new(rule: NeighbourhoodRule) -> Self {
+        NeighbourhoodGenerationCalculator { rule }
+    }
+}
+
+impl GenerationCalculator for NeighbourhoodGenerationCalculator {
+    fn next_generation(&self, grid: &dyn Grid) -> Vec<Change> {
+        let rule = &self.rule.rule;
+        changes_by_cell(grid, |position, state| {
+            let neighbours = count_neighbours_at(grid, position, self.rule.neighbourhood)
+                .expect("x or y out of bounds");
+            match state {
+                ALIVE if !rule.survives(neighbours) => DEAD,
+                ALIVE => ALIVE,
+                _ if rule.is_born(neighbours) => ALIVE,
+                state => state,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::GridImpl;
+    use crate::test_util::{alive_coordinates, step};
+    use crate::topology::Topology;
+
+    fn calculator(rule: &str) -> NeighbourhoodGenerationCalculator {
+        NeighbourhoodGenerationCalculator::new(rule.parse().unwrap())
+    }
+
+    #[test]
+    fn parses_neighbourhood_suffix() {
+        let rule: NeighbourhoodRule = "B2/S34H".parse().unwrap();
+        assert_eq!(
+            NeighbourhoodRule::new(Rule::new(&[2], &[3, 4]).unwrap(), Neighbourhood::Hexagonal),
+            rule
+        );
+        assert_eq!("B2/S34H", rule.to_string());
+        let rule: NeighbourhoodRule = "b3/s23v".parse().unwrap();
+        assert_eq!(Neighbourhood::VonNeumann, rule.neighbourhood());
+        let rule: NeighbourhoodRule = "B3/S23".parse().unwrap();
+        assert_eq!(Neighbourhood::Moore, rule.neighbourhood());
+    }
+
+    #[test]
+    fn fails_on_counts_beyond_neighbourhood() {
+        assert_eq!(
+            Err(NeighbourhoodRuleParseError::TooManyNeighbours(5)),
+            "B3/S5V".parse::<NeighbourhoodRule>()
+        );
+        assert_eq!(
+            Err(NeighbourhoodRuleParseError::TooManyNeighbours(7)),
+            "B27/S3H".parse::<NeighbourhoodRule>()
+        );
+        assert_eq!(
+            Err(NeighbourhoodRuleParseError::Rule(
+                RuleParseError::MissingSeparator
+            )),
+            "B3V".parse::<NeighbourhoodRule>()
+        );
+    }
+
+    #[test]
+    fn counts_hexagonal_neighbours_in_offset_rows() {
+        let mut grid = GridImpl::new(4, 4);
+        for &(x, y) in &[(0, 0), (1, 0), (2, 0), (0, 1), (1, 2), (2, 2)] {
+            grid.set_alive_at(Position { x, y });
+        }
+        // The odd row is shifted to the right, so (1, 1) touches (1, 0) and (2, 0) above
+        let count = |x, y| count_neighbours_at(&grid, Position { x, y }, Neighbourhood::Hexagonal);
+        assert_eq!(Some(5), count(1, 1));
+        assert_eq!(Some(3), count(1, 0));
+        assert_eq!(Some(1), count(2, 3));
+        assert_eq!(None, count(4, 0));
+    }
+
+    #[test]
+    fn counts_von_neumann_neighbours_across_edges() {
+        let mut grid = GridImpl::with_topology(4, 4, Topology::Torus);
+        for &(x, y) in &[(1, 0), (3, 0), (0, 3), (1, 1)] {
+            grid.set_alive_at(Position { x, y });
+        }
+        let count = count_neighbours_at(&grid, Position { x: 0, y: 0 }, Neighbourhood::VonNeumann);
+        assert_eq!(Some(3), count);
+    }
+
+    #[test]
+    fn grows_plus_in_von_neumann_neighbourhood() {
+        let mut grid = GridImpl::new(5, 5);
+        grid.set_alive_at(Position { x: 2, y: 2 });
+        step(&mut grid, &calculator("B1/SV"));
+        let expected = vec![(2, 1), (1, 2), (3, 2), (2, 3)];
+        assert_eq!(expected, alive_coordinates(&grid));
+    }
+
+    #[test]
+    fn grows_ring_in_hexagonal_neighbourhood() {
+        let mut grid = GridImpl::new(5, 5);
+        grid.set_alive_at(Position { x: 2, y: 1 });
+        step(&mut grid, &calculator("B1/SH"));
+        let expected = vec![(2, 0), (3, 0), (1, 1), (3, 1), (2, 2), (3, 2)];
+        assert_eq!(expected, alive_coordinates(&grid));
+
+        let mut grid = GridImpl::new(5, 5);
+        grid.set_alive_at(Position { x: 2, y: 2 });
+        step(&mut grid, &calculator("B1/SH"));
+        let expected = vec![(1, 1), (2, 1), (1, 2), (3, 2), (1, 3), (2, 3)];
+        assert_eq!(expected, alive_coordinates(&grid));
+    }
+}
@@ -0,0 +1,161 @@
+use super::{ParseError, ParseErrorKind, Pattern};
+use crate::grid::Position;
+
+const HEADER: &str = "#Life 1.06";
+
+// Life 1.06 coordinates may be negative, so the pattern is moved to start at the origin
+pub fn parse(life: &str) -> Result<Pattern, ParseError> {
+    let mut lines = life.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim() == HEADER => {}
+        _ => return Err(ParseError::new(1, 1, ParseErrorKind::MissingHeader)),
+    }
+
+    let mut comments = Vec::new();
+    let mut coordinates = Vec::new();
+    for (index, line) in lines {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(comment) = trimmed.strip_prefix("#D") {
+            comments.push(comment.trim().to_string());
+        } else if !trimmed.starts_with('#') {
+            coordinates.push(parse_coordinate(line, line_number)?);
+        }
+    }
+
+    let min_x = coordinates.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = coordinates.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let mut alive_cells = Vec::new();
+    for (x, y) in coordinates {
+        let position = Position {
+            x: (x - min_x) as u32,
+            y: (y - min_y) as u32,
+        };
+        if !alive_cells.contains(&position) {
+            alive_cells.push(position);
+        }
+    }
+    Ok(Pattern {
+        comments,
+        ..Pattern::from_cells(alive_cells)
+    })
+}
+
+fn parse_coordinate(line: &str, line_number: usize) -> Result<(i64, i64), ParseError> {
+    let column = line.chars().take_while(|c| c.is_whitespace()).count() + 1;
+    let invalid_coordinate = || {
+        ParseError::new(
+            line_number,
+            column,
+            ParseErrorKind::InvalidCoordinate(line.trim().to_string()),
+        )
+    };
+    let parts = line.split_whitespace().collect::<Vec<_>>();
+    if parts.len() != 2 {
+        return Err(invalid_coordinate());
+    }
+    let x: i64 = parts[0].parse().map_err(|_| invalid_coordinate())?;
+    let y: i64 = parts[1].parse().map_err(|_| invalid_coordinate())?;
+    if x.abs() > i64::from(u32::MAX / 2) || y.abs() > i64::from(u32::MAX / 2) {
+        return Err(invalid_coordinate());
+    }
+    Ok((x, y))
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let mut life = format!("{}\n", HEADER);
+    for comment in &pattern.comments {
+        life.push_str(&format!("#D {}\n", comment));
+    }
+    for position in &pattern.alive_cells {
+        life.push_str(&format!("{} {}\n", position.x, position.y));
+    }
+    life
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_glider_with_negative_coordinates() {
+        let life = "#Life 1.06
+#D Glider
+0 -1
+1 0
+-1 1
+0 1
+1 1
+";
+        let expected = Pattern {
+            comments: vec!["Glider".to_string()],
+            width: 3,
+            height: 3,
+            alive_cells: vec![
+                Position { x: 1, y: 0 },
+                Position { x: 2, y: 1 },
+                Position { x: 0, y: 2 },
+                Position { x: 1, y: 2 },
+                Position { x: 2, y: 2 },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(Ok(expected), parse(life));
+    }
+
+    #[test]
+    fn ignores_duplicate_cells() {
+        let pattern = parse("#Life 1.06\n3 4\n3 4\n").unwrap();
+        assert_eq!(vec![Position { x: 0, y: 0 }], pattern.alive_cells);
+    }
+
+    #[test]
+    fn parses_empty_pattern() {
+        let pattern = parse("#Life 1.06\n").unwrap();
+        assert_eq!(0, pattern.width);
+        assert!(pattern.alive_cells.is_empty());
+    }
+
+    #[test]
+    fn fails_without_header() {
+        let error = parse("0 0\n").unwrap_err();
+        assert_eq!(ParseError::new(1, 1, ParseErrorKind::MissingHeader), error);
+    }
+
+    #[test]
+    fn fails_on_invalid_coordinate() {
+        let error = parse("#Life 1.06\n0 0\n  1 x\n").unwrap_err();
+        let expected = ParseError::new(3, 3, ParseErrorKind::InvalidCoordinate("1 x".to_string()));
+        assert_eq!(expected, error);
+    }
+
+    #[test]
+    fn fails_on_missing_coordinate() {
+        let error = parse("#Life 1.06\n5\n").unwrap_err();
+        assert_eq!(
+            ParseErrorKind::InvalidCoordinate("5".to_string()),
+            error.kind
+        );
+    }
+
+    #[test]
+    fn round_trips_pattern() {
+        let pattern = Pattern {
+            comments: vec!["Blinker".to_string()],
+            width: 3,
+            height: 1,
+            alive_cells: vec![
+                Position { x: 0, y: 0 },
+                Position { x: 1, y: 0 },
+                Position { x: 2, y: 0 },
+            ],
+            ..Default::default()
+        };
+        let life = write(&pattern);
+        assert_eq!("#Life 1.06\n#D Blinker\n0 0\n1 0\n2 0\n", life);
+        assert_eq!(Ok(pattern), parse(&life));
+    }
+}
@@ -0,0 +1,292 @@
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+use crate::generation_calculator::Change;
+use crate::grid::{alive_cells, cell_states, Grid, GridImpl, Position, ALIVE};
+use crate::rule::{Rule, RuleParseError};
+use crate::topology::{GridSpec, GridSpecParseError};
+use std::fmt;
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+    pub rule: Option<Rule>,
+    pub grid_spec: Option<GridSpec>,
+    pub width: u32,
+    pub height: u32,
+    pub alive_cells: Vec<Position>,
+    // Cells in states beyond ALIVE, which only multi-state rules use
+    pub multi_state_cells: Vec<Change>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ParseErrorKind {
+    MissingHeader,
+    InvalidHeader(String),
+    InvalidRule(RuleParseError),
+    InvalidGridSpec(GridSpecParseError),
+    UnexpectedCharacter(char),
+    CellOutsideBounds,
+    RunTooLong,
+    InvalidCoordinate(String),
+}
+
+// Tells the formats apart by their header, falling back to plaintext
+pub fn parse(text: &str) -> Result<Pattern, ParseError> {
+    let first_line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+    let has_rle_header = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .is_some_and(|line| line.starts_with('x') && line[1..].trim_start().starts_with('='));
+    if first_line.starts_with("#Life 1.06") {
+        life106::parse(text)
+    } else if first_line.starts_with('#') || has_rle_header {
+        rle::parse(text)
+    } else {
+        plaintext::parse(text)
+    }
+}
+
+impl Pattern {
+    pub fn from_grid(grid: &dyn Grid) -> Self {
+        Pattern {
+            width: grid.width(),
+            height: grid.height(),
+            alive_cells: alive_cells(grid),
+            multi_state_cells: cell_states(grid)
+                .into_iter()
+                .filter(|cell| cell.state != ALIVE)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn to_grid(&self) -> GridImpl {
+        let mut grid = GridImpl::new(self.width, self.height);
+        for &position in &self.alive_cells {
+            grid.set_alive_at(position);
+        }
+        for cell in &self.multi_state_cells {
+            grid.set_state_at(cell.position, cell.state);
+        }
+        grid
+    }
+
+    // Cells that fall off a bounded grid are dropped, the other topologies wrap them around
+    pub fn placement_changes(&self, grid: &dyn Grid, offset: Position) -> Vec<Change> {
+        let topology = grid.topology();
+        let mut changes = Vec::new();
+        let alive_cells = self.alive_cells.iter().map(|&position| Change {
+            position,
+            state: ALIVE,
+        });
+        for cell in alive_cells.chain(self.multi_state_cells.iter().cloned()) {
+            let position = &cell.position;
+            let target = topology.resolve(
+                grid.width(),
+                grid.height(),
+                i64::from(offset.x) + i64::from(position.x),
+                i64::from(offset.y) + i64::from(position.y),
+            );
+            if let Some(target) = target {
+                let change = Change {
+                    position: target,
+                    state: cell.state,
+                };
+                if grid.state_at(target) != cell.state && !changes.contains(&change) {
+                    changes.push(change);
+                }
+            }
+        }
+        changes
+    }
+
+    pub fn place(&self, grid: &mut dyn Grid, offset: Position) {
+        for change in self.placement_changes(grid, offset) {
+            grid.set_state_at(change.position, change.state);
+        }
+    }
+
+    fn from_cells(alive_cells: Vec<Position>) -> Self {
+        let width = alive_cells.iter().map(|position| position.x + 1).max();
+        let height = alive_cells.iter().map(|position| position.y + 1).max();
+        Pattern {
+            width: width.unwrap_or(0),
+            height: height.unwrap_or(0),
+            alive_cells,
+            ..Default::default()
+        }
+    }
+}
+
+impl ParseError {
+    fn new(line: usize, column: usize, kind: ParseErrorKind) -> Self {
+        ParseError { line, column, kind }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::MissingHeader => write!(f, "expected a 'x = .., y = ..' header"),
+            ParseErrorKind::InvalidHeader(part) => {
+                write!(f, "'{}' is not a valid header entry", part)
+            }
+            ParseErrorKind::InvalidRule(error) => write!(f, "invalid rule: {}", error),
+            ParseErrorKind::InvalidGridSpec(error) => write!(f, "invalid grid: {}", error),
+            ParseErrorKind::UnexpectedCharacter(character) => {
+                write!(f, "unexpected character '{}'", character)
+            }
+            ParseErrorKind::CellOutsideBounds => {
+                write!(f, "cell lies outside of the declared size")
+            }
+            ParseErrorKind::RunTooLong => write!(f, "run count is too large"),
+            ParseErrorKind::InvalidCoordinate(coordinate) => {
+                write!(f, "'{}' is not a valid coordinate", coordinate)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::topology::Topology;
+
+    #[test]
+    fn creates_pattern_from_grid() {
+        let mut grid = GridImpl::new(4, 3);
+        grid.set_alive_at(Position { x: 3, y: 0 });
+        grid.set_alive_at(Position { x: 1, y: 2 });
+
+        let pattern = Pattern::from_grid(&grid);
+
+        let expected = Pattern {
+            width: 4,
+            height: 3,
+            alive_cells: vec![Position { x: 3, y: 0 }, Position { x: 1, y: 2 }],
+            ..Default::default()
+        };
+        assert_eq!(expected, pattern);
+    }
+
+    #[test]
+    fn converts_pattern_back_to_grid() {
+        let mut grid = GridImpl::new(4, 3);
+        grid.set_alive_at(Position { x: 0, y: 1 });
+        grid.set_alive_at(Position { x: 2, y: 2 });
+
+        assert_eq!(grid, Pattern::from_grid(&grid).to_grid());
+    }
+
+    #[test]
+    fn keeps_states_of_multi_state_cells() {
+        let mut grid = GridImpl::new(3, 2);
+        grid.set_alive_at(Position { x: 0, y: 0 });
+        grid.set_state_at(Position { x: 2, y: 1 }, 3);
+
+        let pattern = Pattern::from_grid(&grid);
+        let expected = vec![Change {
+            position: Position { x: 2, y: 1 },
+            state: 3,
+        }];
+        assert_eq!(expected, pattern.multi_state_cells);
+        assert_eq!(grid, pattern.to_grid());
+
+        let mut placed = GridImpl::new(3, 2);
+        pattern.place(&mut placed, Position { x: 0, y: 0 });
+        assert_eq!(grid, placed);
+    }
+
+    #[test]
+    fn places_pattern_at_offset() {
+        let pattern = Pattern::from_cells(vec![Position { x: 0, y: 0 }, Position { x: 1, y: 1 }]);
+        let mut grid = GridImpl::new(5, 5);
+        grid.set_alive_at(Position { x: 3, y: 2 });
+
+        pattern.place(&mut grid, Position { x: 3, y: 2 });
+
+        let mut expected = GridImpl::new(5, 5);
+        expected.set_alive_at(Position { x: 3, y: 2 });
+        expected.set_alive_at(Position { x: 4, y: 3 });
+        assert_eq!(expected, grid);
+    }
+
+    #[test]
+    fn placement_only_reports_cells_that_change() {
+        let pattern = Pattern::from_cells(vec![Position { x: 0, y: 0 }, Position { x: 1, y: 0 }]);
+        let mut grid = GridImpl::new(5, 5);
+        grid.set_alive_at(Position { x: 2, y: 1 });
+
+        let changes = pattern.placement_changes(&grid, Position { x: 1, y: 1 });
+
+        let expected = vec![Change {
+            position: Position { x: 1, y: 1 },
+            state: ALIVE,
+        }];
+        assert_eq!(expected, changes);
+    }
+
+    #[test]
+    fn clips_pattern_on_bounded_grid() {
+        let pattern = Pattern::from_cells(vec![Position { x: 0, y: 0 }, Position { x: 2, y: 0 }]);
+        let mut grid = GridImpl::new(4, 4);
+
+        pattern.place(&mut grid, Position { x: 2, y: 3 });
+
+        let mut expected = GridImpl::new(4, 4);
+        expected.set_alive_at(Position { x: 2, y: 3 });
+        assert_eq!(expected, grid);
+    }
+
+    #[test]
+    fn wraps_pattern_on_torus() {
+        let pattern = Pattern::from_cells(vec![Position { x: 0, y: 0 }, Position { x: 2, y: 0 }]);
+        let mut grid = GridImpl::with_topology(4, 4, Topology::Torus);
+
+        pattern.place(&mut grid, Position { x: 2, y: 3 });
+
+        assert!(grid.is_alive_at(Position { x: 2, y: 3 }));
+        assert!(grid.is_alive_at(Position { x: 0, y: 3 }));
+    }
+
+    #[test]
+    fn detects_format() {
+        let expected = vec![Position { x: 1, y: 0 }, Position { x: 0, y: 1 }];
+        let patterns = [
+            "#C comment\nx = 2, y = 2\nbo$o!",
+            "x=2,y=2\nbo$o!",
+            "!Name: Diagonal\n.O\nO.",
+            ".O\nO.",
+            "#Life 1.06\n1 0\n0 1",
+        ];
+        for pattern in &patterns {
+            assert_eq!(expected, parse(pattern).unwrap().alive_cells);
+        }
+    }
+
+    #[test]
+    fn displays_error_location() {
+        let error = ParseError::new(3, 7, ParseErrorKind::UnexpectedCharacter('z'));
+        assert_eq!(
+            "line 3, column 7: unexpected character 'z'",
+            error.to_string()
+        );
+    }
+}
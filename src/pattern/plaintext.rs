@@ -0,0 +1,154 @@
+use super::{ParseError, ParseErrorKind, Pattern};
+use crate::grid::Position;
+
+const NAME_PREFIX: &str = "Name:";
+const AUTHOR_PREFIX: &str = "Author:";
+
+pub fn parse(plaintext: &str) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let mut rows = Vec::new();
+    for (index, line) in plaintext.lines().enumerate() {
+        if let Some(comment) = line.strip_prefix('!') {
+            parse_comment(comment.trim(), &mut pattern);
+        } else {
+            rows.push((index + 1, line.trim_end()));
+        }
+    }
+    while rows.last().is_some_and(|(_, row)| row.is_empty()) {
+        rows.pop();
+    }
+
+    for (y, (line_number, row)) in rows.iter().enumerate() {
+        for (x, character) in row.chars().enumerate() {
+            match character {
+                '.' => {}
+                'O' | '*' => pattern.alive_cells.push(Position {
+                    x: x as u32,
+                    y: y as u32,
+                }),
+                character => {
+                    return Err(ParseError::new(
+                        *line_number,
+                        x + 1,
+                        ParseErrorKind::UnexpectedCharacter(character),
+                    ))
+                }
+            }
+        }
+    }
+    pattern.width = rows
+        .iter()
+        .map(|(_, row)| row.chars().count() as u32)
+        .max()
+        .unwrap_or(0);
+    pattern.height = rows.len() as u32;
+    Ok(pattern)
+}
+
+fn parse_comment(comment: &str, pattern: &mut Pattern) {
+    if let Some(name) = comment.strip_prefix(NAME_PREFIX) {
+        pattern.name = Some(name.trim().to_string());
+    } else if let Some(author) = comment.strip_prefix(AUTHOR_PREFIX) {
+        pattern.author = Some(author.trim().to_string());
+    } else {
+        pattern.comments.push(comment.to_string());
+    }
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let mut plaintext = String::new();
+    if let Some(name) = &pattern.name {
+        plaintext.push_str(&format!("!{} {}\n", NAME_PREFIX, name));
+    }
+    if let Some(author) = &pattern.author {
+        plaintext.push_str(&format!("!{} {}\n", AUTHOR_PREFIX, author));
+    }
+    for comment in &pattern.comments {
+        plaintext.push_str(&format!("!{}\n", comment));
+    }
+
+    let mut rows = vec![vec!['.'; pattern.width as usize]; pattern.height as usize];
+    for position in &pattern.alive_cells {
+        rows[position.y as usize][position.x as usize] = 'O';
+    }
+    for row in rows {
+        plaintext.extend(row);
+        plaintext.push('\n');
+    }
+    plaintext
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GLIDER: &str = "!Name: Glider
+!Author: Richard K. Guy
+!The smallest, most common, and first discovered spaceship.
+.O.
+..O
+OOO
+";
+
+    fn glider() -> Pattern {
+        Pattern {
+            name: Some("Glider".to_string()),
+            author: Some("Richard K. Guy".to_string()),
+            comments: vec!["The smallest, most common, and first discovered spaceship.".to_string()],
+            width: 3,
+            height: 3,
+            alive_cells: vec![
+                Position { x: 1, y: 0 },
+                Position { x: 2, y: 1 },
+                Position { x: 0, y: 2 },
+                Position { x: 1, y: 2 },
+                Position { x: 2, y: 2 },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_glider() {
+        assert_eq!(Ok(glider()), parse(GLIDER));
+    }
+
+    #[test]
+    fn parses_short_and_empty_rows() {
+        let pattern = parse("O\n\n..O\n\n").unwrap();
+        assert_eq!(3, pattern.width);
+        assert_eq!(3, pattern.height);
+        let expected = vec![Position { x: 0, y: 0 }, Position { x: 2, y: 2 }];
+        assert_eq!(expected, pattern.alive_cells);
+    }
+
+    #[test]
+    fn accepts_asterisks() {
+        let pattern = parse("*.*").unwrap();
+        let expected = vec![Position { x: 0, y: 0 }, Position { x: 2, y: 0 }];
+        assert_eq!(expected, pattern.alive_cells);
+    }
+
+    #[test]
+    fn fails_on_unexpected_character() {
+        let error = parse("!Name: Broken\n.O.\n.Ox").unwrap_err();
+        let expected = ParseError::new(3, 3, ParseErrorKind::UnexpectedCharacter('x'));
+        assert_eq!(expected, error);
+    }
+
+    #[test]
+    fn writes_glider() {
+        assert_eq!(GLIDER, write(&glider()));
+    }
+
+    #[test]
+    fn writes_empty_rows_at_full_width() {
+        let pattern = Pattern {
+            width: 2,
+            height: 2,
+            alive_cells: vec![Position { x: 1, y: 1 }],
+            ..Default::default()
+        };
+        assert_eq!("..\n.O\n", write(&pattern));
+    }
+}
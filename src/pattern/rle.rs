@@ -0,0 +1,614 @@
+use super::{ParseError, ParseErrorKind, Pattern};
+use crate::generation_calculator::Change;
+use crate::grid::{Position, ALIVE};
+use crate::rule::Rule;
+
+const MAX_LINE_LENGTH: usize = 70;
+// Multi-state patterns write states 1 to 24 as 'A' to 'X', and higher ones behind
+// a prefix from 'p' to 'y' that adds 24 states each
+const STATES_PER_LETTER_RANGE: u32 = 24;
+
+pub fn parse(rle: &str) -> Result<Pattern, ParseError> {
+    let mut pattern = Pattern::default();
+    let mut has_header = false;
+    let mut body = Body::default();
+
+    for (index, line) in rle.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            parse_comment(trimmed, &mut pattern);
+        } else if !has_header {
+            if !trimmed.starts_with('x') {
+                return Err(ParseError::new(
+                    line_number,
+                    column_at(line, line.len() - line.trim_start().len()),
+                    ParseErrorKind::MissingHeader,
+                ));
+            }
+            parse_header(line, line_number, &mut pattern)?;
+            has_header = true;
+        } else if body.parse_line(line, line_number, &mut pattern)? {
+            break;
+        }
+    }
+
+    if !has_header {
+        let line_number = rle.lines().count().max(1);
+        return Err(ParseError::new(
+            line_number,
+            1,
+            ParseErrorKind::MissingHeader,
+        ));
+    }
+    Ok(pattern)
+}
+
+fn parse_comment(line: &str, pattern: &mut Pattern) {
+    let text = line.get(2..).unwrap_or("").trim().to_string();
+    match line.chars().nth(1) {
+        Some('N') => pattern.name = Some(text),
+        Some('O') => pattern.author = Some(text),
+        Some('C') | Some('c') => pattern.comments.push(text),
+        _ => {}
+    }
+}
+
+// Parses e.g. "x = 3, y = 3, rule = B3/S23:T10,10", where the rule always comes last
+fn parse_header(line: &str, line_number: usize, pattern: &mut Pattern) -> Result<(), ParseError> {
+    let mut width = None;
+    let mut height = None;
+    let mut offset = 0;
+    while offset < line.len() {
+        let rest = &line[offset..];
+        let entry_column = column_at(line, offset + rest.len() - rest.trim_start().len());
+        let invalid_entry = |entry: &str| {
+            ParseError::new(
+                line_number,
+                entry_column,
+                ParseErrorKind::InvalidHeader(entry.trim().to_string()),
+            )
+        };
+        let equals = rest.find('=').ok_or_else(|| invalid_entry(rest))?;
+        let key = rest[..equals].trim();
+        let value = &rest[equals + 1..];
+        let value_column = column_at(
+            line,
+            offset + equals + 1 + value.len() - value.trim_start().len(),
+        );
+        let value_length = if key == "rule" {
+            value.len()
+        } else {
+            value.find(',').unwrap_or(value.len())
+        };
+        let entry = &rest[..equals + 1 + value_length];
+        let value = value[..value_length].trim();
+        match key {
+            "x" => width = Some(value.parse().map_err(|_| invalid_entry(entry))?),
+            "y" => height = Some(value.parse().map_err(|_| invalid_entry(entry))?),
+            "rule" => parse_rule(value, line_number, value_column, pattern)?,
+            _ => return Err(invalid_entry(entry)),
+        }
+        offset += entry.len() + 1;
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => {
+            pattern.width = width;
+            pattern.height = height;
+            Ok(())
+        }
+        _ => Err(ParseError::new(
+            line_number,
+            1,
+            ParseErrorKind::InvalidHeader(line.trim().to_string()),
+        )),
+    }
+}
+
+fn parse_rule(
+    value: &str,
+    line_number: usize,
+    column: usize,
+    pattern: &mut Pattern,
+) -> Result<(), ParseError> {
+    let mut parts = value.splitn(2, ':');
+    let rule = parts.next().unwrap_or("");
+    pattern.rule = Some(rule.parse().map_err(|error| {
+        ParseError::new(line_number, column, ParseErrorKind::InvalidRule(error))
+    })?);
+    if let Some(grid_spec) = parts.next() {
+        pattern.grid_spec = Some(grid_spec.parse().map_err(|error| {
+            ParseError::new(line_number, column, ParseErrorKind::InvalidGridSpec(error))
+        })?);
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Body {
+    x: u32,
+    y: u32,
+    run_count: Option<u32>,
+    prefix: Option<char>,
+}
+
+impl Body {
+    // Returns whether the terminating '!' was reached
+    fn parse_line(
+        &mut self,
+        line: &str,
+        line_number: usize,
+        pattern: &mut Pattern,
+    ) -> Result<bool, ParseError> {
+        for (index, character) in line.chars().enumerate() {
+            let error = |kind| ParseError::new(line_number, index + 1, kind);
+            if let Some(prefix) = self.prefix.take() {
+                let state = match character {
+                    'A'..='X' => prefixed_state(prefix, character),
+                    _ => None,
+                }
+                .ok_or_else(|| error(ParseErrorKind::UnexpectedCharacter(character)))?;
+                let run_count = self.run_count.take().unwrap_or(1);
+                self.push_cells(state, run_count, pattern).map_err(error)?;
+                continue;
+            }
+            if let Some(digit) = character.to_digit(10) {
+                let run_count = self
+                    .run_count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|run_count| run_count.checked_add(digit))
+                    .ok_or_else(|| error(ParseErrorKind::RunTooLong))?;
+                self.run_count = Some(run_count);
+                continue;
+            }
+            if let 'p'..='y' = character {
+                self.prefix = Some(character);
+                continue;
+            }
+            let run_count = self.run_count.take().unwrap_or(1);
+            match character {
+                'b' | '.' => {
+                    self.x = self
+                        .x
+                        .checked_add(run_count)
+                        .ok_or_else(|| error(ParseErrorKind::RunTooLong))?;
+                }
+                'o' => self.push_cells(ALIVE, run_count, pattern).map_err(error)?,
+                'A'..='X' => {
+                    let state = character as u8 - b'A' + 1;
+                    self.push_cells(state, run_count, pattern).map_err(error)?;
+                }
+                '$' => {
+                    self.x = 0;
+                    self.y = self
+                        .y
+                        .checked_add(run_count)
+                        .ok_or_else(|| error(ParseErrorKind::RunTooLong))?;
+                }
+                '!' => return Ok(true),
+                character if character.is_whitespace() => {}
+                character => return Err(error(ParseErrorKind::UnexpectedCharacter(character))),
+            }
+        }
+        Ok(false)
+    }
+
+    fn push_cells(
+        &mut self,
+        state: u8,
+        run_count: u32,
+        pattern: &mut Pattern,
+    ) -> Result<(), ParseErrorKind> {
+        let end = u64::from(self.x) + u64::from(run_count);
+        if end > u64::from(pattern.width) || self.y >= pattern.height {
+            return Err(ParseErrorKind::CellOutsideBounds);
+        }
+        for x in self.x..end as u32 {
+            let position = Position { x, y: self.y };
+            if state == ALIVE {
+                pattern.alive_cells.push(position);
+            } else {
+                pattern.multi_state_cells.push(Change { position, state });
+            }
+        }
+        self.x = end as u32;
+        Ok(())
+    }
+}
+
+// Returns None for states beyond 255, e.g. "yP"
+fn prefixed_state(prefix: char, letter: char) -> Option<u8> {
+    let ranges = prefix as u32 - 'p' as u32 + 1;
+    let state = ranges * STATES_PER_LETTER_RANGE + letter as u32 - 'A' as u32 + 1;
+    if state <= u32::from(u8::MAX) {
+        Some(state as u8)
+    } else {
+        None
+    }
+}
+
+fn column_at(line: &str, byte_offset: usize) -> usize {
+    line[..byte_offset].chars().count() + 1
+}
+
+pub fn write(pattern: &Pattern) -> String {
+    let mut rle = String::new();
+    if let Some(name) = &pattern.name {
+        rle.push_str(&format!("#N {}\n", name));
+    }
+    if let Some(author) = &pattern.author {
+        rle.push_str(&format!("#O {}\n", author));
+    }
+    for comment in &pattern.comments {
+        rle.push_str(&format!("#C {}\n", comment));
+    }
+    rle.push_str(&format!("x = {}, y = {}", pattern.width, pattern.height));
+    if let Some(rule) = &pattern.rule {
+        rle.push_str(&format!(", rule = {}", rule));
+    } else if pattern.grid_spec.is_some() {
+        rle.push_str(&format!(", rule = {}", Rule::default()));
+    }
+    if let Some(grid_spec) = &pattern.grid_spec {
+        rle.push_str(&format!(":{}", grid_spec));
+    }
+    rle.push('\n');
+
+    let mut line = String::new();
+    for token in body_tokens(pattern) {
+        if line.len() + token.len() > MAX_LINE_LENGTH {
+            rle.push_str(&line);
+            rle.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+    rle.push_str(&line);
+    rle.push('\n');
+    rle
+}
+
+// Patterns of two-state rules keep the 'b' and 'o' tags that every reader understands
+fn body_tokens(pattern: &Pattern) -> Vec<String> {
+    let is_multi_state = !pattern.multi_state_cells.is_empty();
+    let mut cells = pattern
+        .alive_cells
+        .iter()
+        .map(|&position| Change {
+            position,
+            state: ALIVE,
+        })
+        .chain(pattern.multi_state_cells.iter().cloned())
+        .collect::<Vec<_>>();
+    cells.sort_by_key(|cell| (cell.position.y, cell.position.x));
+    cells.dedup_by_key(|cell| cell.position);
+
+    let mut tokens = Vec::new();
+    let (mut x, mut y) = (0, 0);
+    let mut cells = cells.iter().peekable();
+    while let Some(start) = cells.next() {
+        let mut run_length = 1;
+        while cells.peek().is_some_and(|cell| {
+            cell.state == start.state
+                && cell.position
+                    == Position {
+                        x: start.position.x + run_length,
+                        y: start.position.y,
+                    }
+        }) {
+            cells.next();
+            run_length += 1;
+        }
+        if start.position.y > y {
+            tokens.push(token(start.position.y - y, "$"));
+            x = 0;
+            y = start.position.y;
+        }
+        if start.position.x > x {
+            let tag = if is_multi_state { "." } else { "b" };
+            tokens.push(token(start.position.x - x, tag));
+        }
+        let tag = if is_multi_state {
+            state_tag(start.state)
+        } else {
+            "o".to_string()
+        };
+        tokens.push(token(run_length, &tag));
+        x = start.position.x + run_length;
+    }
+    tokens.push("!".to_string());
+    tokens
+}
+
+fn state_tag(state: u8) -> String {
+    let index = u32::from(state) - 1;
+    let letter = (b'A' + (index % STATES_PER_LETTER_RANGE) as u8) as char;
+    match index / STATES_PER_LETTER_RANGE {
+        0 => letter.to_string(),
+        ranges => format!("{}{}", (b'p' + ranges as u8 - 1) as char, letter),
+    }
+}
+
+fn token(run_count: u32, tag: &str) -> String {
+    if run_count == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", run_count, tag)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rule::RuleParseError;
+    use crate::topology::{GridSpec, Topology};
+
+    const GLIDER: &str = "#N Glider
+#O Richard K. Guy
+#C The smallest, most common, and first discovered spaceship.
+#C www.conwaylife.com/wiki/index.php?title=Glider
+x = 3, y = 3, rule = B3/S23
+bob$2bo$3o!
+";
+
+    fn glider_cells() -> Vec<Position> {
+        vec![
+            Position { x: 1, y: 0 },
+            Position { x: 2, y: 1 },
+            Position { x: 0, y: 2 },
+            Position { x: 1, y: 2 },
+            Position { x: 2, y: 2 },
+        ]
+    }
+
+    #[test]
+    fn parses_glider_with_comments() {
+        let pattern = parse(GLIDER).unwrap();
+
+        let expected = Pattern {
+            name: Some("Glider".to_string()),
+            author: Some("Richard K. Guy".to_string()),
+            comments: vec![
+                "The smallest, most common, and first discovered spaceship.".to_string(),
+                "www.conwaylife.com/wiki/index.php?title=Glider".to_string(),
+            ],
+            rule: Some(Rule::conway()),
+            grid_spec: None,
+            width: 3,
+            height: 3,
+            alive_cells: glider_cells(),
+            multi_state_cells: Vec::new(),
+        };
+        assert_eq!(expected, pattern);
+    }
+
+    #[test]
+    fn parses_header_without_rule() {
+        let pattern = parse("x=3,y=3\nbo$2bo$3o!").unwrap();
+        assert_eq!(None, pattern.rule);
+        assert_eq!(glider_cells(), pattern.alive_cells);
+    }
+
+    #[test]
+    fn parses_rule_with_grid_spec() {
+        let pattern = parse("x = 1, y = 1, rule = B36/S23:T10,8\no!").unwrap();
+        assert_eq!(Some(Rule::new(&[3, 6], &[2, 3]).unwrap()), pattern.rule);
+        let expected = GridSpec {
+            topology: Topology::Torus,
+            width: 10,
+            height: 8,
+        };
+        assert_eq!(Some(expected), pattern.grid_spec);
+    }
+
+    #[test]
+    fn parses_body_wrapped_across_lines() {
+        let pattern = parse("x = 3, y = 3\nb\no$2b\no$3\no!").unwrap();
+        assert_eq!(glider_cells(), pattern.alive_cells);
+    }
+
+    #[test]
+    fn parses_multiple_line_ends_and_missing_terminator() {
+        let error = parse("x = 2, y = 4\n2o3$obo").unwrap_err();
+        assert_eq!(ParseErrorKind::CellOutsideBounds, error.kind);
+
+        let pattern = parse("x = 3, y = 4\n2o3$obo").unwrap();
+        let expected = vec![
+            Position { x: 0, y: 0 },
+            Position { x: 1, y: 0 },
+            Position { x: 0, y: 3 },
+            Position { x: 2, y: 3 },
+        ];
+        assert_eq!(expected, pattern.alive_cells);
+    }
+
+    #[test]
+    fn ignores_everything_after_terminator() {
+        let pattern = parse("x = 1, y = 1\no!\nthis is not rle").unwrap();
+        assert_eq!(vec![Position { x: 0, y: 0 }], pattern.alive_cells);
+    }
+
+    #[test]
+    fn fails_on_unexpected_character() {
+        let error = parse("#C comment\nx = 3, y = 3\nbob$2bo$\n3oz!").unwrap_err();
+        let expected = ParseError::new(4, 3, ParseErrorKind::UnexpectedCharacter('z'));
+        assert_eq!(expected, error);
+    }
+
+    #[test]
+    fn fails_on_cell_outside_declared_size() {
+        let error = parse("x = 2, y = 1\nb2o!").unwrap_err();
+        let expected = ParseError::new(2, 3, ParseErrorKind::CellOutsideBounds);
+        assert_eq!(expected, error);
+    }
+
+    #[test]
+    fn fails_on_missing_header() {
+        let error = parse("#N Nothing\n  bob$2bo$3o!").unwrap_err();
+        let expected = ParseError::new(2, 3, ParseErrorKind::MissingHeader);
+        assert_eq!(expected, error);
+        assert_eq!(ParseErrorKind::MissingHeader, parse("").unwrap_err().kind);
+    }
+
+    #[test]
+    fn fails_on_invalid_header_entry() {
+        let error = parse("x = 3, y = three\no!").unwrap_err();
+        let expected =
+            ParseError::new(1, 8, ParseErrorKind::InvalidHeader("y = three".to_string()));
+        assert_eq!(expected, error);
+    }
+
+    #[test]
+    fn fails_on_header_without_height() {
+        let error = parse("x = 3\no!").unwrap_err();
+        assert_eq!(
+            ParseErrorKind::InvalidHeader("x = 3".to_string()),
+            error.kind
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_rule() {
+        let error = parse("x = 3, y = 3, rule = B9/S23\no!").unwrap_err();
+        let expected = ParseError::new(
+            1,
+            22,
+            ParseErrorKind::InvalidRule(RuleParseError::InvalidNeighbourCount('9')),
+        );
+        assert_eq!(expected, error);
+    }
+
+    #[test]
+    fn fails_on_too_long_run() {
+        let error = parse("x = 3, y = 3\n99999999999o!").unwrap_err();
+        assert_eq!(ParseErrorKind::RunTooLong, error.kind);
+    }
+
+    #[test]
+    fn writes_glider() {
+        let pattern = Pattern {
+            rule: Some(Rule::conway()),
+            width: 3,
+            height: 3,
+            alive_cells: glider_cells(),
+            ..Default::default()
+        };
+        assert_eq!("x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n", write(&pattern));
+    }
+
+    #[test]
+    fn writes_comments_and_grid_spec() {
+        let pattern = Pattern {
+            name: Some("Block".to_string()),
+            author: Some("John Conway".to_string()),
+            comments: vec!["A still life".to_string()],
+            grid_spec: Some(GridSpec {
+                topology: Topology::Torus,
+                width: 4,
+                height: 4,
+            }),
+            width: 4,
+            height: 4,
+            alive_cells: vec![
+                Position { x: 1, y: 1 },
+                Position { x: 2, y: 1 },
+                Position { x: 1, y: 2 },
+                Position { x: 2, y: 2 },
+            ],
+            ..Default::default()
+        };
+        let expected = "#N Block
+#O John Conway
+#C A still life
+x = 4, y = 4, rule = B3/S23:T4,4
+$b2o$b2o!
+";
+        assert_eq!(expected, write(&pattern));
+    }
+
+    #[test]
+    fn wraps_long_lines() {
+        let alive_cells = (0..200)
+            .filter(|x| x % 2 == 0)
+            .map(|x| Position { x, y: 0 })
+            .collect::<Vec<_>>();
+        let pattern = Pattern {
+            width: 200,
+            height: 1,
+            alive_cells,
+            ..Default::default()
+        };
+
+        let rle = write(&pattern);
+
+        assert!(rle.lines().count() > 2);
+        assert!(rle.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(pattern, parse(&rle).unwrap());
+    }
+
+    #[test]
+    fn round_trips_glider() {
+        let pattern = parse(GLIDER).unwrap();
+        assert_eq!(pattern, parse(&write(&pattern)).unwrap());
+    }
+
+    fn wireworld_cells() -> Vec<Change> {
+        vec![
+            Change {
+                position: Position { x: 0, y: 0 },
+                state: 2,
+            },
+            Change {
+                position: Position { x: 2, y: 0 },
+                state: 3,
+            },
+            Change {
+                position: Position { x: 3, y: 0 },
+                state: 3,
+            },
+            Change {
+                position: Position { x: 1, y: 1 },
+                state: 25,
+            },
+            Change {
+                position: Position { x: 2, y: 1 },
+                state: 255,
+            },
+        ]
+    }
+
+    #[test]
+    fn parses_multi_state_cells() {
+        let pattern = parse("x = 4, y = 2\nBA2C$.pAyOA!").unwrap();
+        assert_eq!(
+            vec![Position { x: 1, y: 0 }, Position { x: 3, y: 1 }],
+            pattern.alive_cells
+        );
+        assert_eq!(wireworld_cells(), pattern.multi_state_cells);
+    }
+
+    #[test]
+    fn fails_on_state_beyond_255() {
+        let error = parse("x = 1, y = 1\nyP!").unwrap_err();
+        let expected = ParseError::new(2, 2, ParseErrorKind::UnexpectedCharacter('P'));
+        assert_eq!(expected, error);
+    }
+
+    #[test]
+    fn writes_multi_state_cells() {
+        let pattern = Pattern {
+            width: 4,
+            height: 2,
+            alive_cells: vec![Position { x: 1, y: 0 }],
+            multi_state_cells: wireworld_cells(),
+            ..Default::default()
+        };
+        let rle = write(&pattern);
+        assert_eq!("x = 4, y = 2\nBA2C$.pAyO!\n", rle);
+        assert_eq!(pattern, parse(&rle).unwrap());
+    }
+}
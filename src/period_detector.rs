@@ -0,0 +1,401 @@
+use crate::generation_calculator::Change;
+use crate::grid::{Position, DEAD};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Periodicity {
+    StillLife,
+    Oscillator { period: u32 },
+    Spaceship { dx: i64, dy: i64, period: u32 },
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Stabilisation {
+    pub generation: u64,
+    pub periodicity: Periodicity,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Sighting {
+    generation: u64,
+    origin: Option<Position>,
+    shape: Vec<Change>,
+}
+
+// Remembers every generation's cells and states relative to their bounding box, looked up
+// by their hash, so a repeated shape reveals the period and a moved bounding box the displacement.
+// Shapes whose sightings lie more than `max_period` generations apart are not detected.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PeriodDetector {
+    max_period: u32,
+    cells: HashMap<Position, u8>,
+    sightings: HashMap<u64, Sighting>,
+    recorded_hashes: VecDeque<(u64, u64)>,
+    stabilisation: Option<Stabilisation>,
+}
+
+impl PeriodDetector {
+    pub fn new(max_period: u32, cells: &[Change]) -> Self {
+        PeriodDetector {
+            max_period: max_period.max(1),
+            cells: cells
+                .iter()
+                .map(|cell| (cell.position, cell.state))
+                .collect(),
+            sightings: HashMap::new(),
+            recorded_hashes: VecDeque::new(),
+            stabilisation: None,
+        }
+    }
+
+    pub fn apply(&mut self, changes: &[Change]) {
+        for change in changes {
+            if change.state == DEAD {
+                self.cells.remove(&change.position);
+            } else {
+                self.cells.insert(change.position, change.state);
+            }
+        }
+    }
+
+    // Forgets everything seen so far, e.g. after the cells were edited by hand
+    pub fn restart(&mut self, generation: u64) {
+        self.sightings.clear();
+        self.recorded_hashes.clear();
+        self.stabilisation = None;
+        self.record(generation);
+    }
+
+    pub fn record(&mut self, generation: u64) {
+        if self.stabilisation.is_some() {
+            return;
+        }
+        self.forget_before(generation.saturating_sub(u64::from(self.max_period)));
+        let (shape, origin) = self.shape();
+        let hash = hash(&shape);
+        if let Some(sighting) = self.sightings.get(&hash) {
+            // Shapes that merely share the hash replace the earlier sighting
+            if sighting.generation < generation && sighting.shape == shape {
+                self.stabilisation = Some(Stabilisation {
+                    generation: sighting.generation,
+                    // Older sightings are forgotten, so the period fits max_period
+                    periodicity: periodicity(
+                        (generation - sighting.generation) as u32,
+                        sighting.origin,
+                        origin,
+                    ),
+                });
+                return;
+            }
+        }
+        self.sightings.insert(
+            hash,
+            Sighting {
+                generation,
+                origin,
+                shape,
+            },
+        );
+        self.recorded_hashes.push_back((generation, hash));
+    }
+
+    pub fn stabilisation(&self) -> Option<Stabilisation> {
+        self.stabilisation
+    }
+
+    fn forget_before(&mut self, generation: u64) {
+        while let Some(&(recorded_generation, hash)) = self.recorded_hashes.front() {
+            if recorded_generation >= generation {
+                break;
+            }
+            self.recorded_hashes.pop_front();
+            let is_latest_sighting = self
+                .sightings
+                .get(&hash)
+                .is_some_and(|sighting| sighting.generation == recorded_generation);
+            if is_latest_sighting {
+                self.sightings.remove(&hash);
+            }
+        }
+    }
+
+    // The cells relative to the top left corner of their bounding box, row by row
+    fn shape(&self) -> (Vec<Change>, Option<Position>) {
+        let min_x = self.cells.keys().map(|position| position.x).min();
+        let min_y = self.cells.keys().map(|position| position.y).min();
+        let origin = match (min_x, min_y) {
+            (Some(x), Some(y)) => Position { x, y },
+            _ => return (Vec::new(), None),
+        };
+        let mut shape = self
+            .cells
+            .iter()
+            .map(|(position, &state)| Change {
+                position: Position {
+                    x: position.x - origin.x,
+                    y: position.y - origin.y,
+                },
+                state,
+            })
+            .collect::<Vec<_>>();
+        shape.sort_by_key(|cell| (cell.position.y, cell.position.x));
+        (shape, Some(origin))
+    }
+}
+
+fn hash(shape: &[Change]) -> u64 {
+    shape.iter().fold(shape.len() as u64, |hash, cell| {
+        let x = u64::from(cell.position.x);
+        let y = u64::from(cell.position.y);
+        mix(hash ^ mix(x << 32 | y) ^ u64::from(cell.state))
+    })
+}
+
+fn periodicity(
+    period: u32,
+    previous_origin: Option<Position>,
+    origin: Option<Position>,
+) -> Periodicity {
+    let (dx, dy) = match (previous_origin, origin) {
+        (Some(previous), Some(current)) => (
+            i64::from(current.x) - i64::from(previous.x),
+            i64::from(current.y) - i64::from(previous.y),
+        ),
+        _ => (0, 0),
+    };
+    if dx != 0 || dy != 0 {
+        Periodicity::Spaceship { dx, dy, period }
+    } else if period == 1 {
+        Periodicity::StillLife
+    } else {
+        Periodicity::Oscillator { period }
+    }
+}
+
+// Finalizer of splitmix64, spreads neighbouring coordinates over the whole range
+fn mix(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+impl Periodicity {
+    pub fn period(&self) -> u32 {
+        match *self {
+            Periodicity::StillLife => 1,
+            Periodicity::Oscillator { period } | Periodicity::Spaceship { period, .. } => period,
+        }
+    }
+}
+
+impl fmt::Display for Periodicity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Periodicity::StillLife => write!(f, "still life"),
+            Periodicity::Oscillator { period } => write!(f, "oscillator of period {}", period),
+            Periodicity::Spaceship { dx, dy, period } => write!(
+                f,
+                "spaceship with displacement ({}, {}) every {} generations",
+                dx, dy, period
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Stabilisation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "stabilised at generation {}, period {}",
+            self.generation,
+            self.periodicity.period()
+        )?;
+        if let Periodicity::Spaceship { dx, dy, .. } = self.periodicity {
+            write!(f, ", moving ({}, {})", dx, dy)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::generation_calculator::{GenerationCalculator, GenerationCalculatorImpl};
+    use crate::grid::{cell_states, Grid, GridImpl, ALIVE};
+    use crate::rule::Rule;
+
+    fn run(grid: &mut GridImpl, generations: u32) -> Option<Stabilisation> {
+        let calculator = GenerationCalculatorImpl::new(Rule::default());
+        let mut detector = PeriodDetector::new(100, &cell_states(grid));
+        detector.restart(0);
+        for generation in 1..=u64::from(generations) {
+            let changes = calculator.next_generation(grid);
+            for change in &changes {
+                if change.is_alive() {
+                    grid.set_alive_at(change.position);
+                } else {
+                    grid.set_dead_at(change.position);
+                }
+            }
+            detector.apply(&changes);
+            detector.record(generation);
+        }
+        detector.stabilisation()
+    }
+
+    fn grid_with(cells: &[(u32, u32)]) -> GridImpl {
+        let mut grid = GridImpl::new(20, 20);
+        for &(x, y) in cells {
+            grid.set_alive_at(Position { x, y });
+        }
+        grid
+    }
+
+    #[test]
+    fn detects_still_life() {
+        let mut grid = grid_with(&[(5, 5), (6, 5), (5, 6), (6, 6)]);
+        let expected = Stabilisation {
+            generation: 0,
+            periodicity: Periodicity::StillLife,
+        };
+        assert_eq!(Some(expected), run(&mut grid, 3));
+    }
+
+    #[test]
+    fn detects_oscillator() {
+        let mut grid = grid_with(&[(4, 5), (5, 5), (6, 5)]);
+        let expected = Stabilisation {
+            generation: 0,
+            periodicity: Periodicity::Oscillator { period: 2 },
+        };
+        assert_eq!(Some(expected), run(&mut grid, 3));
+    }
+
+    #[test]
+    fn detects_spaceship() {
+        let mut grid = grid_with(&[(2, 1), (3, 2), (1, 3), (2, 3), (3, 3)]);
+        let expected = Stabilisation {
+            generation: 0,
+            periodicity: Periodicity::Spaceship {
+                dx: 1,
+                dy: 1,
+                period: 4,
+            },
+        };
+        assert_eq!(Some(expected), run(&mut grid, 5));
+    }
+
+    #[test]
+    fn reports_generation_at_which_board_settled() {
+        // A pre-block turns into a block after one generation
+        let mut grid = grid_with(&[(5, 5), (6, 5), (5, 6)]);
+        let expected = Stabilisation {
+            generation: 1,
+            periodicity: Periodicity::StillLife,
+        };
+        assert_eq!(Some(expected), run(&mut grid, 3));
+    }
+
+    #[test]
+    fn does_not_report_unsettled_board() {
+        let mut grid = grid_with(&[(4, 5), (5, 5), (6, 5)]);
+        assert_eq!(None, run(&mut grid, 1));
+    }
+
+    #[test]
+    fn restarting_forgets_earlier_generations() {
+        let mut detector = PeriodDetector::new(100, &[]);
+        detector.restart(0);
+        detector.record(1);
+        assert!(detector.stabilisation().is_some());
+
+        detector.apply(&[Change {
+            position: Position { x: 1, y: 1 },
+            state: ALIVE,
+        }]);
+        detector.restart(1);
+
+        assert_eq!(None, detector.stabilisation());
+    }
+
+    #[test]
+    fn tells_states_of_cells_apart() {
+        let position = Position { x: 3, y: 3 };
+        let mut detector = PeriodDetector::new(100, &[Change { position, state: 2 }]);
+        detector.restart(0);
+
+        detector.apply(&[Change { position, state: 3 }]);
+        detector.record(1);
+
+        assert_eq!(None, detector.stabilisation());
+    }
+
+    #[test]
+    fn ignores_other_shape_with_same_hash() {
+        let position = Position { x: 1, y: 1 };
+        let mut detector = PeriodDetector::new(
+            100,
+            &[Change {
+                position,
+                state: ALIVE,
+            }],
+        );
+        detector.restart(0);
+        // Pretends the earlier generation was a different shape that collided
+        for sighting in detector.sightings.values_mut() {
+            sighting.shape[0].state = 2;
+        }
+
+        detector.record(1);
+
+        assert_eq!(None, detector.stabilisation());
+    }
+
+    #[test]
+    fn ignores_periods_longer_than_maximum() {
+        let mut detector = PeriodDetector::new(2, &[]);
+        detector.restart(0);
+        let cell = |x, is_alive| Change {
+            position: Position { x, y: 0 },
+            state: if is_alive { ALIVE } else { DEAD },
+        };
+        detector.apply(&[cell(1, true)]);
+        detector.record(1);
+        detector.apply(&[cell(1, false), cell(5, true), cell(6, true)]);
+        detector.record(2);
+        detector.apply(&[cell(5, false), cell(6, false)]);
+        detector.record(3);
+
+        assert_eq!(None, detector.stabilisation());
+    }
+
+    #[test]
+    fn displays_stabilisation() {
+        let stabilisation = Stabilisation {
+            generation: 1103,
+            periodicity: Periodicity::Oscillator { period: 2 },
+        };
+        assert_eq!(
+            "stabilised at generation 1103, period 2",
+            stabilisation.to_string()
+        );
+        let spaceship = Stabilisation {
+            generation: 4,
+            periodicity: Periodicity::Spaceship {
+                dx: -1,
+                dy: 1,
+                period: 4,
+            },
+        };
+        assert_eq!(
+            "stabilised at generation 4, period 4, moving (-1, 1)",
+            spaceship.to_string()
+        );
+        assert_eq!(
+            "spaceship with displacement (-1, 1) every 4 generations",
+            spaceship.periodicity.to_string()
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+use std::str::FromStr;
+
+const MAX_NEIGHBOURS: usize = 8;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Rule {
+    birth: [bool; MAX_NEIGHBOURS + 1],
+    survival: [bool; MAX_NEIGHBOURS + 1],
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RuleParseError {
+    MissingSeparator,
+    TooManySeparators,
+    InvalidNeighbourCount(char),
+    DuplicateNeighbourCount(u32),
+    MixedNotation,
+    DuplicatePart(char),
+    NeighbourCountOutOfRange(u32),
+}
+
+impl Rule {
+    // Neighbour counts range from 0 to 8
+    pub fn new(birth: &[u32], survival: &[u32]) -> Result<Self, RuleParseError> {
+        let mut rule = Rule {
+            birth: [false; MAX_NEIGHBOURS + 1],
+            survival: [false; MAX_NEIGHBOURS + 1],
+        };
+        set_neighbour_counts(birth, &mut rule.birth)?;
+        set_neighbour_counts(survival, &mut rule.survival)?;
+        Ok(rule)
+    }
+
+    pub fn conway() -> Self {
+        Rule::new(&[3], &[2, 3]).expect("B3/S23 only has valid neighbour counts")
+    }
+
+    pub fn is_born(&self, neighbours: u32) -> bool {
+        self.birth
+            .get(neighbours as usize)
+            .cloned()
+            .unwrap_or(false)
+    }
+
+    pub fn survives(&self, neighbours: u32) -> bool {
+        self.survival
+            .get(neighbours as usize)
+            .cloned()
+            .unwrap_or(false)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::conway()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleParseError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let (birth, survival) = split_parts(rule)?;
+        let mut rule = Rule::new(&[], &[])?;
+        parse_neighbour_counts(birth, &mut rule.birth)?;
+        parse_neighbour_counts(survival, &mut rule.survival)?;
+        Ok(rule)
+    }
+}
+
+// Returns the birth and survival parts of a rule in either notation
+pub fn split_parts(rule: &str) -> Result<(&str, &str), RuleParseError> {
+    let parts = rule.trim().split('/').collect::<Vec<_>>();
+    if parts.len() < 2 {
+        return Err(RuleParseError::MissingSeparator);
+    }
+    if parts.len() > 2 {
+        return Err(RuleParseError::TooManySeparators);
+    }
+
+    let first = split_prefix(parts[0]);
+    let second = split_prefix(parts[1]);
+    match (first, second) {
+        // Legacy notation without prefixes lists survival first, e.g. "23/3"
+        ((None, survival), (None, birth)) => Ok((birth, survival)),
+        ((Some(first_prefix), first), (Some(second_prefix), second)) => {
+            if first_prefix == second_prefix {
+                Err(RuleParseError::DuplicatePart(second_prefix))
+            } else if first_prefix == 'B' {
+                Ok((first, second))
+            } else {
+                Ok((second, first))
+            }
+        }
+        _ => Err(RuleParseError::MixedNotation),
+    }
+}
+
+fn split_prefix(part: &str) -> (Option<char>, &str) {
+    match part.chars().next() {
+        Some(prefix) if prefix == 'B' || prefix == 'b' => (Some('B'), &part[1..]),
+        Some(prefix) if prefix == 'S' || prefix == 's' => (Some('S'), &part[1..]),
+        _ => (None, part),
+    }
+}
+
+fn parse_neighbour_counts(
+    counts: &str,
+    target: &mut [bool; MAX_NEIGHBOURS + 1],
+) -> Result<(), RuleParseError> {
+    for character in counts.chars() {
+        let neighbours = character
+            .to_digit(10)
+            .filter(|&neighbours| neighbours as usize <= MAX_NEIGHBOURS)
+            .ok_or(RuleParseError::InvalidNeighbourCount(character))?;
+        if target[neighbours as usize] {
+            return Err(RuleParseError::DuplicateNeighbourCount(neighbours));
+        }
+        target[neighbours as usize] = true;
+    }
+    Ok(())
+}
+
+fn set_neighbour_counts(
+    counts: &[u32],
+    target: &mut [bool; MAX_NEIGHBOURS + 1],
+) -> Result<(), RuleParseError> {
+    for &neighbours in counts {
+        let set = target
+            .get_mut(neighbours as usize)
+            .ok_or(RuleParseError::NeighbourCountOutOfRange(neighbours))?;
+        *set = true;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        for (neighbours, _) in self.birth.iter().enumerate().filter(|(_, &set)| set) {
+            write!(f, "{}", neighbours)?;
+        }
+        write!(f, "/S")?;
+        for (neighbours, _) in self.survival.iter().enumerate().filter(|(_, &set)| set) {
+            write!(f, "{}", neighbours)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleParseError::MissingSeparator => {
+                write!(f, "expected a '/' between birth and survival")
+            }
+            RuleParseError::TooManySeparators => write!(f, "expected exactly one '/'"),
+            RuleParseError::InvalidNeighbourCount(character) => {
+                write!(
+                    f,
+                    "'{}' is not a neighbour count between 0 and 8",
+                    character
+                )
+            }
+            RuleParseError::DuplicateNeighbourCount(neighbours) => {
+                write!(f, "neighbour count {} is listed twice", neighbours)
+            }
+            RuleParseError::MixedNotation => {
+                write!(f, "either both or neither part may have a B/S prefix")
+            }
+            RuleParseError::DuplicatePart(prefix) => write!(f, "'{}' part is listed twice", prefix),
+            RuleParseError::NeighbourCountOutOfRange(neighbours) => {
+                write!(f, "neighbour count {} is not between 0 and 8", neighbours)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_is_conway() {
+        let rule = Rule::default();
+        assert_eq!(Rule::new(&[3], &[2, 3]).unwrap(), rule);
+    }
+
+    #[test]
+    fn conway_rule_matches_b3_s23() {
+        let rule = Rule::conway();
+        for neighbours in 0..=8 {
+            assert_eq!(neighbours == 3, rule.is_born(neighbours));
+            assert_eq!(
+                neighbours == 2 || neighbours == 3,
+                rule.survives(neighbours)
+            );
+        }
+    }
+
+    #[test]
+    fn parses_prefixed_notation() {
+        let rule: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(Rule::new(&[3, 6], &[2, 3]).unwrap(), rule);
+    }
+
+    #[test]
+    fn parses_prefixed_notation_in_any_order_and_case() {
+        let rule: Rule = "s23/b36".parse().unwrap();
+        assert_eq!(Rule::new(&[3, 6], &[2, 3]).unwrap(), rule);
+    }
+
+    #[test]
+    fn parses_legacy_survival_birth_notation() {
+        let rule: Rule = "23/3".parse().unwrap();
+        assert_eq!(Rule::conway(), rule);
+    }
+
+    #[test]
+    fn parses_every_neighbour_count() {
+        let rule: Rule = "B3/S012345678".parse().unwrap();
+        assert_eq!(Rule::new(&[3], &[0, 1, 2, 3, 4, 5, 6, 7, 8]).unwrap(), rule);
+    }
+
+    #[test]
+    fn parses_empty_parts() {
+        let seeds: Rule = "B2/S".parse().unwrap();
+        assert_eq!(Rule::new(&[2], &[]).unwrap(), seeds);
+        let seeds_legacy: Rule = "/2".parse().unwrap();
+        assert_eq!(seeds, seeds_legacy);
+    }
+
+    #[test]
+    fn fails_without_separator() {
+        assert_eq!(
+            Err(RuleParseError::MissingSeparator),
+            "B3S23".parse::<Rule>()
+        );
+    }
+
+    #[test]
+    fn fails_with_too_many_separators() {
+        assert_eq!(
+            Err(RuleParseError::TooManySeparators),
+            "B3/S23/2".parse::<Rule>()
+        );
+    }
+
+    #[test]
+    fn fails_on_out_of_range_neighbour_count() {
+        assert_eq!(
+            Err(RuleParseError::InvalidNeighbourCount('9')),
+            "B39/S23".parse::<Rule>()
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_neighbour_count_in_constructor() {
+        assert_eq!(
+            Err(RuleParseError::NeighbourCountOutOfRange(9)),
+            Rule::new(&[3], &[2, 9])
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_character() {
+        assert_eq!(
+            Err(RuleParseError::InvalidNeighbourCount('x')),
+            "B3/S2x".parse::<Rule>()
+        );
+    }
+
+    #[test]
+    fn fails_on_duplicate_neighbour_count() {
+        assert_eq!(
+            Err(RuleParseError::DuplicateNeighbourCount(3)),
+            "B33/S23".parse::<Rule>()
+        );
+    }
+
+    #[test]
+    fn fails_on_mixed_notation() {
+        assert_eq!(Err(RuleParseError::MixedNotation), "B3/23".parse::<Rule>());
+    }
+
+    #[test]
+    fn fails_on_duplicate_part() {
+        assert_eq!(
+            Err(RuleParseError::DuplicatePart('B')),
+            "B3/B23".parse::<Rule>()
+        );
+    }
+
+    #[test]
+    fn displays_prefixed_notation() {
+        let rule: Rule = "23/36".parse().unwrap();
+        assert_eq!("B36/S23", rule.to_string());
+    }
+}
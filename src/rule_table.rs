@@ -0,0 +1,811 @@
+use crate::generation_calculator::{changes_by_cell, Change, GenerationCalculator};
+use crate::grid::{Grid, DEAD};
+use crate::neighbourhood::Neighbourhood;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+pub type Colour = (u8, u8, u8);
+
+pub const WIREWORLD: &str = "\
+@RULE WireWorld
+
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={a}
+var c={a}
+var d={a}
+var e={a}
+var f={a}
+var g={a}
+var h={a}
+var o={0,2,3}
+var p={o}
+var q={o}
+var r={o}
+var s={o}
+var t={o}
+var u={o}
+# Electron heads turn into tails, which turn back into conductors
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+# Conductors next to one or two electron heads become heads themselves
+3,1,o,p,q,r,s,t,u,1
+3,1,1,o,p,q,r,s,t,1
+
+@COLORS
+1 0 128 255
+2 255 255 255
+3 255 128 0
+";
+
+// A cellular automaton with any number of states, read from the @TABLE and @COLORS
+// sections of a Golly .rule file. Cells change according to the first transition
+// matching them and their neighbours, or keep their state if there is none.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RuleTable {
+    name: String,
+    states: u8,
+    neighbourhood: Neighbourhood,
+    symmetries: Symmetries,
+    transitions: Vec<Transition>,
+    colours: Vec<(u8, Colour)>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RuleTableParseError {
+    pub line: usize,
+    pub kind: RuleTableParseErrorKind,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RuleTableParseErrorKind {
+    MissingTable,
+    MissingStateCount,
+    InvalidStateCount(String),
+    UnknownNeighbourhood(String),
+    UnknownSymmetries(String),
+    InvalidVariable(String),
+    UnknownVariable(String),
+    InvalidState(String),
+    WrongElementCount(usize),
+    UnboundOutput(String),
+    InvalidColour(String),
+}
+
+// The orders in which the neighbours of a transition are compared to the ones of a cell
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Symmetries {
+    Orders(Vec<Vec<usize>>),
+    Permute,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+enum Element {
+    State(u8),
+    Variable(usize),
+}
+
+// The centre and the neighbours in Golly's order, followed by the resulting state.
+// A variable used more than once has to stand for the same state everywhere.
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Transition {
+    inputs: Vec<Element>,
+    output: Element,
+    variables: Vec<Vec<u8>>,
+}
+
+impl RuleTable {
+    pub fn wireworld() -> Self {
+        WIREWORLD.parse().expect("invalid built-in WireWorld table")
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    pub fn colours(&self) -> &[(u8, Colour)] {
+        &self.colours
+    }
+
+    // The neighbours are expected in Golly's order, clockwise from the top
+    pub fn next_state(&self, centre: u8, neighbours: &[u8]) -> u8 {
+        self.transitions
+            .iter()
+            .filter_map(|transition| match self.symmetries {
+                Symmetries::Orders(ref orders) => orders
+                    .iter()
+                    .filter_map(|order| transition.apply(centre, neighbours, order))
+                    .next(),
+                Symmetries::Permute => transition.apply_permuted(centre, neighbours),
+            })
+            .next()
+            .unwrap_or(centre)
+    }
+}
+
+impl Transition {
+    fn apply(&self, centre: u8, neighbours: &[u8], order: &[usize]) -> Option<u8> {
+        let mut bindings = vec![None; self.variables.len()];
+        let matches = self.bind(&self.inputs[0], centre, &mut bindings)
+            && neighbours.iter().zip(order).all(|(&neighbour, &slot)| {
+                self.bind(&self.inputs[slot + 1], neighbour, &mut bindings)
+            });
+        if matches {
+            Some(self.output(&bindings))
+        } else {
+            None
+        }
+    }
+
+    fn apply_permuted(&self, centre: u8, neighbours: &[u8]) -> Option<u8> {
+        let mut bindings = vec![None; self.variables.len()];
+        let mut used_slots = vec![false; neighbours.len()];
+        if self.bind(&self.inputs[0], centre, &mut bindings)
+            && self.assign(neighbours, &mut used_slots, &mut bindings)
+        {
+            Some(self.output(&bindings))
+        } else {
+            None
+        }
+    }
+
+    // Looks for a slot for every neighbour, backtracking when the remaining ones don't fit
+    fn assign(
+        &self,
+        neighbours: &[u8],
+        used_slots: &mut [bool],
+        bindings: &mut Vec<Option<u8>>,
+    ) -> bool {
+        let (&neighbour, rest) = match neighbours.split_first() {
+            Some(split) => split,
+            None => return true,
+        };
+        for slot in 0..used_slots.len() {
+            if used_slots[slot] {
+                continue;
+            }
+            let mut attempt = bindings.clone();
+            if self.bind(&self.inputs[slot + 1], neighbour, &mut attempt) {
+                used_slots[slot] = true;
+                if self.assign(rest, used_slots, &mut attempt) {
+                    *bindings = attempt;
+                    return true;
+                }
+                used_slots[slot] = false;
+            }
+        }
+        false
+    }
+
+    fn bind(&self, element: &Element, state: u8, bindings: &mut [Option<u8>]) -> bool {
+        match *element {
+            Element::State(expected) => expected == state,
+            Element::Variable(index) => match bindings[index] {
+                Some(bound) => bound == state,
+                None if self.variables[index].contains(&state) => {
+                    bindings[index] = Some(state);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    fn output(&self, bindings: &[Option<u8>]) -> u8 {
+        match self.output {
+            Element::State(state) => state,
+            Element::Variable(index) => bindings[index].expect("output bound by the inputs"),
+        }
+    }
+}
+
+impl FromStr for RuleTable {
+    type Err = RuleTableParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::default();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let result = if line.starts_with('@') {
+                parser.start_section(line);
+                Ok(())
+            } else {
+                match parser.section.as_str() {
+                    "@TABLE" => parser.parse_table_line(line),
+                    "@COLORS" => parser.parse_colour(line),
+                    // Icons, names and the like only matter to Golly
+                    _ => Ok(()),
+                }
+            };
+            result.map_err(|kind| RuleTableParseError {
+                line: line_number,
+                kind,
+            })?;
+        }
+        parser.finish().map_err(|kind| RuleTableParseError {
+            line: text.lines().count(),
+            kind,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    section: String,
+    name: String,
+    has_table: bool,
+    states: Option<u8>,
+    neighbourhood: Option<Neighbourhood>,
+    symmetries: Option<String>,
+    variables: HashMap<String, Vec<u8>>,
+    transitions: Vec<Transition>,
+    colours: Vec<(u8, Colour)>,
+}
+
+impl Parser {
+    fn start_section(&mut self, line: &str) {
+        let mut words = line.splitn(2, char::is_whitespace);
+        self.section = words.next().unwrap_or("").to_string();
+        match self.section.as_str() {
+            "@RULE" => self.name = words.next().unwrap_or("").trim().to_string(),
+            "@TABLE" => self.has_table = true,
+            _ => {}
+        }
+    }
+
+    fn parse_table_line(&mut self, line: &str) -> Result<(), RuleTableParseErrorKind> {
+        if let Some(variable) = line.strip_prefix("var ") {
+            return self.parse_variable(variable);
+        }
+        if let Some(separator) = line.find(':') {
+            let value = line[separator + 1..].trim();
+            return match line[..separator].trim() {
+                "n_states" => {
+                    self.states = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|&states| states >= 2)
+                            .ok_or_else(|| {
+                                RuleTableParseErrorKind::InvalidStateCount(value.to_string())
+                            })?,
+                    );
+                    Ok(())
+                }
+                "neighborhood" => {
+                    self.neighbourhood = Some(match value {
+                        "Moore" => Neighbourhood::Moore,
+                        "vonNeumann" => Neighbourhood::VonNeumann,
+                        "hexagonal" => Neighbourhood::Hexagonal,
+                        _ => {
+                            return Err(RuleTableParseErrorKind::UnknownNeighbourhood(
+                                value.to_string(),
+                            ))
+                        }
+                    });
+                    Ok(())
+                }
+                "symmetries" => {
+                    self.symmetries = Some(value.to_string());
+                    Ok(())
+                }
+                key => Err(RuleTableParseErrorKind::InvalidVariable(key.to_string())),
+            };
+        }
+        self.parse_transition(line)
+    }
+
+    fn parse_variable(&mut self, declaration: &str) -> Result<(), RuleTableParseErrorKind> {
+        let invalid = || RuleTableParseErrorKind::InvalidVariable(declaration.trim().to_string());
+        let mut parts = declaration.splitn(2, '=');
+        let name = parts.next().unwrap_or("").trim();
+        let values = parts.next().ok_or_else(invalid)?.trim();
+        if name.is_empty() || !values.starts_with('{') {
+            return Err(invalid());
+        }
+        let values = self.parse_set(values)?;
+        self.variables.insert(name.to_string(), values);
+        Ok(())
+    }
+
+    // A set like "{0,a,2}", which may list the states of earlier variables
+    fn parse_set(&self, set: &str) -> Result<Vec<u8>, RuleTableParseErrorKind> {
+        if !set.ends_with('}') {
+            return Err(RuleTableParseErrorKind::InvalidVariable(set.to_string()));
+        }
+        let mut states = Vec::new();
+        for element in set[1..set.len() - 1].split(',').map(str::trim) {
+            match self.variables.get(element) {
+                Some(values) => states.extend(values),
+                None => states.push(self.parse_state(element)?),
+            }
+        }
+        Ok(states)
+    }
+
+    fn parse_state(&self, state: &str) -> Result<u8, RuleTableParseErrorKind> {
+        let states = self
+            .states
+            .ok_or(RuleTableParseErrorKind::MissingStateCount)?;
+        state
+            .parse::<u8>()
+            .ok()
+            .filter(|&parsed| parsed < states)
+            .ok_or_else(|| {
+                if state.chars().all(|character| character.is_ascii_digit()) {
+                    RuleTableParseErrorKind::InvalidState(state.to_string())
+                } else {
+                    RuleTableParseErrorKind::UnknownVariable(state.to_string())
+                }
+            })
+    }
+
+    fn parse_transition(&mut self, line: &str) -> Result<(), RuleTableParseErrorKind> {
+        let neighbours = self.neighbourhood.unwrap_or_default().size() as usize;
+        let tokens = split_transition(line);
+        if tokens.len() != neighbours + 2 {
+            return Err(RuleTableParseErrorKind::WrongElementCount(tokens.len()));
+        }
+
+        let mut transition = Transition {
+            inputs: Vec::new(),
+            output: Element::State(DEAD),
+            variables: Vec::new(),
+        };
+        let mut bound_names: HashMap<&str, usize> = HashMap::new();
+        let (output, inputs) = tokens.split_last().expect("at least two elements");
+        for &token in inputs {
+            let element = if token.starts_with('{') {
+                transition.variables.push(self.parse_set(token)?);
+                Element::Variable(transition.variables.len() - 1)
+            } else if let Some(&index) = bound_names.get(token) {
+                Element::Variable(index)
+            } else if let Some(values) = self.variables.get(token) {
+                transition.variables.push(values.clone());
+                bound_names.insert(token, transition.variables.len() - 1);
+                Element::Variable(transition.variables.len() - 1)
+            } else {
+                Element::State(self.parse_state(token)?)
+            };
+            transition.inputs.push(element);
+        }
+        transition.output = match bound_names.get(output) {
+            Some(&index) => Element::Variable(index),
+            None if self.variables.contains_key(*output) || output.starts_with('{') => {
+                return Err(RuleTableParseErrorKind::UnboundOutput(output.to_string()))
+            }
+            None => Element::State(self.parse_state(output)?),
+        };
+        self.transitions.push(transition);
+        Ok(())
+    }
+
+    // Either "state r g b" or "r g b r g b" for a gradient over all states but the dead one
+    fn parse_colour(&mut self, line: &str) -> Result<(), RuleTableParseErrorKind> {
+        let invalid = || RuleTableParseErrorKind::InvalidColour(line.to_string());
+        let numbers = line
+            .split(|character: char| character.is_whitespace() || character == ',')
+            .filter(|number| !number.is_empty())
+            .map(|number| number.parse::<u8>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        match numbers.len() {
+            4 => self.set_colour(numbers[0], (numbers[1], numbers[2], numbers[3])),
+            6 => {
+                let states = self.states.unwrap_or(2);
+                let steps = f64::from(states.saturating_sub(2).max(1));
+                let blend = |from: u8, to: u8, progress: f64| {
+                    (f64::from(from) + (f64::from(to) - f64::from(from)) * progress).round() as u8
+                };
+                for state in 1..states {
+                    let progress = f64::from(state - 1) / steps;
+                    let colour = (
+                        blend(numbers[0], numbers[3], progress),
+                        blend(numbers[1], numbers[4], progress),
+                        blend(numbers[2], numbers[5], progress),
+                    );
+                    self.set_colour(state, colour);
+                }
+            }
+            _ => return Err(invalid()),
+        }
+        Ok(())
+    }
+
+    fn set_colour(&mut self, state: u8, colour: Colour) {
+        self.colours.retain(|&(coloured, _)| coloured != state);
+        self.colours.push((state, colour));
+        self.colours.sort_by_key(|&(state, _)| state);
+    }
+
+    fn finish(self) -> Result<RuleTable, RuleTableParseErrorKind> {
+        if !self.has_table {
+            return Err(RuleTableParseErrorKind::MissingTable);
+        }
+        let states = self
+            .states
+            .ok_or(RuleTableParseErrorKind::MissingStateCount)?;
+        let neighbourhood = self.neighbourhood.unwrap_or_default();
+        let symmetries = self.symmetries.unwrap_or_else(|| "none".to_string());
+        let symmetries = parse_symmetries(&symmetries, neighbourhood.size() as usize)
+            .ok_or(RuleTableParseErrorKind::UnknownSymmetries(symmetries))?;
+        Ok(RuleTable {
+            name: self.name,
+            states,
+            neighbourhood,
+            symmetries,
+            transitions: self.transitions,
+            colours: self.colours,
+        })
+    }
+}
+
+// Elements are separated by commas, except for tables with fewer than
+// eleven states, which may also list them as single digits like "000012"
+fn split_transition(line: &str) -> Vec<&str> {
+    if !line.contains(',') {
+        return line
+            .char_indices()
+            .filter(|&(_, character)| !character.is_whitespace())
+            .map(|(index, character)| &line[index..index + character.len_utf8()])
+            .collect();
+    }
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, character) in line.char_indices() {
+        match character {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                tokens.push(line[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    tokens.push(line[start..].trim());
+    tokens
+}
+
+// Rotations turn the neighbours by a fraction of a full turn,
+// e.g. "rotate4" by quarter turns, and reflections also mirror every rotation
+fn parse_symmetries(symmetries: &str, neighbours: usize) -> Option<Symmetries> {
+    let identity = (0..neighbours).collect::<Vec<_>>();
+    let mirrored = |order: &[usize]| {
+        (0..neighbours)
+            .map(|index| order[(neighbours - index) % neighbours])
+            .collect::<Vec<_>>()
+    };
+    let orders = match symmetries {
+        "none" => vec![identity],
+        "permute" => return Some(Symmetries::Permute),
+        "reflect_horizontal" => vec![mirrored(&identity), identity],
+        _ if symmetries.starts_with("rotate") => {
+            let is_reflecting = symmetries.ends_with("reflect");
+            let end = symmetries.len() - if is_reflecting { 7 } else { 0 };
+            let rotations = symmetries[6..end].parse::<usize>().ok()?;
+            if rotations == 0 || !neighbours.is_multiple_of(rotations) {
+                return None;
+            }
+            let step = neighbours / rotations;
+            let mut orders = Vec::new();
+            for rotation in 0..rotations {
+                let order = (0..neighbours)
+                    .map(|index| (index + rotation * step) % neighbours)
+                    .collect::<Vec<_>>();
+                if is_reflecting {
+                    orders.push(mirrored(&order));
+                }
+                orders.push(order);
+            }
+            orders
+        }
+        _ => return None,
+    };
+    Some(Symmetries::Orders(orders))
+}
+
+impl fmt::Display for RuleTableParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            RuleTableParseErrorKind::MissingTable => write!(f, "expected a @TABLE section"),
+            RuleTableParseErrorKind::MissingStateCount => {
+                write!(f, "expected 'n_states' before the states are used")
+            }
+            RuleTableParseErrorKind::InvalidStateCount(states) => {
+                write!(f, "'{}' is not a state count between 2 and 255", states)
+            }
+            RuleTableParseErrorKind::UnknownNeighbourhood(neighbourhood) => {
+                write!(f, "'{}' is not a supported neighborhood", neighbourhood)
+            }
+            RuleTableParseErrorKind::UnknownSymmetries(symmetries) => {
+                write!(f, "'{}' are not symmetries of the neighborhood", symmetries)
+            }
+            RuleTableParseErrorKind::InvalidVariable(variable) => {
+                write!(f, "'{}' is not a valid declaration", variable)
+            }
+            RuleTableParseErrorKind::UnknownVariable(variable) => {
+                write!(f, "'{}' is not a declared variable", variable)
+            }
+            RuleTableParseErrorKind::InvalidState(state) => {
+                write!(f, "'{}' is not one of the table's states", state)
+            }
+            RuleTableParseErrorKind::WrongElementCount(elements) => write!(
+                f,
+                "{} elements don't fit the neighborhood of the transitions",
+                elements
+            ),
+            RuleTableParseErrorKind::UnboundOutput(output) => {
+                write!(f, "'{}' does not appear among the inputs", output)
+            }
+            RuleTableParseErrorKind::InvalidColour(colour) => {
+                write!(f, "'{}' is not a valid colour", colour)
+            }
+        }
+    }
+}
+
+pub struct RuleTableCalculator {
+    table: RuleTable,
+    // Most neighbourhoods come up again and again, so their results are kept
+    known_states: RefCell<HashMap<Vec<u8>, u8>>,
+}
+
+impl RuleTableCalculator {
+    pub fn new(table: RuleTable) -> Self {
+        RuleTableCalculator {
+            table,
+            known_states: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+// Golly lists hexagonal neighbours clockwise from the upper right one
+fn golly_offsets(neighbourhood: Neighbourhood, y: u32) -> Vec<(i64, i64)> {
+    let offsets = neighbourhood.offsets(y);
+    match neighbourhood {
+        Neighbourhood::Hexagonal => offsets[1..].iter().chain(&offsets[..1]).cloned().collect(),
+        _ => offsets.to_vec(),
+    }
+}
+
+impl GenerationCalculator for RuleTableCalculator {
+    fn next_generation(&self, grid: &dyn Grid) -> Vec<Change> {
+        let width = grid.width();
+        let height = grid.height();
+        let topology = grid.topology();
+        // The offsets of hexagonal neighbourhoods differ between even and odd rows
+        let offsets = [
+            golly_offsets(self.table.neighbourhood, 0),
+            golly_offsets(self.table.neighbourhood, 1),
+        ];
+        let mut known_states = self.known_states.borrow_mut();
+        changes_by_cell(grid, |position, state| {
+            let mut cells = vec![state];
+            let offsets = &offsets[(position.y % 2) as usize];
+            cells.extend(offsets.iter().map(|&(delta_x, delta_y)| {
+                topology
+                    .resolve(
+                        width,
+                        height,
+                        i64::from(position.x) + delta_x,
+                        i64::from(position.y) + delta_y,
+                    )
+                    .map_or(DEAD, |neighbour| grid.state_at(neighbour))
+            }));
+            match known_states.get(&cells) {
+                Some(&next_state) => next_state,
+                None => {
+                    let next_state = self.table.next_state(state, &cells[1..]);
+                    known_states.insert(cells, next_state);
+                    next_state
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::{cell_states, GridImpl, Position};
+    use crate::test_util::step;
+
+    const EMPTY: u8 = 0;
+    const HEAD: u8 = 1;
+    const TAIL: u8 = 2;
+    const CONDUCTOR: u8 = 3;
+
+    fn table(transitions: &str) -> RuleTable {
+        format!("@TABLE\nn_states:3\n{}", transitions)
+            .parse()
+            .unwrap()
+    }
+
+    fn row_after_generation(row: &[u8], calculator: &RuleTableCalculator) -> Vec<u8> {
+        let mut grid = GridImpl::new(row.len() as u32, 3);
+        for (x, &state) in row.iter().enumerate() {
+            grid.set_state_at(Position { x: x as u32, y: 1 }, state);
+        }
+        step(&mut grid, calculator);
+        (0..row.len())
+            .map(|x| grid.state_at(Position { x: x as u32, y: 1 }))
+            .collect()
+    }
+
+    #[test]
+    fn parses_wireworld() {
+        let wireworld = RuleTable::wireworld();
+        assert_eq!("WireWorld", wireworld.name());
+        assert_eq!(4, wireworld.states());
+        assert_eq!(Neighbourhood::Moore, wireworld.neighbourhood());
+        assert_eq!(
+            &[(1, (0, 128, 255)), (2, (255, 255, 255)), (3, (255, 128, 0))],
+            wireworld.colours()
+        );
+    }
+
+    #[test]
+    fn moves_electron_along_wire() {
+        let calculator = RuleTableCalculator::new(RuleTable::wireworld());
+        let wire = [CONDUCTOR, TAIL, HEAD, CONDUCTOR, CONDUCTOR];
+        let wire = row_after_generation(&wire, &calculator);
+        assert_eq!(vec![CONDUCTOR, CONDUCTOR, TAIL, HEAD, CONDUCTOR], wire);
+        let wire = row_after_generation(&wire, &calculator);
+        assert_eq!(vec![CONDUCTOR, CONDUCTOR, CONDUCTOR, TAIL, HEAD], wire);
+    }
+
+    #[test]
+    fn conductor_ignores_three_heads() {
+        let wireworld = RuleTable::wireworld();
+        let three_heads = [HEAD, HEAD, HEAD, EMPTY, EMPTY, EMPTY, EMPTY, EMPTY];
+        assert_eq!(CONDUCTOR, wireworld.next_state(CONDUCTOR, &three_heads));
+        let two_heads = [EMPTY, EMPTY, HEAD, EMPTY, CONDUCTOR, EMPTY, EMPTY, HEAD];
+        assert_eq!(HEAD, wireworld.next_state(CONDUCTOR, &two_heads));
+        assert_eq!(EMPTY, wireworld.next_state(EMPTY, &two_heads));
+    }
+
+    #[test]
+    fn matches_transitions_in_all_rotations() {
+        let rotated = table("symmetries:rotate4\n0,1,0,0,0,0,0,0,0,2");
+        assert_eq!(2, rotated.next_state(0, &[0, 0, 1, 0, 0, 0, 0, 0]));
+        assert_eq!(2, rotated.next_state(0, &[0, 0, 0, 0, 0, 0, 1, 0]));
+        // Quarter turns never move an edge onto a corner
+        assert_eq!(0, rotated.next_state(0, &[0, 1, 0, 0, 0, 0, 0, 0]));
+
+        let fixed = table("symmetries:none\n0,1,0,0,0,0,0,0,0,2");
+        assert_eq!(0, fixed.next_state(0, &[0, 0, 1, 0, 0, 0, 0, 0]));
+
+        let reflected = table("symmetries:reflect_horizontal\n0,0,1,0,0,0,0,0,0,2");
+        assert_eq!(2, reflected.next_state(0, &[0, 0, 0, 0, 0, 0, 0, 1]));
+        assert_eq!(0, reflected.next_state(0, &[0, 0, 0, 1, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn binds_variables_used_twice() {
+        let table = table(
+            "var a={1,2}\n\
+             var b={0,1,2}\n\
+             0,a,b,0,0,a,b,0,0,a\n\
+             0,{1,2},b,0,0,b,0,0,0,1",
+        );
+        assert_eq!(2, table.next_state(0, &[2, 1, 0, 0, 2, 1, 0, 0]));
+        assert_eq!(1, table.next_state(0, &[1, 2, 0, 0, 2, 0, 0, 0]));
+        // The upper right and lower left cells differ, as do the upper and lower right ones
+        assert_eq!(0, table.next_state(0, &[2, 1, 0, 0, 2, 0, 0, 0]));
+    }
+
+    #[test]
+    fn reads_compact_von_neumann_and_hexagonal_tables() {
+        let von_neumann: RuleTable = "@TABLE\nn_states:3\nneighborhood:vonNeumann\n\
+                                      symmetries:rotate4\n012002"
+            .parse()
+            .unwrap();
+        assert_eq!(2, von_neumann.next_state(0, &[0, 0, 1, 2]));
+        assert_eq!(0, von_neumann.next_state(0, &[1, 0, 0, 2]));
+
+        let hexagonal: RuleTable = "@TABLE\nn_states:2\nneighborhood:hexagonal\n\
+                                    symmetries:rotate6\n0,1,1,0,0,0,0,1"
+            .parse()
+            .unwrap();
+        assert_eq!(1, hexagonal.next_state(0, &[0, 0, 0, 0, 1, 1]));
+        assert_eq!(0, hexagonal.next_state(0, &[0, 1, 0, 0, 1, 0]));
+    }
+
+    #[test]
+    fn plays_hexagonal_tables_in_offset_rows() {
+        // Births below a cell to the north in Golly's hexagonal order, i.e. to the upper right
+        let table: RuleTable = "@TABLE\nn_states:2\nneighborhood:hexagonal\n\
+                                symmetries:none\n0,1,0,0,0,0,0,1"
+            .parse()
+            .unwrap();
+        let calculator = RuleTableCalculator::new(table);
+        let mut grid = GridImpl::new(4, 4);
+        grid.set_state_at(Position { x: 2, y: 1 }, 1);
+
+        let changes = calculator.next_generation(&grid);
+
+        let expected = vec![Change {
+            position: Position { x: 2, y: 2 },
+            state: 1,
+        }];
+        assert_eq!(expected, changes);
+        assert_eq!(1, cell_states(&grid).len());
+    }
+
+    #[test]
+    fn reads_colour_gradients() {
+        let table: RuleTable = "@TABLE\nn_states:4\n@COLORS\n0 0 0 200 100 0\n2 1 2 3"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            &[(1, (0, 0, 0)), (2, (1, 2, 3)), (3, (200, 100, 0))],
+            table.colours()
+        );
+    }
+
+    #[test]
+    fn fails_on_invalid_tables() {
+        let error = |text: &str| text.parse::<RuleTable>().unwrap_err();
+        assert_eq!(
+            RuleTableParseError {
+                line: 1,
+                kind: RuleTableParseErrorKind::MissingTable
+            },
+            error("@RULE Nothing")
+        );
+        assert_eq!(
+            RuleTableParseError {
+                line: 3,
+                kind: RuleTableParseErrorKind::UnknownVariable("x".to_string())
+            },
+            error("@TABLE\nn_states:2\n0,x,0,0,0,0,0,0,0,1")
+        );
+        assert_eq!(
+            RuleTableParseError {
+                line: 3,
+                kind: RuleTableParseErrorKind::InvalidState("2".to_string())
+            },
+            error("@TABLE\nn_states:2\n0,2,0,0,0,0,0,0,0,1")
+        );
+        assert_eq!(
+            RuleTableParseError {
+                line: 3,
+                kind: RuleTableParseErrorKind::WrongElementCount(3)
+            },
+            error("@TABLE\nn_states:2\n0,1,1")
+        );
+        assert_eq!(
+            RuleTableParseError {
+                line: 4,
+                kind: RuleTableParseErrorKind::UnboundOutput("a".to_string())
+            },
+            error("@TABLE\nn_states:2\nvar a={0,1}\n0,1,0,0,0,0,0,0,0,a")
+        );
+        assert_eq!(
+            RuleTableParseError {
+                line: 3,
+                kind: RuleTableParseErrorKind::UnknownSymmetries("rotate3".to_string())
+            },
+            error("@TABLE\nn_states:2\nsymmetries:rotate3")
+        );
+    }
+}
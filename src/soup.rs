@@ -0,0 +1,352 @@
+use crate::generation_calculator::Change;
+use crate::grid::{apply_changes, Grid, Position, ALIVE, DEAD};
+use std::fmt;
+use std::str::FromStr;
+
+// Number of distinct values produced by `Random::next_u32`
+const RANDOM_RANGE: f64 = 4_294_967_296.0;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Symmetry {
+    C1,
+    C2,
+    C4,
+    D2,
+    D4,
+    D8,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SymmetryParseError(String);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Soup {
+    pub seed: u64,
+    // Chance of a cell being alive, see `is_valid_density`
+    pub density: f64,
+    pub symmetry: Symmetry,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Region {
+    pub origin: Position,
+    pub width: u32,
+    pub height: u32,
+}
+
+// SplitMix64 only relies on wrapping integer arithmetic,
+// so a seed yields the same soup on every platform
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        ((value ^ (value >> 31)) >> 32) as u32
+    }
+}
+
+// Densities are probabilities, anything else is rejected where they are read in
+pub fn is_valid_density(density: f64) -> bool {
+    (0.0..=1.0).contains(&density)
+}
+
+impl Soup {
+    pub fn new(seed: u64, density: f64, symmetry: Symmetry) -> Self {
+        Soup {
+            seed,
+            density,
+            symmetry,
+        }
+    }
+
+    // Only cells of the symmetry's fundamental domain are drawn at random,
+    // the others copy the state of the cell they are mapped from.
+    // C4 and D8 need a square, so they use the largest one fitting into the given size.
+    pub fn alive_cells(&self, width: u32, height: u32) -> Vec<Position> {
+        let (width, height) = match self.symmetry {
+            Symmetry::C4 | Symmetry::D8 => (width.min(height), width.min(height)),
+            _ => (width, height),
+        };
+        let threshold = (self.density * RANDOM_RANGE) as u64;
+        let mut random = Random::new(self.seed);
+        let mut is_alive = vec![false; (width * height) as usize];
+        let mut alive_cells = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let index = (y * width + x) as usize;
+                let source = self
+                    .symmetry
+                    .images(x, y, width, height)
+                    .into_iter()
+                    .map(|(x, y)| (y * width + x) as usize)
+                    .min()
+                    .unwrap_or(index);
+                is_alive[index] = if source == index {
+                    u64::from(random.next_u32()) < threshold
+                } else {
+                    is_alive[source]
+                };
+                if is_alive[index] {
+                    alive_cells.push(Position { x, y });
+                }
+            }
+        }
+        alive_cells
+    }
+
+    // Cells of the region outside of the grid are left out
+    pub fn changes(&self, grid: &dyn Grid, region: Region) -> Vec<Change> {
+        let width = region
+            .width
+            .min(grid.width().saturating_sub(region.origin.x));
+        let height = region
+            .height
+            .min(grid.height().saturating_sub(region.origin.y));
+        let mut is_alive = vec![false; (width * height) as usize];
+        for position in self.alive_cells(width, height) {
+            is_alive[(position.y * width + position.x) as usize] = true;
+        }
+        let mut changes = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let position = Position {
+                    x: region.origin.x + x,
+                    y: region.origin.y + y,
+                };
+                let state = if is_alive[(y * width + x) as usize] {
+                    ALIVE
+                } else {
+                    DEAD
+                };
+                // Dying cells of Generations rules are replaced as well
+                if grid.state_at(position) != state {
+                    changes.push(Change { position, state });
+                }
+            }
+        }
+        changes
+    }
+
+    pub fn fill(&self, grid: &mut dyn Grid, region: Region) {
+        let changes = self.changes(grid, region);
+        apply_changes(grid, &changes);
+    }
+}
+
+impl Symmetry {
+    // Every cell the given one is mapped to, including itself
+    fn images(self, x: u32, y: u32, width: u32, height: u32) -> Vec<(u32, u32)> {
+        let (max_x, max_y) = (width - 1, height - 1);
+        match self {
+            Symmetry::C1 => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), (max_x - x, max_y - y)],
+            Symmetry::C4 => vec![
+                (x, y),
+                (max_y - y, x),
+                (max_x - x, max_y - y),
+                (y, max_x - x),
+            ],
+            Symmetry::D2 => vec![(x, y), (max_x - x, y)],
+            Symmetry::D4 => vec![
+                (x, y),
+                (max_x - x, y),
+                (x, max_y - y),
+                (max_x - x, max_y - y),
+            ],
+            Symmetry::D8 => vec![
+                (x, y),
+                (max_y - y, x),
+                (max_x - x, max_y - y),
+                (y, max_x - x),
+                (max_x - x, y),
+                (x, max_y - y),
+                (y, x),
+                (max_y - y, max_x - x),
+            ],
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = SymmetryParseError;
+
+    fn from_str(symmetry: &str) -> Result<Self, Self::Err> {
+        match symmetry.trim() {
+            "C1" => Ok(Symmetry::C1),
+            "C2" => Ok(Symmetry::C2),
+            "C4" => Ok(Symmetry::C4),
+            "D2" => Ok(Symmetry::D2),
+            "D4" => Ok(Symmetry::D4),
+            "D8" => Ok(Symmetry::D8),
+            other => Err(SymmetryParseError(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl fmt::Display for SymmetryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not one of the symmetries C1, C2, C4, D2, D4 or D8",
+            self.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::{alive_cells, GridImpl};
+    use std::collections::HashSet;
+
+    fn cell_set(soup: &Soup, width: u32, height: u32) -> HashSet<(u32, u32)> {
+        soup.alive_cells(width, height)
+            .into_iter()
+            .map(|position| (position.x, position.y))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_soup() {
+        let soup = Soup::new(42, 0.5, Symmetry::C1);
+        assert_eq!(soup.alive_cells(16, 16), soup.alive_cells(16, 16));
+        let other = Soup::new(43, 0.5, Symmetry::C1);
+        assert_ne!(soup.alive_cells(16, 16), other.alive_cells(16, 16));
+    }
+
+    #[test]
+    fn seed_reproduces_known_soup() {
+        // Guards against the generator silently changing between platforms or versions
+        let soup = Soup::new(1, 0.5, Symmetry::C1);
+        let expected = vec![Position { x: 3, y: 0 }, Position { x: 0, y: 1 }];
+        assert_eq!(expected, soup.alive_cells(4, 2));
+    }
+
+    #[test]
+    fn respects_density() {
+        assert!(Soup::new(7, 0.0, Symmetry::C1)
+            .alive_cells(32, 32)
+            .is_empty());
+        assert_eq!(
+            32 * 32,
+            Soup::new(7, 1.0, Symmetry::C1).alive_cells(32, 32).len()
+        );
+        let alive = Soup::new(7, 0.25, Symmetry::C1).alive_cells(64, 64).len();
+        assert!(alive > 800 && alive < 1250, "{} cells alive", alive);
+    }
+
+    #[test]
+    fn rejects_densities_that_are_not_probabilities() {
+        assert!(is_valid_density(0.0));
+        assert!(is_valid_density(0.5));
+        assert!(is_valid_density(1.0));
+        for &density in &[-0.1, 1.5, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(!is_valid_density(density), "{} accepted", density);
+        }
+    }
+
+    #[test]
+    fn generates_symmetric_soups() {
+        let symmetries = [
+            Symmetry::C2,
+            Symmetry::C4,
+            Symmetry::D2,
+            Symmetry::D4,
+            Symmetry::D8,
+        ];
+        for &symmetry in &symmetries {
+            let (width, height) = (16, 16);
+            let cells = cell_set(&Soup::new(3, 0.5, symmetry), width, height);
+            for &(x, y) in &cells {
+                for image in symmetry.images(x, y, width, height) {
+                    assert!(cells.contains(&image), "{} soup is not symmetric", symmetry);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn c4_uses_largest_square() {
+        let cells = Soup::new(3, 1.0, Symmetry::C4).alive_cells(10, 6);
+        assert_eq!(36, cells.len());
+        assert!(cells.iter().all(|position| position.x < 6));
+    }
+
+    #[test]
+    fn fills_region_of_grid() {
+        let mut grid = GridImpl::new(10, 10);
+        grid.set_alive_at(Position { x: 3, y: 3 });
+        grid.set_alive_at(Position { x: 9, y: 9 });
+        let soup = Soup::new(5, 0.0, Symmetry::C1);
+        let region = Region {
+            origin: Position { x: 2, y: 2 },
+            width: 4,
+            height: 4,
+        };
+
+        let changes = soup.changes(&grid, region);
+        soup.fill(&mut grid, region);
+
+        let expected_changes = vec![Change {
+            position: Position { x: 3, y: 3 },
+            state: DEAD,
+        }];
+        assert_eq!(expected_changes, changes);
+        assert_eq!(vec![Position { x: 9, y: 9 }], alive_cells(&grid));
+    }
+
+    #[test]
+    fn clears_dying_cells() {
+        let mut grid = GridImpl::new(10, 10);
+        let dying = Position { x: 4, y: 4 };
+        grid.set_state_at(dying, 2);
+        let soup = Soup::new(5, 0.0, Symmetry::C1);
+        let region = Region {
+            origin: Position { x: 2, y: 2 },
+            width: 4,
+            height: 4,
+        };
+
+        soup.fill(&mut grid, region);
+
+        assert_eq!(DEAD, grid.state_at(dying));
+    }
+
+    #[test]
+    fn clips_region_to_grid() {
+        let mut grid = GridImpl::new(10, 10);
+        let region = Region {
+            origin: Position { x: 8, y: 7 },
+            width: 5,
+            height: 5,
+        };
+
+        Soup::new(5, 1.0, Symmetry::C1).fill(&mut grid, region);
+
+        assert_eq!(6, alive_cells(&grid).len());
+    }
+
+    #[test]
+    fn parses_symmetry() {
+        assert_eq!(Ok(Symmetry::D8), "D8".parse());
+        assert_eq!(
+            Err(SymmetryParseError("C3".to_string())),
+            "C3".parse::<Symmetry>()
+        );
+    }
+}
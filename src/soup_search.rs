@@ -0,0 +1,636 @@
+use crate::active_region::ActiveRegionGenerationCalculator;
+use crate::bit_grid::{BitGenerationCalculator, BitGrid};
+use crate::census::census;
+use crate::generation_calculator::{GenerationCalculator, GenerationCalculatorImpl};
+use crate::grid::{apply_changes, Grid, GridImpl, Position};
+use crate::period_detector::{PeriodDetector, Periodicity, Stabilisation};
+use crate::rule::Rule;
+use crate::soup::{Region, Soup, Symmetry};
+use crate::topology::{GridSpec, Topology};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+const MAX_DETECTED_PERIOD: u32 = 1024;
+
+// Objects that show up in nearly every soup and are therefore not worth reporting
+const COMMON_OBJECTS: [&str; 12] = [
+    "block",
+    "blinker",
+    "beehive",
+    "loaf",
+    "boat",
+    "ship",
+    "tub",
+    "pond",
+    "glider",
+    "long boat",
+    "barge",
+    "beacon",
+];
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum CalculatorKind {
+    Naive,
+    ActiveRegion,
+    Bit,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum SearchError {
+    UnknownCalculator(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SearchSettings {
+    pub grid_spec: GridSpec,
+    pub soup_size: u32,
+    pub density: f64,
+    pub symmetry: Symmetry,
+    pub rule: Rule,
+    pub calculator: CalculatorKind,
+    pub max_generations: u32,
+    pub methuselah_lifespan: u32,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SoupResult {
+    pub seed: u64,
+    pub stabilisation: Option<Stabilisation>,
+    pub max_population: u32,
+    pub final_population: u32,
+    pub census: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SearchReport {
+    pub settings: SearchSettings,
+    pub results: Vec<SoupResult>,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        SearchSettings {
+            grid_spec: GridSpec {
+                topology: Topology::Bounded,
+                width: 64,
+                height: 64,
+            },
+            soup_size: 16,
+            density: 0.5,
+            symmetry: Symmetry::C1,
+            rule: Rule::default(),
+            calculator: CalculatorKind::Bit,
+            max_generations: 10_000,
+            methuselah_lifespan: 1000,
+        }
+    }
+}
+
+impl SearchSettings {
+    // The soup is placed in the middle of the board, leaving room for debris to spread out
+    fn soup_region(&self) -> Region {
+        let width = self.soup_size.min(self.grid_spec.width);
+        let height = self.soup_size.min(self.grid_spec.height);
+        Region {
+            origin: Position {
+                x: (self.grid_spec.width - width) / 2,
+                y: (self.grid_spec.height - height) / 2,
+            },
+            width,
+            height,
+        }
+    }
+
+    fn game_parts(&self) -> (Box<dyn Grid>, Box<dyn GenerationCalculator>) {
+        let GridSpec {
+            topology,
+            width,
+            height,
+        } = self.grid_spec;
+        let rule = self.rule.clone();
+        match self.calculator {
+            CalculatorKind::Bit => {
+                let grid = BitGrid::with_topology(width, height, topology);
+                let generation_calculator = BitGenerationCalculator::new(&grid, rule);
+                (Box::new(grid), Box::new(generation_calculator))
+            }
+            CalculatorKind::ActiveRegion => (
+                Box::new(GridImpl::with_topology(width, height, topology)),
+                Box::new(ActiveRegionGenerationCalculator::new(rule)),
+            ),
+            CalculatorKind::Naive => (
+                Box::new(GridImpl::with_topology(width, height, topology)),
+                Box::new(GenerationCalculatorImpl::new(rule)),
+            ),
+        }
+    }
+}
+
+// Runs a single soup until it settles or `max_generations` have passed
+pub fn run_soup(settings: &SearchSettings, seed: u64) -> SoupResult {
+    let (mut grid, generation_calculator) = settings.game_parts();
+    let soup = Soup::new(seed, settings.density, settings.symmetry);
+    let initial_changes = soup.changes(&*grid, settings.soup_region());
+    apply_changes(&mut *grid, &initial_changes);
+    generation_calculator.accept_changes(&initial_changes);
+
+    let mut population = initial_changes.len() as u32;
+    let mut max_population = population;
+    let mut period_detector = PeriodDetector::new(MAX_DETECTED_PERIOD, &initial_changes);
+    period_detector.restart(0);
+    for generation in 1..=settings.max_generations {
+        let changes = generation_calculator.next_generation(&*grid);
+        apply_changes(&mut *grid, &changes);
+        generation_calculator.accept_changes(&changes);
+        for change in &changes {
+            if change.is_alive() {
+                population += 1;
+            } else {
+                population -= 1;
+            }
+        }
+        max_population = max_population.max(population);
+        period_detector.apply(&changes);
+        period_detector.record(u64::from(generation));
+        if period_detector.stabilisation().is_some() {
+            break;
+        }
+    }
+
+    SoupResult {
+        seed,
+        stabilisation: period_detector.stabilisation(),
+        max_population,
+        final_population: population,
+        census: census(&*grid),
+    }
+}
+
+// Runs the soups with the seeds counting up from first_seed, which must not overflow
+pub fn search<F>(
+    settings: &SearchSettings,
+    first_seed: u64,
+    soups: u32,
+    mut on_result: F,
+) -> SearchReport
+where
+    F: FnMut(&SoupResult),
+{
+    let results = (0..u64::from(soups))
+        .map(|index| {
+            let result = run_soup(settings, first_seed + index);
+            on_result(&result);
+            result
+        })
+        .collect();
+    SearchReport {
+        settings: settings.clone(),
+        results,
+    }
+}
+
+impl SoupResult {
+    pub fn lifespan(&self) -> Option<u64> {
+        self.stabilisation
+            .map(|stabilisation| stabilisation.generation)
+    }
+
+    pub fn unusual_objects(&self) -> Vec<&str> {
+        self.census
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !COMMON_OBJECTS.contains(name))
+            .collect()
+    }
+}
+
+impl SearchReport {
+    // Soups that took long to settle or did not settle at all
+    pub fn methuselahs(&self) -> Vec<&SoupResult> {
+        let methuselah_lifespan = u64::from(self.settings.methuselah_lifespan);
+        self.results
+            .iter()
+            .filter(|result| {
+                result
+                    .lifespan()
+                    .is_none_or(|lifespan| lifespan >= methuselah_lifespan)
+            })
+            .collect()
+    }
+
+    pub fn unusual_objects(&self) -> BTreeMap<&str, Vec<u64>> {
+        let mut unusual_objects = BTreeMap::new();
+        for result in &self.results {
+            for name in result.unusual_objects() {
+                unusual_objects
+                    .entry(name)
+                    .or_insert_with(Vec::new)
+                    .push(result.seed);
+            }
+        }
+        unusual_objects
+    }
+
+    pub fn max_population(&self) -> Option<&SoupResult> {
+        self.results
+            .iter()
+            .max_by_key(|result| (result.max_population, Reverse(result.seed)))
+    }
+
+    pub fn total_census(&self) -> BTreeMap<&str, u32> {
+        let mut total_census = BTreeMap::new();
+        for result in &self.results {
+            for (name, count) in &result.census {
+                *total_census.entry(name.as_str()).or_insert(0) += count;
+            }
+        }
+        total_census
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "seed,stabilised_at,period,dx,dy,max_population,final_population,objects\n",
+        );
+        for result in &self.results {
+            let (stabilised_at, period, dx, dy) = match result.stabilisation {
+                Some(Stabilisation {
+                    generation,
+                    periodicity,
+                }) => {
+                    let (dx, dy) = displacement(periodicity);
+                    (
+                        generation.to_string(),
+                        periodicity.period().to_string(),
+                        dx.to_string(),
+                        dy.to_string(),
+                    )
+                }
+                None => Default::default(),
+            };
+            let objects = result
+                .census
+                .iter()
+                .map(|(name, count)| format!("{}:{}", name, count))
+                .collect::<Vec<_>>()
+                .join(";");
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                result.seed,
+                stabilised_at,
+                period,
+                dx,
+                dy,
+                result.max_population,
+                result.final_population,
+                objects
+            ));
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let settings = &self.settings;
+        let mut json = String::from("{\n");
+        json.push_str(&format!(
+            "  \"rule\": {},\n",
+            json_string(&settings.rule.to_string())
+        ));
+        json.push_str(&format!(
+            "  \"grid\": {},\n",
+            json_string(&settings.grid_spec.to_string())
+        ));
+        json.push_str(&format!("  \"soup_size\": {},\n", settings.soup_size));
+        json.push_str(&format!("  \"density\": {},\n", settings.density));
+        json.push_str(&format!(
+            "  \"symmetry\": {},\n",
+            json_string(&settings.symmetry.to_string())
+        ));
+        json.push_str(&format!(
+            "  \"calculator\": {},\n",
+            json_string(&settings.calculator.to_string())
+        ));
+        json.push_str(&format!("  \"soups\": {},\n", self.results.len()));
+
+        let methuselahs = self
+            .methuselahs()
+            .iter()
+            .map(|result| result.seed.to_string())
+            .collect::<Vec<_>>();
+        json.push_str(&format!(
+            "  \"methuselahs\": [{}],\n",
+            methuselahs.join(", ")
+        ));
+
+        let unusual_objects = self
+            .unusual_objects()
+            .iter()
+            .map(|(name, seeds)| {
+                let seeds = seeds.iter().map(u64::to_string).collect::<Vec<_>>();
+                format!("{}: [{}]", json_string(name), seeds.join(", "))
+            })
+            .collect::<Vec<_>>();
+        json.push_str(&format!(
+            "  \"unusual_objects\": {{{}}},\n",
+            unusual_objects.join(", ")
+        ));
+
+        match self.max_population() {
+            Some(result) => json.push_str(&format!(
+                "  \"max_population\": {{\"seed\": {}, \"population\": {}}},\n",
+                result.seed, result.max_population
+            )),
+            None => json.push_str("  \"max_population\": null,\n"),
+        }
+        json.push_str(&format!(
+            "  \"census\": {},\n",
+            json_counts(self.total_census().into_iter())
+        ));
+
+        let results = self
+            .results
+            .iter()
+            .map(|result| format!("    {}", result_to_json(result)))
+            .collect::<Vec<_>>();
+        json.push_str(&format!("  \"results\": [\n{}\n  ]\n", results.join(",\n")));
+        json.push_str("}\n");
+        json
+    }
+}
+
+fn result_to_json(result: &SoupResult) -> String {
+    let stabilisation = match result.stabilisation {
+        Some(Stabilisation {
+            generation,
+            periodicity,
+        }) => {
+            let (dx, dy) = displacement(periodicity);
+            format!(
+                "{{\"generation\": {}, \"period\": {}, \"dx\": {}, \"dy\": {}}}",
+                generation,
+                periodicity.period(),
+                dx,
+                dy
+            )
+        }
+        None => "null".to_string(),
+    };
+    format!(
+        "{{\"seed\": {}, \"stabilisation\": {}, \"max_population\": {}, \"final_population\": {}, \"objects\": {}}}",
+        result.seed,
+        stabilisation,
+        result.max_population,
+        result.final_population,
+        json_counts(
+            result
+                .census
+                .iter()
+                .map(|(name, &count)| (name.as_str(), count))
+        )
+    )
+}
+
+fn displacement(periodicity: Periodicity) -> (i64, i64) {
+    match periodicity {
+        Periodicity::Spaceship { dx, dy, .. } => (dx, dy),
+        _ => (0, 0),
+    }
+}
+
+fn json_counts<'a, I>(counts: I) -> String
+where
+    I: Iterator<Item = (&'a str, u32)>,
+{
+    let entries = counts
+        .map(|(name, count)| format!("{}: {}", json_string(name), count))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", entries.join(", "))
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            character if character.is_control() => {
+                json.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+    json
+}
+
+impl FromStr for CalculatorKind {
+    type Err = SearchError;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind.trim() {
+            "naive" => Ok(CalculatorKind::Naive),
+            "active" => Ok(CalculatorKind::ActiveRegion),
+            "bit" => Ok(CalculatorKind::Bit),
+            other => Err(SearchError::UnknownCalculator(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for CalculatorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CalculatorKind::Naive => "naive",
+            CalculatorKind::ActiveRegion => "active",
+            CalculatorKind::Bit => "bit",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchError::UnknownCalculator(name) => write!(
+                f,
+                "'{}' is not one of the calculators naive, active or bit",
+                name
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::topology::TwistedEdges;
+
+    fn small_settings(calculator: CalculatorKind) -> SearchSettings {
+        SearchSettings {
+            grid_spec: GridSpec {
+                topology: Topology::Bounded,
+                width: 32,
+                height: 32,
+            },
+            soup_size: 8,
+            calculator,
+            max_generations: 2000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn calculators_agree_on_soup() {
+        let expected = run_soup(&small_settings(CalculatorKind::Naive), 3);
+        for &calculator in &[CalculatorKind::ActiveRegion, CalculatorKind::Bit] {
+            assert_eq!(expected, run_soup(&small_settings(calculator), 3));
+        }
+    }
+
+    #[test]
+    fn runs_soup_until_it_settles() {
+        let settings = small_settings(CalculatorKind::Bit);
+        let result = run_soup(&settings, 11);
+
+        assert!(result.stabilisation.is_some());
+        assert!(result.max_population >= result.final_population);
+        let census_population = result.census.values().sum::<u32>();
+        assert!(census_population > 0 || result.final_population == 0);
+    }
+
+    #[test]
+    fn empty_soup_settles_immediately() {
+        let settings = SearchSettings {
+            density: 0.0,
+            ..small_settings(CalculatorKind::Bit)
+        };
+        let expected = SoupResult {
+            seed: 1,
+            stabilisation: Some(Stabilisation {
+                generation: 0,
+                periodicity: Periodicity::StillLife,
+            }),
+            max_population: 0,
+            final_population: 0,
+            census: BTreeMap::new(),
+        };
+        assert_eq!(expected, run_soup(&settings, 1));
+    }
+
+    #[test]
+    fn gives_up_after_max_generations() {
+        let settings = SearchSettings {
+            max_generations: 1,
+            ..small_settings(CalculatorKind::Bit)
+        };
+        let result = run_soup(&settings, 5);
+        assert_eq!(None, result.stabilisation);
+    }
+
+    #[test]
+    fn searches_consecutive_seeds() {
+        let settings = small_settings(CalculatorKind::Bit);
+        let mut seen = Vec::new();
+        let report = search(&settings, 100, 3, |result| seen.push(result.seed));
+
+        assert_eq!(vec![100, 101, 102], seen);
+        assert_eq!(3, report.results.len());
+    }
+
+    fn result(seed: u64, lifespan: Option<u64>, census: &[(&str, u32)]) -> SoupResult {
+        SoupResult {
+            seed,
+            stabilisation: lifespan.map(|generation| Stabilisation {
+                generation,
+                periodicity: Periodicity::Oscillator { period: 2 },
+            }),
+            max_population: seed as u32 * 10,
+            final_population: 7,
+            census: census
+                .iter()
+                .map(|&(name, count)| (name.to_string(), count))
+                .collect(),
+        }
+    }
+
+    fn report() -> SearchReport {
+        SearchReport {
+            settings: SearchSettings {
+                methuselah_lifespan: 500,
+                ..Default::default()
+            },
+            results: vec![
+                result(1, Some(120), &[("block", 2), ("blinker", 1)]),
+                result(2, Some(800), &[("block", 1), ("toad", 1)]),
+                result(3, None, &[("unidentified 9-cell object", 1)]),
+            ],
+        }
+    }
+
+    #[test]
+    fn reports_methuselahs_and_unusual_objects() {
+        let report = report();
+
+        let methuselahs = report
+            .methuselahs()
+            .iter()
+            .map(|result| result.seed)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![2, 3], methuselahs);
+        let mut expected = BTreeMap::new();
+        expected.insert("toad", vec![2]);
+        expected.insert("unidentified 9-cell object", vec![3]);
+        assert_eq!(expected, report.unusual_objects());
+        assert_eq!(Some(3), report.max_population().map(|result| result.seed));
+    }
+
+    #[test]
+    fn writes_csv() {
+        let expected = "seed,stabilised_at,period,dx,dy,max_population,final_population,objects
+1,120,2,0,0,10,7,blinker:1;block:2
+2,800,2,0,0,20,7,block:1;toad:1
+3,,,,,30,7,unidentified 9-cell object:1
+";
+        assert_eq!(expected, report().to_csv());
+    }
+
+    #[test]
+    fn writes_json() {
+        let json = report().to_json();
+
+        assert!(json.starts_with("{\n  \"rule\": \"B3/S23\",\n  \"grid\": \"P64,64\",\n"));
+        assert!(json.contains("  \"methuselahs\": [2, 3],\n"));
+        assert!(json.contains(
+            "  \"unusual_objects\": {\"toad\": [2], \"unidentified 9-cell object\": [3]},\n"
+        ));
+        assert!(json.contains("  \"max_population\": {\"seed\": 3, \"population\": 30},\n"));
+        assert!(json.contains("  \"census\": {\"blinker\": 1, \"block\": 3, \"toad\": 1, \"unidentified 9-cell object\": 1},\n"));
+        assert!(json.contains("    {\"seed\": 3, \"stabilisation\": null, \"max_population\": 30, \"final_population\": 7, \"objects\": {\"unidentified 9-cell object\": 1}}\n"));
+        assert!(json.ends_with("  ]\n}\n"));
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!("\"a \\\"b\\\" \\\\ c\\n\"", json_string("a \"b\" \\ c\n"));
+    }
+
+    #[test]
+    fn calculators_agree_on_twisted_topologies() {
+        for &topology in &[
+            Topology::KleinBottle(TwistedEdges::TopAndBottom),
+            Topology::CrossSurface,
+        ] {
+            let settings = |calculator| SearchSettings {
+                grid_spec: GridSpec {
+                    topology,
+                    width: 16,
+                    height: 16,
+                },
+                ..small_settings(calculator)
+            };
+            let expected = run_soup(&settings(CalculatorKind::Naive), 3);
+            assert_eq!(expected, run_soup(&settings(CalculatorKind::Bit), 3));
+        }
+    }
+}
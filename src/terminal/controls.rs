@@ -0,0 +1,78 @@
+use termion::event::Key;
+
+// Cells the board moves per scroll key press
+const SCROLL_STEP: i64 = 8;
+
+pub const HELP: &str =
+    "space start/stop  n step  b back  arrows/hjkl move  enter/t toggle  wasd scroll  +/- speed  u/r undo/redo  c clear  q quit";
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Command {
+    ToggleRunning,
+    Step,
+    StepBack,
+    SpeedUp,
+    SlowDown,
+    MoveCursor(i64, i64),
+    Scroll(i64, i64),
+    ToggleCell,
+    Undo,
+    Redo,
+    Clear,
+    Quit,
+}
+
+pub fn command_for_key(key: Key) -> Option<Command> {
+    let command = match key {
+        Key::Char(' ') => Command::ToggleRunning,
+        Key::Char('n') => Command::Step,
+        Key::Char('b') => Command::StepBack,
+        Key::Char('+') | Key::Char('=') => Command::SpeedUp,
+        Key::Char('-') => Command::SlowDown,
+        Key::Left | Key::Char('h') => Command::MoveCursor(-1, 0),
+        Key::Right | Key::Char('l') => Command::MoveCursor(1, 0),
+        Key::Up | Key::Char('k') => Command::MoveCursor(0, -1),
+        Key::Down | Key::Char('j') => Command::MoveCursor(0, 1),
+        Key::Char('a') => Command::Scroll(-SCROLL_STEP, 0),
+        Key::Char('d') => Command::Scroll(SCROLL_STEP, 0),
+        Key::Char('w') => Command::Scroll(0, -SCROLL_STEP),
+        Key::Char('s') => Command::Scroll(0, SCROLL_STEP),
+        Key::Char('\n') | Key::Char('t') => Command::ToggleCell,
+        Key::Char('u') => Command::Undo,
+        Key::Char('r') => Command::Redo,
+        Key::Char('c') => Command::Clear,
+        Key::Char('q') | Key::Esc | Key::Ctrl('c') => Command::Quit,
+        _ => return None,
+    };
+    Some(command)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maps_keys_to_commands() {
+        assert_eq!(
+            Some(Command::ToggleRunning),
+            command_for_key(Key::Char(' '))
+        );
+        assert_eq!(Some(Command::MoveCursor(0, -1)), command_for_key(Key::Up));
+        assert_eq!(
+            Some(Command::MoveCursor(0, -1)),
+            command_for_key(Key::Char('k'))
+        );
+        assert_eq!(
+            Some(Command::Scroll(SCROLL_STEP, 0)),
+            command_for_key(Key::Char('d'))
+        );
+        assert_eq!(Some(Command::ToggleCell), command_for_key(Key::Char('\n')));
+        assert_eq!(Some(Command::Quit), command_for_key(Key::Ctrl('c')));
+    }
+
+    #[test]
+    fn ignores_unbound_keys() {
+        assert_eq!(None, command_for_key(Key::Char('z')));
+        assert_eq!(None, command_for_key(Key::F(1)));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod controls;
+pub mod presenter;
+pub mod screen;
+pub mod view;
@@ -0,0 +1,83 @@
+use crate::generation_calculator::Change;
+use crate::grid::Position;
+use crate::interactive_game::Presenter;
+use crate::terminal::screen::Screen;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[cfg(test)]
+extern crate mockers;
+#[cfg(test)]
+use mockers_derive::mocked;
+
+#[cfg_attr(test, mocked)]
+pub trait TerminalView {
+    fn draw(&mut self, lines: &[String]);
+}
+
+// The screen is shared with the controls, which move the cursor and scroll around
+pub struct TerminalPresenter {
+    view: Box<dyn TerminalView>,
+    screen: Rc<RefCell<Screen>>,
+}
+
+impl TerminalPresenter {
+    pub fn new(view: Box<dyn TerminalView>, screen: Rc<RefCell<Screen>>) -> Self {
+        TerminalPresenter { view, screen }
+    }
+}
+
+impl Presenter for TerminalPresenter {
+    fn init_board(&mut self, alive_cells: &[Position]) {
+        self.screen.borrow_mut().set_alive_cells(alive_cells);
+        self.view.draw(&self.screen.borrow().render());
+    }
+
+    // The run loop draws the screen along with the status line once per tick
+    fn present_changes(&mut self, changes: &[Change]) {
+        self.screen.borrow_mut().apply(changes);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::ALIVE;
+    use mockers::matchers::{check, ANY};
+    use mockers::Scenario;
+
+    #[test]
+    fn draws_initial_board() {
+        let scenario = Scenario::new();
+        let (view, view_handle) = scenario.create_mock_for::<dyn TerminalView>();
+        let screen = Rc::new(RefCell::new(Screen::new(2, 2, 2, 1)));
+        let expected = [format!("{}▀{}▄", "\x1b[7m", "\x1b[27m"), String::new()];
+        scenario.expect(
+            view_handle
+                .draw(check(move |lines: &&[String]| *lines == &expected[..]))
+                .and_return(()),
+        );
+        let mut presenter = TerminalPresenter::new(Box::new(view), screen);
+
+        presenter.init_board(&[Position { x: 0, y: 0 }, Position { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn applies_changes_to_shared_screen_without_drawing() {
+        let scenario = Scenario::new();
+        let (view, view_handle) = scenario.create_mock_for::<dyn TerminalView>();
+        let screen = Rc::new(RefCell::new(Screen::new(2, 2, 2, 1)));
+        scenario.expect(view_handle.draw(ANY).and_return(()));
+        let mut presenter = TerminalPresenter::new(Box::new(view), screen.clone());
+
+        presenter.init_board(&[]);
+        presenter.present_changes(&[Change {
+            position: Position { x: 1, y: 0 },
+            state: ALIVE,
+        }]);
+
+        let mut expected = Screen::new(2, 2, 2, 1);
+        expected.set_alive_cells(&[Position { x: 1, y: 0 }]);
+        assert_eq!(expected, *screen.borrow());
+    }
+}
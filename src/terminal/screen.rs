@@ -0,0 +1,226 @@
+use crate::generation_calculator::Change;
+use crate::grid::Position;
+
+const INVERSE: &str = "\x1b[7m";
+const NO_INVERSE: &str = "\x1b[27m";
+
+// What the terminal shows of the board. Every character covers two cells stacked
+// on top of each other, so a screen of `lines` lines shows twice as many rows.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Screen {
+    width: u32,
+    height: u32,
+    alive: Vec<bool>,
+    origin: Position,
+    columns: u32,
+    lines: u32,
+    cursor: Position,
+    status: String,
+}
+
+impl Screen {
+    pub fn new(width: u32, height: u32, columns: u32, lines: u32) -> Self {
+        Screen {
+            width,
+            height,
+            alive: vec![false; (width * height) as usize],
+            origin: Position { x: 0, y: 0 },
+            columns,
+            lines,
+            cursor: Position { x: 0, y: 0 },
+            status: String::new(),
+        }
+    }
+
+    pub fn set_alive_cells(&mut self, alive_cells: &[Position]) {
+        self.alive = vec![false; (self.width * self.height) as usize];
+        for &position in alive_cells {
+            self.set(position, true);
+        }
+    }
+
+    pub fn apply(&mut self, changes: &[Change]) {
+        for change in changes {
+            self.set(change.position, change.is_alive());
+        }
+    }
+
+    pub fn cursor(&self) -> Position {
+        self.cursor
+    }
+
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+
+    pub fn set_status(&mut self, status: &str) {
+        self.status = status.to_string();
+    }
+
+    // Scrolls along whenever the cursor would leave the screen
+    pub fn move_cursor(&mut self, dx: i64, dy: i64) {
+        self.cursor = Position {
+            x: clamp(i64::from(self.cursor.x) + dx, self.width.saturating_sub(1)),
+            y: clamp(i64::from(self.cursor.y) + dy, self.height.saturating_sub(1)),
+        };
+        let (rows, columns) = (self.rows(), self.columns);
+        if self.cursor.x < self.origin.x {
+            self.origin.x = self.cursor.x;
+        } else if self.cursor.x >= self.origin.x + columns {
+            self.origin.x = self.cursor.x + 1 - columns;
+        }
+        if self.cursor.y < self.origin.y {
+            self.origin.y = self.cursor.y;
+        } else if self.cursor.y >= self.origin.y + rows {
+            self.origin.y = self.cursor.y + 1 - rows;
+        }
+    }
+
+    // Takes the cursor along so it stays on the screen
+    pub fn scroll(&mut self, dx: i64, dy: i64) {
+        self.origin = Position {
+            x: clamp(
+                i64::from(self.origin.x) + dx,
+                self.width.saturating_sub(self.columns),
+            ),
+            y: clamp(
+                i64::from(self.origin.y) + dy,
+                self.height.saturating_sub(self.rows()),
+            ),
+        };
+        let last_x = (self.origin.x + self.columns)
+            .min(self.width)
+            .saturating_sub(1);
+        let last_y = (self.origin.y + self.rows())
+            .min(self.height)
+            .saturating_sub(1);
+        self.cursor = Position {
+            x: self.cursor.x.max(self.origin.x).min(last_x),
+            y: self.cursor.y.max(self.origin.y).min(last_y),
+        };
+    }
+
+    // One string per line followed by the status line, cut to the width of the screen
+    pub fn render(&self) -> Vec<String> {
+        let mut rendered = Vec::with_capacity(self.lines as usize + 1);
+        for line in 0..self.lines {
+            let top = self.origin.y + line * 2;
+            let mut text = String::new();
+            for column in 0..self.columns {
+                let x = self.origin.x + column;
+                if x >= self.width {
+                    break;
+                }
+                let character = match (self.is_alive(x, top), self.is_alive(x, top + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                };
+                let has_cursor =
+                    self.cursor.x == x && (self.cursor.y == top || self.cursor.y == top + 1);
+                if has_cursor {
+                    text.push_str(INVERSE);
+                    text.push(character);
+                    text.push_str(NO_INVERSE);
+                } else {
+                    text.push(character);
+                }
+            }
+            rendered.push(text);
+        }
+        // A status line wider than the terminal would wrap and scroll the whole screen
+        rendered.push(self.status.chars().take(self.columns as usize).collect());
+        rendered
+    }
+
+    fn rows(&self) -> u32 {
+        self.lines * 2
+    }
+
+    fn is_alive(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.alive[(y * self.width + x) as usize]
+    }
+
+    fn set(&mut self, position: Position, is_alive: bool) {
+        if position.x < self.width && position.y < self.height {
+            self.alive[(position.y * self.width + position.x) as usize] = is_alive;
+        }
+    }
+}
+
+fn clamp(value: i64, max: u32) -> u32 {
+    value.max(0).min(i64::from(max)) as u32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::ALIVE;
+
+    #[test]
+    fn renders_two_rows_per_line() {
+        let mut screen = Screen::new(4, 4, 4, 2);
+        screen.set_alive_cells(&[
+            Position { x: 1, y: 0 },
+            Position { x: 2, y: 1 },
+            Position { x: 3, y: 0 },
+            Position { x: 3, y: 1 },
+        ]);
+        screen.move_cursor(0, 2);
+        screen.set_status("status");
+
+        let expected = vec![
+            " ▀▄█".to_string(),
+            format!("{} {}   ", INVERSE, NO_INVERSE),
+            "stat".to_string(),
+        ];
+        assert_eq!(expected, screen.render());
+    }
+
+    #[test]
+    fn applies_changes() {
+        let mut screen = Screen::new(2, 2, 2, 1);
+        screen.move_cursor(1, 1);
+        screen.apply(&[
+            Change {
+                position: Position { x: 0, y: 1 },
+                state: ALIVE,
+            },
+            Change {
+                position: Position { x: 5, y: 5 },
+                state: ALIVE,
+            },
+        ]);
+        assert_eq!("▄", &screen.render()[0][..'▄'.len_utf8()]);
+    }
+
+    #[test]
+    fn cursor_stays_on_board() {
+        let mut screen = Screen::new(10, 6, 20, 10);
+        screen.move_cursor(-3, 2);
+        assert_eq!(Position { x: 0, y: 2 }, screen.cursor());
+        screen.move_cursor(30, 30);
+        assert_eq!(Position { x: 9, y: 5 }, screen.cursor());
+    }
+
+    #[test]
+    fn scrolls_to_follow_cursor() {
+        let mut screen = Screen::new(100, 100, 10, 5);
+        screen.move_cursor(12, 10);
+        assert_eq!(Position { x: 3, y: 1 }, screen.origin());
+        screen.move_cursor(-10, -10);
+        assert_eq!(Position { x: 2, y: 0 }, screen.origin());
+    }
+
+    #[test]
+    fn scrolling_takes_cursor_along() {
+        let mut screen = Screen::new(100, 100, 10, 5);
+        screen.scroll(20, 500);
+        assert_eq!(Position { x: 20, y: 90 }, screen.origin());
+        assert_eq!(Position { x: 20, y: 90 }, screen.cursor());
+        screen.scroll(-100, 0);
+        assert_eq!(Position { x: 0, y: 90 }, screen.origin());
+        assert_eq!(Position { x: 9, y: 90 }, screen.cursor());
+    }
+}
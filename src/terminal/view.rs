@@ -0,0 +1,32 @@
+use super::presenter::TerminalView;
+use std::io::Write;
+use termion::{clear, cursor};
+
+pub struct TerminalViewImpl<W: Write> {
+    output: W,
+}
+
+impl<W: Write> TerminalViewImpl<W> {
+    pub fn new(output: W) -> Self {
+        TerminalViewImpl { output }
+    }
+}
+
+// Redraws everything in place, so nothing scrolls by in the terminal
+impl<W: Write> TerminalView for TerminalViewImpl<W> {
+    fn draw(&mut self, lines: &[String]) {
+        let mut text = format!("{}", cursor::Goto(1, 1));
+        for (index, line) in lines.iter().enumerate() {
+            if index > 0 {
+                text.push_str("\r\n");
+            }
+            text.push_str(line);
+            text.push_str(&format!("{}", clear::UntilNewline));
+        }
+        // A closed terminal is no reason to bring the game down
+        let _ = self
+            .output
+            .write_all(text.as_bytes())
+            .and_then(|_| self.output.flush());
+    }
+}
@@ -0,0 +1,42 @@
+use crate::generation_calculator::{GenerationCalculator, GenerationCalculatorImpl};
+use crate::grid::{alive_cells, apply_changes, Grid, GridImpl, Position};
+use crate::rule::Rule;
+use crate::soup::{Soup, Symmetry};
+
+// Brings every cell to life with a chance of one half
+pub fn fill_soup(grid: &mut dyn Grid, seed: u64) {
+    let soup = Soup::new(seed, 0.5, Symmetry::C1);
+    for position in soup.alive_cells(grid.width(), grid.height()) {
+        grid.set_alive_at(position);
+    }
+}
+
+pub fn step(grid: &mut (dyn Grid + 'static), calculator: &dyn GenerationCalculator) {
+    let changes = calculator.next_generation(grid);
+    apply_changes(grid, &changes);
+    calculator.accept_changes(&changes);
+}
+
+pub fn alive_coordinates(grid: &dyn Grid) -> Vec<(u32, u32)> {
+    alive_cells(grid)
+        .into_iter()
+        .map(|position| (position.x, position.y))
+        .collect()
+}
+
+// Runs a glider for two periods next to the plain Life calculator
+pub fn assert_plays_like_life(calculator: &dyn GenerationCalculator) {
+    let mut grid = GridImpl::new(8, 8);
+    for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+        grid.set_alive_at(Position { x, y });
+    }
+    let mut expected = grid.clone();
+    let life = GenerationCalculatorImpl::new(Rule::conway());
+
+    for _ in 0..8 {
+        step(&mut grid, calculator);
+        step(&mut expected, &life);
+    }
+
+    assert_eq!(alive_cells(&expected), alive_cells(&grid));
+}
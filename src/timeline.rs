@@ -0,0 +1,497 @@
+use crate::generation_calculator::Change;
+use crate::grid::{cell_states, Grid, DEAD};
+use crate::infinite_grid::Coordinate;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+struct Keyframe {
+    generation: u64,
+    origin: Coordinate,
+    cells: Vec<Change>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct TimelinePosition {
+    pub earliest: u64,
+    pub current: u64,
+    pub latest: u64,
+}
+
+// Stores a full keyframe every few generations and the changes between consecutive
+// generations, so any recorded generation can be rebuilt from the keyframe before it.
+// Edits made between two generations become part of the next generation's changes.
+// On boards larger than the grid, the cells are seen from the origin of the viewport
+// that was current when the keyframe before them was taken.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Timeline {
+    keyframe_interval: u64,
+    max_stored_cells: usize,
+    origin: Coordinate,
+    keyframes: VecDeque<Keyframe>,
+    deltas: VecDeque<Vec<Change>>,
+    pending_edits: Vec<Change>,
+    stored_cells: usize,
+}
+
+impl Timeline {
+    pub fn new(keyframe_interval: u64, max_stored_cells: usize) -> Self {
+        Timeline {
+            keyframe_interval: keyframe_interval.max(1),
+            max_stored_cells,
+            origin: Coordinate { x: 0, y: 0 },
+            keyframes: VecDeque::new(),
+            deltas: VecDeque::new(),
+            pending_edits: Vec::new(),
+            stored_cells: 0,
+        }
+    }
+
+    // Takes every cell that is not dead together with its state
+    pub fn start(&mut self, generation: u64, cells: Vec<Change>) {
+        self.stored_cells = cells.len();
+        self.keyframes.clear();
+        self.keyframes.push_back(Keyframe {
+            generation,
+            origin: self.origin,
+            cells,
+        });
+        self.deltas.clear();
+        self.pending_edits.clear();
+    }
+
+    pub fn earliest_generation(&self) -> u64 {
+        self.keyframes
+            .front()
+            .map_or(0, |keyframe| keyframe.generation)
+    }
+
+    pub fn latest_generation(&self) -> u64 {
+        self.earliest_generation() + self.deltas.len() as u64
+    }
+
+    pub fn stored_cells(&self) -> usize {
+        self.stored_cells
+    }
+
+    pub fn amend(&mut self, changes: &[Change]) {
+        self.pending_edits.extend_from_slice(changes);
+    }
+
+    pub fn discard_edits(&mut self) {
+        self.pending_edits.clear();
+    }
+
+    // Continues the timeline as seen from another origin. Unless the generation was
+    // recorded from there, it is recorded again, dropping the future and folding in any edits.
+    pub fn move_to(&mut self, generation: u64, origin: Coordinate, grid: &dyn Grid) {
+        let is_recorded = self.origin_at(generation) == Some(origin);
+        self.origin = origin;
+        if is_recorded {
+            return;
+        }
+        if generation < self.earliest_generation() || generation > self.latest_generation() {
+            self.start(generation, cell_states(grid));
+            return;
+        }
+        self.truncate_after(generation);
+        self.pending_edits.clear();
+        if self
+            .keyframes
+            .back()
+            .is_some_and(|keyframe| keyframe.generation == generation)
+        {
+            let keyframe = self.keyframes.pop_back().unwrap();
+            self.stored_cells -= keyframe.cells.len();
+        }
+        let cells = cell_states(grid);
+        self.stored_cells += cells.len();
+        self.keyframes.push_back(Keyframe {
+            generation,
+            origin,
+            cells,
+        });
+        self.evict();
+    }
+
+    // The origin the cells of the given generation are seen from
+    pub fn origin_at(&self, generation: u64) -> Option<Coordinate> {
+        if generation < self.earliest_generation() || generation > self.latest_generation() {
+            return None;
+        }
+        self.keyframes
+            .iter()
+            .rev()
+            .find(|keyframe| keyframe.generation <= generation)
+            .map(|keyframe| keyframe.origin)
+    }
+
+    // The changes leading from the given generation to the next one,
+    // as long as nothing was edited and both are seen from the current origin
+    pub fn replay(&self, generation: u64) -> Option<&[Change]> {
+        if !self.pending_edits.is_empty()
+            || generation < self.earliest_generation()
+            || self.origin_at(generation + 1) != Some(self.origin)
+        {
+            return None;
+        }
+        let index = (generation - self.earliest_generation()) as usize;
+        self.deltas.get(index).map(|delta| delta.as_slice())
+    }
+
+    // Records the step from `generation` to the next one, dropping any recorded future
+    pub fn record(&mut self, generation: u64, changes: &[Change], grid: &dyn Grid) {
+        if generation < self.earliest_generation() || generation > self.latest_generation() {
+            self.start(generation + 1, cell_states(grid));
+            return;
+        }
+        self.truncate_after(generation);
+        let mut delta = self.pending_edits.split_off(0);
+        delta.extend_from_slice(changes);
+        self.stored_cells += delta.len();
+        self.deltas.push_back(delta);
+
+        let latest_generation = self.latest_generation();
+        let is_over_capacity = self.stored_cells > self.max_stored_cells;
+        if latest_generation.is_multiple_of(self.keyframe_interval)
+            || (is_over_capacity && self.keyframes.len() == 1)
+        {
+            let cells = cell_states(grid);
+            self.stored_cells += cells.len();
+            self.keyframes.push_back(Keyframe {
+                generation: latest_generation,
+                origin: self.origin,
+                cells,
+            });
+        }
+        self.evict();
+    }
+
+    // Every cell that is not dead at the given generation, ordered by row
+    pub fn state_at(&self, generation: u64) -> Option<Vec<Change>> {
+        if generation < self.earliest_generation() || generation > self.latest_generation() {
+            return None;
+        }
+        let keyframe = self
+            .keyframes
+            .iter()
+            .rev()
+            .find(|keyframe| keyframe.generation <= generation)?;
+        let mut states = keyframe
+            .cells
+            .iter()
+            .map(|cell| (cell.position, cell.state))
+            .collect::<HashMap<_, _>>();
+        let first_delta = (keyframe.generation - self.earliest_generation()) as usize;
+        let last_delta = (generation - self.earliest_generation()) as usize;
+        for delta in self.deltas.range(first_delta..last_delta) {
+            for change in delta {
+                if change.state == DEAD {
+                    states.remove(&change.position);
+                } else {
+                    states.insert(change.position, change.state);
+                }
+            }
+        }
+        let mut cells = states
+            .into_iter()
+            .map(|(position, state)| Change { position, state })
+            .collect::<Vec<_>>();
+        cells.sort_by_key(|cell| (cell.position.y, cell.position.x));
+        Some(cells)
+    }
+
+    fn truncate_after(&mut self, generation: u64) {
+        let kept_deltas = (generation - self.earliest_generation()) as usize;
+        while self.deltas.len() > kept_deltas {
+            let delta = self.deltas.pop_back().unwrap();
+            self.stored_cells -= delta.len();
+        }
+        while self
+            .keyframes
+            .back()
+            .is_some_and(|keyframe| keyframe.generation > generation)
+        {
+            let keyframe = self.keyframes.pop_back().unwrap();
+            self.stored_cells -= keyframe.cells.len();
+        }
+    }
+
+    // Forgets the oldest keyframe together with the changes leading up to the next one
+    fn evict(&mut self) {
+        while self.stored_cells > self.max_stored_cells && self.keyframes.len() > 1 {
+            let keyframe = self.keyframes.pop_front().unwrap();
+            self.stored_cells -= keyframe.cells.len();
+            let next_generation = self.earliest_generation();
+            for _ in keyframe.generation..next_generation {
+                let delta = self.deltas.pop_front().unwrap();
+                self.stored_cells -= delta.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::{GridImpl, Position, ALIVE};
+    use crate::infinite_grid::{InfiniteGrid, Viewport};
+
+    fn blinker_grid() -> GridImpl {
+        let mut grid = GridImpl::new(5, 5);
+        for x in 1..4 {
+            grid.set_alive_at(Position { x, y: 2 });
+        }
+        grid
+    }
+
+    fn step(grid: &mut GridImpl, generation: u64) -> Vec<Change> {
+        let changes = if generation.is_multiple_of(2) {
+            vec![
+                Change {
+                    position: Position { x: 2, y: 1 },
+                    state: ALIVE,
+                },
+                Change {
+                    position: Position { x: 1, y: 2 },
+                    state: DEAD,
+                },
+                Change {
+                    position: Position { x: 3, y: 2 },
+                    state: DEAD,
+                },
+                Change {
+                    position: Position { x: 2, y: 3 },
+                    state: ALIVE,
+                },
+            ]
+        } else {
+            vec![
+                Change {
+                    position: Position { x: 2, y: 1 },
+                    state: DEAD,
+                },
+                Change {
+                    position: Position { x: 1, y: 2 },
+                    state: ALIVE,
+                },
+                Change {
+                    position: Position { x: 3, y: 2 },
+                    state: ALIVE,
+                },
+                Change {
+                    position: Position { x: 2, y: 3 },
+                    state: DEAD,
+                },
+            ]
+        };
+        for change in &changes {
+            grid.set_state_at(change.position, change.state);
+        }
+        changes
+    }
+
+    fn record_blinker(timeline: &mut Timeline, generations: u64) -> GridImpl {
+        let mut grid = blinker_grid();
+        timeline.start(0, cell_states(&grid));
+        for generation in 0..generations {
+            let changes = step(&mut grid, generation);
+            timeline.record(generation, &changes, &grid);
+        }
+        grid
+    }
+
+    #[test]
+    fn rebuilds_every_recorded_generation() {
+        let mut timeline = Timeline::new(4, 1000);
+        record_blinker(&mut timeline, 10);
+
+        let horizontal = cell_states(&blinker_grid());
+        let mut vertical_grid = blinker_grid();
+        step(&mut vertical_grid, 0);
+        let vertical = cell_states(&vertical_grid);
+        for generation in 0..=10 {
+            let expected = if generation % 2 == 0 {
+                &horizontal
+            } else {
+                &vertical
+            };
+            assert_eq!(Some(expected.clone()), timeline.state_at(generation));
+        }
+        assert_eq!(None, timeline.state_at(11));
+    }
+
+    #[test]
+    fn replays_recorded_changes() {
+        let mut timeline = Timeline::new(4, 1000);
+        record_blinker(&mut timeline, 3);
+
+        let mut grid = blinker_grid();
+        let expected = step(&mut grid, 1);
+        assert_eq!(Some(expected.as_slice()), timeline.replay(1));
+        assert_eq!(None, timeline.replay(3));
+    }
+
+    #[test]
+    fn does_not_replay_after_edits() {
+        let mut timeline = Timeline::new(4, 1000);
+        record_blinker(&mut timeline, 3);
+        timeline.amend(&[Change {
+            position: Position { x: 0, y: 0 },
+            state: ALIVE,
+        }]);
+        assert_eq!(None, timeline.replay(1));
+    }
+
+    #[test]
+    fn folds_edits_into_next_generation() {
+        let mut timeline = Timeline::new(4, 1000);
+        let mut grid = blinker_grid();
+        timeline.start(0, cell_states(&grid));
+        let edit = Change {
+            position: Position { x: 0, y: 0 },
+            state: ALIVE,
+        };
+        grid.set_alive_at(edit.position);
+        timeline.amend(&[edit]);
+        let changes = step(&mut grid, 0);
+        timeline.record(0, &changes, &grid);
+
+        assert_eq!(Some(cell_states(&grid)), timeline.state_at(1));
+        assert_eq!(Some(cell_states(&blinker_grid())), timeline.state_at(0));
+    }
+
+    #[test]
+    fn recording_in_the_past_drops_the_future() {
+        let mut timeline = Timeline::new(2, 1000);
+        record_blinker(&mut timeline, 6);
+        let mut grid = blinker_grid();
+        let changes = step(&mut grid, 0);
+
+        timeline.record(0, &changes, &grid);
+
+        assert_eq!(1, timeline.latest_generation());
+        assert_eq!(Some(cell_states(&grid)), timeline.state_at(1));
+    }
+
+    #[test]
+    fn stays_within_memory_cap() {
+        let mut timeline = Timeline::new(4, 40);
+        record_blinker(&mut timeline, 100);
+
+        assert!(timeline.stored_cells() <= 40);
+        assert!(timeline.earliest_generation() > 0);
+        assert_eq!(100, timeline.latest_generation());
+        let expected = cell_states(&blinker_grid());
+        assert_eq!(Some(expected), timeline.state_at(100));
+    }
+
+    #[test]
+    fn adds_keyframe_when_over_capacity_without_one() {
+        let mut timeline = Timeline::new(1000, 20);
+        record_blinker(&mut timeline, 10);
+
+        assert!(timeline.stored_cells() <= 20);
+        assert!(timeline.earliest_generation() > 0);
+        assert_eq!(10, timeline.latest_generation());
+    }
+
+    #[test]
+    fn restarts_when_recording_unknown_generation() {
+        let mut timeline = Timeline::new(4, 1000);
+        let grid = blinker_grid();
+        timeline.start(0, Vec::new());
+
+        timeline.record(7, &[], &grid);
+
+        assert_eq!(8, timeline.earliest_generation());
+        assert_eq!(Some(cell_states(&grid)), timeline.state_at(8));
+    }
+
+    #[test]
+    fn rebuilds_multi_state_cells() {
+        let mut timeline = Timeline::new(4, 1000);
+        let mut grid = GridImpl::new(3, 1);
+        timeline.start(0, cell_states(&grid));
+        for state in 1..4 {
+            let changes = [Change {
+                position: Position { x: 1, y: 0 },
+                state,
+            }];
+            grid.set_state_at(changes[0].position, state);
+            timeline.record(state as u64 - 1, &changes, &grid);
+        }
+
+        let cell = |state| {
+            Some(vec![Change {
+                position: Position { x: 1, y: 0 },
+                state,
+            }])
+        };
+        assert_eq!(cell(2), timeline.state_at(2));
+        assert_eq!(cell(3), timeline.state_at(3));
+    }
+
+    fn glider_grid(origin: Coordinate) -> InfiniteGrid {
+        let mut grid = InfiniteGrid::new(Viewport {
+            origin: Coordinate { x: 0, y: 0 },
+            width: 5,
+            height: 5,
+        });
+        for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid.set_alive_at(Position { x, y });
+        }
+        grid.shared_viewport().borrow_mut().origin = origin;
+        grid
+    }
+
+    #[test]
+    fn sees_generations_from_origin_they_were_recorded_from() {
+        let origin = Coordinate { x: 0, y: 0 };
+        let moved_origin = Coordinate { x: -2, y: 1 };
+        let mut timeline = Timeline::new(4, 1000);
+        timeline.start(0, cell_states(&glider_grid(origin)));
+        timeline.record(0, &[], &glider_grid(origin));
+        timeline.record(1, &[], &glider_grid(origin));
+
+        let moved_grid = glider_grid(moved_origin);
+        timeline.move_to(1, moved_origin, &moved_grid);
+
+        assert_eq!(1, timeline.latest_generation());
+        assert_eq!(Some(origin), timeline.origin_at(0));
+        assert_eq!(Some(moved_origin), timeline.origin_at(1));
+        assert_eq!(
+            Some(cell_states(&glider_grid(origin))),
+            timeline.state_at(0)
+        );
+        assert_eq!(Some(cell_states(&moved_grid)), timeline.state_at(1));
+    }
+
+    #[test]
+    fn does_not_replay_into_generation_seen_from_elsewhere() {
+        let origin = Coordinate { x: 0, y: 0 };
+        let moved_origin = Coordinate { x: -2, y: 1 };
+        let mut timeline = Timeline::new(4, 1000);
+        timeline.start(0, cell_states(&glider_grid(origin)));
+        timeline.record(0, &[], &glider_grid(origin));
+        timeline.move_to(1, moved_origin, &glider_grid(moved_origin));
+        timeline.record(1, &[], &glider_grid(moved_origin));
+
+        timeline.move_to(0, origin, &glider_grid(origin));
+
+        assert_eq!(None, timeline.replay(0));
+        assert_eq!(2, timeline.latest_generation());
+    }
+
+    #[test]
+    fn keeps_recording_when_moved_back() {
+        let origin = Coordinate { x: 0, y: 0 };
+        let mut timeline = Timeline::new(4, 1000);
+        timeline.start(0, cell_states(&glider_grid(origin)));
+        timeline.record(0, &[], &glider_grid(origin));
+
+        timeline.move_to(1, origin, &glider_grid(origin));
+
+        assert_eq!(1, timeline.latest_generation());
+        assert_eq!(Some(&[][..]), timeline.replay(0));
+    }
+}
@@ -0,0 +1,342 @@
+use crate::grid::Position;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub enum Topology {
+    #[default]
+    Bounded,
+    Torus,
+    KleinBottle(TwistedEdges),
+    CrossSurface,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum TwistedEdges {
+    TopAndBottom,
+    LeftAndRight,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct GridSpec {
+    pub topology: Topology,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum GridSpecParseError {
+    UnknownTopology(char),
+    MissingSize,
+    InvalidSize(String),
+    MissingTwist,
+    UnexpectedTwist,
+}
+
+impl Topology {
+    pub fn resolve(self, width: u32, height: u32, x: i64, y: i64) -> Option<Position> {
+        let (width, height) = (i64::from(width), i64::from(height));
+        let (twist_top_and_bottom, twist_left_and_right) = match self {
+            Topology::Bounded => {
+                return if x < 0 || y < 0 || x >= width || y >= height {
+                    None
+                } else {
+                    Some(to_position(x, y))
+                }
+            }
+            Topology::Torus => (false, false),
+            Topology::KleinBottle(TwistedEdges::TopAndBottom) => (true, false),
+            Topology::KleinBottle(TwistedEdges::LeftAndRight) => (false, true),
+            Topology::CrossSurface => (true, true),
+        };
+        // Every time a twisted edge is crossed, the coordinate along that edge gets mirrored
+        let mirrored_x = twist_top_and_bottom && is_odd(crossings(y, height));
+        let mirrored_y = twist_left_and_right && is_odd(crossings(x, width));
+        let x = if mirrored_x { width - 1 - x } else { x };
+        let y = if mirrored_y { height - 1 - y } else { y };
+        Some(to_position(wrap(x, width), wrap(y, height)))
+    }
+}
+
+fn to_position(x: i64, y: i64) -> Position {
+    Position {
+        x: x as u32,
+        y: y as u32,
+    }
+}
+
+fn crossings(coordinate: i64, size: i64) -> i64 {
+    (coordinate - wrap(coordinate, size)) / size
+}
+
+fn is_odd(value: i64) -> bool {
+    value % 2 != 0
+}
+
+fn wrap(coordinate: i64, size: i64) -> i64 {
+    ((coordinate % size) + size) % size
+}
+
+// Parses the bounded grid suffix Golly appends to rules, e.g. "T100,80" or ":K100*,80"
+impl FromStr for GridSpec {
+    type Err = GridSpecParseError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let spec = spec.trim();
+        let spec = spec.strip_prefix(':').unwrap_or(spec);
+        let mut characters = spec.chars();
+        let kind = characters
+            .next()
+            .ok_or(GridSpecParseError::MissingSize)?
+            .to_ascii_uppercase();
+        let sizes = characters.as_str().split(',').collect::<Vec<_>>();
+        if sizes.len() != 2 {
+            return Err(GridSpecParseError::MissingSize);
+        }
+        let (width, width_is_twisted) = parse_size(sizes[0])?;
+        let (height, height_is_twisted) = parse_size(sizes[1])?;
+
+        let topology = match (kind, width_is_twisted, height_is_twisted) {
+            ('P', false, false) => Topology::Bounded,
+            ('T', false, false) => Topology::Torus,
+            ('C', false, false) => Topology::CrossSurface,
+            ('K', true, false) => Topology::KleinBottle(TwistedEdges::TopAndBottom),
+            ('K', false, true) => Topology::KleinBottle(TwistedEdges::LeftAndRight),
+            ('K', _, _) => return Err(GridSpecParseError::MissingTwist),
+            ('P', _, _) | ('T', _, _) | ('C', _, _) => {
+                return Err(GridSpecParseError::UnexpectedTwist)
+            }
+            (kind, _, _) => return Err(GridSpecParseError::UnknownTopology(kind)),
+        };
+        Ok(GridSpec {
+            topology,
+            width,
+            height,
+        })
+    }
+}
+
+fn parse_size(size: &str) -> Result<(u32, bool), GridSpecParseError> {
+    let is_twisted = size.ends_with('*');
+    let digits = if is_twisted {
+        &size[..size.len() - 1]
+    } else {
+        size
+    };
+    match digits.parse() {
+        Ok(size) if size > 0 => Ok((size, is_twisted)),
+        _ => Err(GridSpecParseError::InvalidSize(size.to_string())),
+    }
+}
+
+impl fmt::Display for GridSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (kind, width_twist, height_twist) = match self.topology {
+            Topology::Bounded => ('P', "", ""),
+            Topology::Torus => ('T', "", ""),
+            Topology::KleinBottle(TwistedEdges::TopAndBottom) => ('K', "*", ""),
+            Topology::KleinBottle(TwistedEdges::LeftAndRight) => ('K', "", "*"),
+            Topology::CrossSurface => ('C', "", ""),
+        };
+        write!(
+            f,
+            "{}{}{},{}{}",
+            kind, self.width, width_twist, self.height, height_twist
+        )
+    }
+}
+
+impl fmt::Display for GridSpecParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridSpecParseError::UnknownTopology(kind) => {
+                write!(f, "'{}' is not one of the topologies P, T, K or C", kind)
+            }
+            GridSpecParseError::MissingSize => write!(f, "expected a width and height"),
+            GridSpecParseError::InvalidSize(size) => write!(f, "'{}' is not a valid size", size),
+            GridSpecParseError::MissingTwist => {
+                write!(f, "a Klein bottle needs exactly one twisted ('*') edge")
+            }
+            GridSpecParseError::UnexpectedTwist => {
+                write!(f, "only a Klein bottle can have a twisted ('*') edge")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bounded_resolves_inside() {
+        let position = Topology::Bounded.resolve(5, 4, 4, 3);
+        assert_eq!(Some(Position { x: 4, y: 3 }), position);
+    }
+
+    #[test]
+    fn bounded_does_not_resolve_outside() {
+        assert_eq!(None, Topology::Bounded.resolve(5, 4, -1, 0));
+        assert_eq!(None, Topology::Bounded.resolve(5, 4, 0, -1));
+        assert_eq!(None, Topology::Bounded.resolve(5, 4, 5, 0));
+        assert_eq!(None, Topology::Bounded.resolve(5, 4, 0, 4));
+    }
+
+    #[test]
+    fn torus_wraps_horizontally() {
+        assert_eq!(
+            Some(Position { x: 4, y: 2 }),
+            Topology::Torus.resolve(5, 4, -1, 2)
+        );
+        assert_eq!(
+            Some(Position { x: 0, y: 2 }),
+            Topology::Torus.resolve(5, 4, 5, 2)
+        );
+    }
+
+    #[test]
+    fn torus_wraps_vertically() {
+        assert_eq!(
+            Some(Position { x: 1, y: 3 }),
+            Topology::Torus.resolve(5, 4, 1, -1)
+        );
+        assert_eq!(
+            Some(Position { x: 1, y: 0 }),
+            Topology::Torus.resolve(5, 4, 1, 4)
+        );
+    }
+
+    #[test]
+    fn torus_wraps_corners() {
+        assert_eq!(
+            Some(Position { x: 4, y: 3 }),
+            Topology::Torus.resolve(5, 4, -1, -1)
+        );
+        assert_eq!(
+            Some(Position { x: 0, y: 0 }),
+            Topology::Torus.resolve(5, 4, 5, 4)
+        );
+    }
+
+    #[test]
+    fn klein_bottle_mirrors_across_twisted_top_and_bottom() {
+        let topology = Topology::KleinBottle(TwistedEdges::TopAndBottom);
+        assert_eq!(Some(Position { x: 3, y: 3 }), topology.resolve(5, 4, 1, -1));
+        assert_eq!(Some(Position { x: 4, y: 0 }), topology.resolve(5, 4, 0, 4));
+    }
+
+    #[test]
+    fn klein_bottle_wraps_untwisted_left_and_right() {
+        let topology = Topology::KleinBottle(TwistedEdges::TopAndBottom);
+        assert_eq!(Some(Position { x: 4, y: 1 }), topology.resolve(5, 4, -1, 1));
+        assert_eq!(Some(Position { x: 0, y: 1 }), topology.resolve(5, 4, 5, 1));
+    }
+
+    #[test]
+    fn klein_bottle_mirrors_across_twisted_left_and_right() {
+        let topology = Topology::KleinBottle(TwistedEdges::LeftAndRight);
+        assert_eq!(Some(Position { x: 4, y: 2 }), topology.resolve(5, 4, -1, 1));
+        assert_eq!(Some(Position { x: 1, y: 3 }), topology.resolve(5, 4, 1, -1));
+    }
+
+    #[test]
+    fn cross_surface_mirrors_across_every_edge() {
+        let topology = Topology::CrossSurface;
+        assert_eq!(Some(Position { x: 4, y: 2 }), topology.resolve(5, 4, -1, 1));
+        assert_eq!(Some(Position { x: 3, y: 3 }), topology.resolve(5, 4, 1, -1));
+        assert_eq!(
+            Some(Position { x: 0, y: 0 }),
+            topology.resolve(5, 4, -1, -1)
+        );
+    }
+
+    #[test]
+    fn crossing_a_twisted_edge_twice_does_not_mirror() {
+        let topology = Topology::KleinBottle(TwistedEdges::TopAndBottom);
+        assert_eq!(Some(Position { x: 1, y: 3 }), topology.resolve(5, 4, 1, -5));
+    }
+
+    #[test]
+    fn parses_golly_suffixes() {
+        let cases = [
+            (":P30,20", Topology::Bounded),
+            ("T30,20", Topology::Torus),
+            (
+                ":K30*,20",
+                Topology::KleinBottle(TwistedEdges::TopAndBottom),
+            ),
+            (
+                ":K30,20*",
+                Topology::KleinBottle(TwistedEdges::LeftAndRight),
+            ),
+            (":c30,20", Topology::CrossSurface),
+        ];
+        for (spec, topology) in &cases {
+            let expected = GridSpec {
+                topology: *topology,
+                width: 30,
+                height: 20,
+            };
+            assert_eq!(Ok(expected), spec.parse::<GridSpec>());
+        }
+    }
+
+    #[test]
+    fn displays_golly_suffix() {
+        let spec = GridSpec {
+            topology: Topology::KleinBottle(TwistedEdges::LeftAndRight),
+            width: 30,
+            height: 20,
+        };
+        assert_eq!("K30,20*", spec.to_string());
+    }
+
+    #[test]
+    fn fails_on_unknown_topology() {
+        assert_eq!(
+            Err(GridSpecParseError::UnknownTopology('S')),
+            "S30,20".parse::<GridSpec>()
+        );
+    }
+
+    #[test]
+    fn fails_on_missing_size() {
+        assert_eq!(
+            Err(GridSpecParseError::MissingSize),
+            "T30".parse::<GridSpec>()
+        );
+        assert_eq!(Err(GridSpecParseError::MissingSize), "".parse::<GridSpec>());
+    }
+
+    #[test]
+    fn fails_on_invalid_size() {
+        assert_eq!(
+            Err(GridSpecParseError::InvalidSize("0".to_string())),
+            "T0,20".parse::<GridSpec>()
+        );
+        assert_eq!(
+            Err(GridSpecParseError::InvalidSize("x".to_string())),
+            "T30,x".parse::<GridSpec>()
+        );
+    }
+
+    #[test]
+    fn fails_on_klein_bottle_without_single_twist() {
+        assert_eq!(
+            Err(GridSpecParseError::MissingTwist),
+            "K30,20".parse::<GridSpec>()
+        );
+        assert_eq!(
+            Err(GridSpecParseError::MissingTwist),
+            "K30*,20*".parse::<GridSpec>()
+        );
+    }
+
+    #[test]
+    fn fails_on_twist_outside_klein_bottle() {
+        assert_eq!(
+            Err(GridSpecParseError::UnexpectedTwist),
+            "T30*,20".parse::<GridSpec>()
+        );
+    }
+}